@@ -13,6 +13,7 @@
 use std::sync::Arc;
 use crate::helpers::{check_dir, create_client_verbose, json_account, print_account, query_account_field};
 use crate::config::Config;
+use crate::call::format_json_result;
 use serde_json::{json, Value};
 use ton_client::error::ClientError;
 use ton_client::net::{ParamsOfQueryCollection, query_collection, ResultOfSubscription, ParamsOfSubscribeCollection};
@@ -190,7 +191,7 @@ pub async fn get_account(config: &Config, addresses: Vec<String>, dumptvc: Optio
             }
         }
         if config.is_json {
-            println!("{:#}", json_res);
+            println!("{}", format_json_result(&json_res, config));
         }
     } else if config.is_json {
         println!("{{\n}}");
@@ -12,33 +12,175 @@
  */
 use crate::config::Config;
 use crate::convert;
-use crate::helpers::{TonClient, now_ms, create_client_verbose, load_abi, query_account_field,
-    create_client, load_ton_abi, get_blockchain_config};
+use crate::output;
+use crate::helpers::{TonClient, now, now_ms, create_client_verbose, load_abi, query_account_field,
+    create_client, create_client_local, load_ton_abi, parse_ton_abi, get_blockchain_config, check_abi_function,
+    ask_confirmation, load_ton_address, load_params, load_state_libs, add_account_libs, load_abi_str, ClientPool};
 
-use ton_client::abi::{encode_message, decode_message, ParamsOfDecodeMessage, ParamsOfEncodeMessage,
-                      Abi};
+use ton_client::abi::{encode_message, encode_internal_message, encode_message_body, decode_message, MessageBodyType,
+                      ParamsOfDecodeMessage, ParamsOfEncodeMessage, ParamsOfEncodeInternalMessage,
+                      ParamsOfEncodeMessageBody, Abi, CallSet, DecodedMessageBody, FunctionHeader};
+use ton_client::boc::{get_boc_hash, ParamsOfGetBocHash};
 use ton_client::processing::{
+    DecodedOutput,
     ParamsOfSendMessage,
     ParamsOfWaitForTransaction,
     ParamsOfProcessMessage,
     ProcessingEvent,
+    ResultOfProcessMessage,
     wait_for_transaction,
     send_message,
 };
 use ton_client::tvm::{
     run_executor,
+    run_tvm,
     ParamsOfRunExecutor,
+    ParamsOfRunTvm,
+    ResultOfRunExecutor,
     AccountForExecutor
 };
-use ton_block::{Account, Serializable};
+use ton_block::{Account, AccountStatus, Deserializable, ExternalInboundMessageHeader, Message, MsgAddressExt,
+    Serializable};
+use ton_types::{BuilderData, Cell, SliceData, ed25519_sign_with_secret};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::io::{BufRead, IsTerminal, Write};
 use std::str::FromStr;
-use serde_json::{json, Value};
-use ton_abi::ParamType;
+use std::time::{Duration, Instant};
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde::ser::SerializeMap;
+use serde_json::{json, Map, Value};
+use ton_abi::{Param, ParamType};
+use ton_client::encoding::decode_abi_bigint;
+use num_bigint::{BigInt, Sign};
+use num_traits::One;
 use ton_client::error::ClientError;
+use ton_client::net::{ParamsOfQueryCollection, ParamsOfWaitForCollection};
+use crate::crypto::load_keypair;
 use crate::debug::{init_debug_logger, debug_error, DebugParams};
-use crate::message::{EncodedMessage, prepare_message_params, print_encoded_message, unpack_message};
+use crate::decode::load_body_input;
+use crate::message::{EncodedMessage, add_signatures, prepare_message_params, print_encoded_message, unpack_message};
 
-async fn decode_call_parameters(ton: TonClient, msg: &EncodedMessage, abi: Abi) -> Result<(String, String), String> {
+/// Result of the contract call: decoded output together with the transaction details.
+#[derive(Serialize, Clone, Default)]
+pub struct CallResult {
+    pub output: Value,
+    pub transaction_id: Option<String>,
+    pub exit_code: Option<i32>,
+    /// Argument of the exception thrown by the contract, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_arg: Option<i64>,
+    pub fees: Option<Value>,
+    /// ABI events emitted by the contract during the transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<Value>>,
+    /// Internal out messages decoded with the `--out-abi` ABIs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_messages: Option<Vec<Value>>,
+    /// BOCs of the out messages of the transaction.
+    #[serde(skip)]
+    pub out_message_bocs: Vec<String>,
+    /// Undecoded transaction, printed instead of the output with `--raw`.
+    #[serde(skip)]
+    pub transaction: Option<Value>,
+}
+
+impl CallResult {
+    fn from_processing(result: ResultOfProcessMessage) -> Self {
+        let (output, events) = split_decoded_output(result.decoded);
+        Self {
+            transaction_id: result.transaction["id"].as_str().map(|id| id.to_owned()),
+            exit_code: result.transaction["compute"]["exit_code"].as_i64().map(|code| code as i32),
+            exit_arg: compute_exit_arg(&result.transaction),
+            fees: serde_json::to_value(&result.fees).ok(),
+            output,
+            events,
+            out_messages: None,
+            out_message_bocs: result.out_messages,
+            transaction: Some(result.transaction),
+        }
+    }
+    fn from_emulation(result: ResultOfRunExecutor) -> Self {
+        let (output, events) = split_decoded_output(result.decoded);
+        Self {
+            transaction_id: None,
+            exit_code: result.transaction["compute"]["exit_code"].as_i64().map(|code| code as i32),
+            exit_arg: compute_exit_arg(&result.transaction),
+            fees: serde_json::to_value(&result.fees).ok(),
+            output,
+            events,
+            out_messages: None,
+            out_message_bocs: result.out_messages,
+            transaction: Some(result.transaction),
+        }
+    }
+}
+
+/// Returns the exit argument from the compute phase of the transaction.
+fn compute_exit_arg(transaction: &Value) -> Option<i64> {
+    let exit_arg = &transaction["compute"]["exit_arg"];
+    exit_arg.as_i64().or_else(|| exit_arg.as_str()?.parse().ok())
+}
+
+/// Adds the exit argument of the failed compute phase to the error message.
+fn add_exit_arg(e: &mut ClientError) {
+    let exit_arg = &e.data["exit_arg"];
+    let exit_arg = match exit_arg.as_i64().or_else(|| exit_arg.as_str()?.parse().ok()) {
+        Some(exit_arg) => exit_arg,
+        None => return,
+    };
+    if !e.message.contains("exit arg") {
+        e.message = format!("{}, exit argument: {}", e.message.trim_end_matches('.'), exit_arg);
+    }
+}
+
+/// Exit code thrown by the replay protection of Solidity contracts when the message signature
+/// doesn't match the public key of the contract.
+const INVALID_SIGNATURE_EXIT_CODE: i64 = 40;
+
+// replaces the opaque signature check error with an explanation of what is wrong with the keys.
+fn add_signature_hint(e: &mut ClientError, signed: bool) {
+    if e.data["exit_code"].as_i64() != Some(INVALID_SIGNATURE_EXIT_CODE) {
+        return;
+    }
+    let hint = if signed {
+        "the provided signing key does not match the contract's required public key, check the keys passed with --sign"
+    } else {
+        "the contract requires a signed message, pass the keys with --sign"
+    };
+    if !e.message.starts_with(hint) {
+        e.message = format!("{} ({})", hint, e.message);
+    }
+}
+
+fn decoded_event(decoded: &DecodedMessageBody) -> Option<Value> {
+    match decoded.body_type {
+        MessageBodyType::Event => Some(json!({
+            "name": decoded.name,
+            "value": decoded.value.clone().unwrap_or(json!({})),
+        })),
+        _ => None,
+    }
+}
+
+/// Returns the decoded function output and ABI events found among the out messages.
+/// Events are `None` if there are none, so the field is omitted from the result.
+fn split_decoded_output(decoded: Option<DecodedOutput>) -> (Value, Option<Vec<Value>>) {
+    let decoded = match decoded {
+        Some(decoded) => decoded,
+        None => return (json!({}), None),
+    };
+    let events = decoded.out_messages.iter()
+        .filter_map(|msg| msg.as_ref().and_then(decoded_event))
+        .collect::<Vec<_>>();
+    (
+        decoded.output.unwrap_or(json!({})),
+        if events.is_empty() { None } else { Some(events) },
+    )
+}
+
+async fn decode_call_parameters(ton: TonClient, msg: &EncodedMessage, abi: Abi) -> Result<(MessageBodyType, String, String), String> {
     let result = decode_message(
         ton,
         ParamsOfDecodeMessage {
@@ -50,135 +192,660 @@ async fn decode_call_parameters(ton: TonClient, msg: &EncodedMessage, abi: Abi)
     .map_err(|e| format!("couldn't decode message: {}", e))?;
 
     Ok((
+        result.body_type,
         result.name,
         format!("{:#}", result.value.unwrap_or(json!({})))
     ))
 }
 
-fn parse_integer_param(value: &str) -> Result<String, String> {
-    let value = value.trim_matches('\"');
+/// Decodes method name and parameters of the message without connecting to the network.
+pub async fn decode_call_parameters_offline(msg: &EncodedMessage, abi: Abi) -> Result<(String, String), String> {
+    decode_call_parameters_with_abis(msg, &[abi]).await
+        .map(|(_, _, method, params)| (method, params))
+}
 
-    if value.ends_with('T') {
-        convert::convert_token(value.trim_end_matches('T'))
-    } else {
-        Ok(value.to_owned())
+/// Tries to decode the message with each of the candidate ABIs without connecting to the network.
+/// Returns index of the first matched ABI with the decoded body type, method (or event) name and parameters.
+pub async fn decode_call_parameters_with_abis(
+    msg: &EncodedMessage,
+    abis: &[Abi],
+) -> Result<(usize, MessageBodyType, String, String), String> {
+    let ton = create_client_local()?;
+    let mut last_error = None;
+    for (index, abi) in abis.iter().enumerate() {
+        match decode_call_parameters(ton.clone(), msg, abi.clone()).await {
+            Ok((body_type, method, params)) => return Ok((index, body_type, method, params)),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    match (abis.len(), last_error) {
+        (1, Some(e)) => Err(e),
+        _ => Err("couldn't decode message: none of the specified ABIs matches the message".to_string()),
+    }
+}
+
+pub async fn load_out_abis(config: &Config) -> Result<Vec<Abi>, String> {
+    let mut abis = vec![];
+    for abi_path in &config.out_abis {
+        abis.push(load_abi(abi_path, config).await?);
+    }
+    Ok(abis)
+}
+
+/// Decodes internal out messages with the first matching ABI. Messages which are not matched by any
+/// of the ABIs are reported with their raw BOC.
+pub async fn decode_out_messages(bocs: &[String], abis: &[Abi]) -> Result<Vec<Value>, String> {
+    let mut out_messages = vec![];
+    for boc in bocs {
+        let bytes = base64::decode(boc)
+            .map_err(|e| format!("failed to decode outbound message: {}", e))?;
+        let msg = Message::construct_from_bytes(&bytes)
+            .map_err(|e| format!("failed to parse outbound message: {}", e))?;
+        if !msg.is_internal() {
+            continue;
+        }
+        let mut out_msg = json!({
+            "dst": msg.dst().map(|dst| dst.to_string()),
+            "value": msg.get_value().map(|value| value.grams.as_u128().to_string()),
+        });
+        match decode_call_parameters_with_abis(&encoded_message_from_boc(&bytes)?, abis).await {
+            Ok((_, _, function, params)) => {
+                out_msg["function"] = json!(function);
+                out_msg["body"] = serde_json::from_str(&params).unwrap_or(Value::String(params));
+            },
+            Err(_) => out_msg["boc"] = json!(boc),
+        }
+        out_messages.push(out_msg);
+    }
+    Ok(out_messages)
+}
+
+/// Token units accepted as integer parameter suffixes and their number of decimals in nanotokens.
+const TOKEN_UNITS: [(&str, usize); 11] = [
+    ("nano", 0), ("nanoever", 0), ("never", 0),
+    ("micro", 3), ("microever", 3), ("uever", 3),
+    ("milli", 6), ("milliever", 6), ("mever", 6),
+    ("ever", 9), ("t", 9),
+];
+
+pub fn parse_integer_param(value: &str) -> Result<String, String> {
+    let value = value.trim().trim_matches('\"');
+    if value.starts_with("0x") || value.starts_with("-0x") {
+        return Ok(value.to_owned());
+    }
+    let unit_start = value.trim_end_matches(|c: char| c.is_ascii_alphabetic()).len();
+    let (amount, unit) = value.split_at(unit_start);
+    if unit.is_empty() {
+        return Ok(value.to_owned());
+    }
+    let decimals = TOKEN_UNITS.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+        .map(|(_, decimals)| *decimals)
+        .ok_or_else(|| {
+            let units: Vec<&str> = TOKEN_UNITS.iter().map(|(name, _)| *name).collect();
+            format!("unknown unit \"{}\" in \"{}\". Valid units: {} (case-insensitive)", unit, value, units.join(", "))
+        })?;
+    convert::convert_units(amount, decimals)
+}
+
+/// Parses the integer and checks that it fits into the ABI type. The value is returned
+/// in decimal form.
+fn parse_integer_value(kind: &ParamType, value: &str) -> Result<String, String> {
+    let value = parse_integer_param(value)?;
+    let number = decode_abi_bigint(&value)
+        .map_err(|e| format!("invalid integer \"{}\": {}", value, e))?;
+    let fits = match kind {
+        ParamType::Uint(bits) => number.sign() != Sign::Minus && number.bits() <= *bits as u64,
+        ParamType::Int(bits) => {
+            let bound = BigInt::one() << (bits - 1);
+            number >= -bound.clone() && number < bound
+        },
+        _ => true,
+    };
+    if !fits {
+        return Err(format!("value {} is out of range of type {}", value, kind.type_signature()));
+    }
+    Ok(number.to_string())
+}
+
+/// Integer values of enums by their qualified names, e.g. `Status.Active` -> `2`.
+type EnumValues = HashMap<String, String>;
+
+/// Adds to `values` the enums from json object in form `{"Status": {"Active": 2, ...}, ...}`.
+fn add_enum_values(values: &mut EnumValues, enums: &Value) -> Result<(), String> {
+    let enums = enums.as_object().ok_or("enums must be a json object".to_string())?;
+    for (name, variants) in enums {
+        let variants = variants.as_object()
+            .ok_or(format!("variants of enum \"{}\" must be a json object", name))?;
+        for (variant, value) in variants {
+            let value = match value {
+                Value::Number(n) => n.to_string(),
+                Value::String(s) => s.clone(),
+                _ => return Err(format!("value of \"{}.{}\" must be an integer", name, variant)),
+            };
+            values.insert(format!("{}.{}", name, variant), value);
+        }
+    }
+    Ok(())
+}
+
+/// Loads enums declared in the ABI (non-standard `enums` section) and in the `--enums` file.
+/// Values from the file take precedence.
+async fn load_enum_values(abi_path: &str, config: &Config) -> Result<EnumValues, String> {
+    let mut values = EnumValues::new();
+    let abi: Value = serde_json::from_str(&load_abi_str(abi_path, config).await?)
+        .map_err(|e| format!("failed to parse ABI: {}", e))?;
+    if let Some(enums) = abi.get("enums") {
+        add_enum_values(&mut values, enums)?;
+    }
+    if let Some(path) = &config.enums {
+        let enums = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read enums file: {}", e))?;
+        let enums: Value = serde_json::from_str(&enums)
+            .map_err(|e| format!("failed to parse enums file: {}", e))?;
+        add_enum_values(&mut values, &enums)?;
+    }
+    Ok(values)
+}
+
+/// Replaces the enum name with its integer value, other values are left as is.
+fn resolve_enum<'a>(value: &'a str, enums: &'a EnumValues) -> &'a str {
+    enums.get(value.trim().trim_matches('\"')).map(|v| v.as_str()).unwrap_or(value)
+}
+
+fn parse_param_value(kind: &ParamType, value: String, enums: &EnumValues) -> Result<Value, String> {
+    let value = match kind {
+        ParamType::Uint(_) | ParamType::Int(_) => {
+            json!(parse_integer_value(kind, resolve_enum(&value, enums))?)
+        },
+        ParamType::Array(ref x) => {
+            let mut result_vec: Vec<String> = vec![];
+            for i in value.split(|c| c == ',' || c == '[' || c == ']') {
+                if !i.is_empty() {
+                    result_vec.push(match **x {
+                        ParamType::Uint(_) | ParamType::Int(_) => parse_integer_value(x, resolve_enum(i, enums))?,
+                        _ => i.trim().trim_matches('\"').to_owned(),
+                    })
+                }
+            }
+            json!(result_vec)
+        },
+        ParamType::Tuple(_) => {
+            serde_json::from_str(&value)
+                .map_err(|e| format!("tuple value must be a json object: {}", e))?
+        },
+        _ => {
+            json!(value)
+        }
+    };
+    Ok(value)
+}
+
+fn find_param_value(params_vec: &[&str], name: &str, kind: &ParamType) -> Result<Option<String>, String> {
+    let mut iter = params_vec.iter();
+    if iter.find(|x| x.starts_with('-') && (x.trim_start_matches('-') == name)).is_none() {
+        return Ok(None);
+    }
+    let value = iter.next()
+        .ok_or(format!(r#"argument "{}" of type "{}" has no value"#, name, kind))?
+        .to_string();
+    Ok(Some(value))
+}
+
+/// Parameter value built from the command line arguments. Fields are kept in the ABI
+/// declaration order and serialized in this order, so the same call always gives the same json.
+enum ParamValue {
+    Value(Value),
+    Fields(Vec<(String, ParamValue)>),
+}
+
+impl Serialize for ParamValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ParamValue::Value(value) => value.serialize(serializer),
+            ParamValue::Fields(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, value) in fields {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            },
+        }
+    }
+}
+
+/// Asks the user for the value of the missing argument. Returns `None` on the end of input.
+fn prompt_param_value(input: &mut dyn BufRead, name: &str, kind: &ParamType) -> Result<Option<String>, String> {
+    eprint!("{} ({}): ", name, kind);
+    std::io::stderr().flush().map_err(|e| format!("failed to flush stderr: {}", e))?;
+    let mut value = String::new();
+    let len = input.read_line(&mut value)
+        .map_err(|e| format!("failed to read argument \"{}\": {}", name, e))?;
+    Ok(if len == 0 { None } else { Some(value.trim().to_owned()) })
+}
+
+/// Builds parameter value from `-name value` pair. Tuple fields can be specified
+/// separately with dotted names: `-name.field value`. Missing values are asked from
+/// `input` if it is specified.
+fn build_param_value(
+    params_vec: &[&str],
+    name: &str,
+    kind: &ParamType,
+    enums: &EnumValues,
+    input: &mut Option<&mut dyn BufRead>,
+    known_names: &mut Vec<String>,
+) -> Result<ParamValue, String> {
+    known_names.push(name.to_owned());
+    if let Some(value) = find_param_value(params_vec, name, kind)? {
+        return parse_param_value(kind, value, enums).map(ParamValue::Value);
+    }
+    if let ParamType::Tuple(components) = kind {
+        let mut fields = vec![];
+        for component in components {
+            let path = format!("{}.{}", name, component.name);
+            fields.push((
+                component.name.clone(),
+                build_param_value(params_vec, &path, &component.kind, enums, input, known_names)?,
+            ));
+        }
+        return Ok(ParamValue::Fields(fields));
+    }
+    if let Some(input) = input {
+        if let Some(value) = prompt_param_value(*input, name, kind)? {
+            return parse_param_value(kind, value, enums).map(ParamValue::Value);
+        }
+    }
+    Err(format!(r#"argument "{}" of type "{}" not found"#, name, kind))
+}
+
+fn build_params_json(
+    params_vec: &[&str],
+    inputs: &[Param],
+    enums: &EnumValues,
+    mut input: Option<&mut dyn BufRead>,
+) -> Result<String, String> {
+    let mut params = vec![];
+    let mut known_names = vec![];
+    for param in inputs {
+        params.push((
+            param.name.clone(),
+            build_param_value(params_vec, &param.name, &param.kind, enums, &mut input, &mut known_names)?,
+        ));
     }
+    for param in params_vec.iter() {
+        let name = param.trim_start_matches('-');
+        let is_name = param.starts_with('-') && name.starts_with(|c: char| c.is_alphabetic() || c == '_');
+        if is_name && name.contains('.') && !known_names.iter().any(|known| known == name) {
+            return Err(format!(r#"argument "{}" doesn't correspond to any field of the function parameters"#, name));
+        }
+    }
+
+    serde_json::to_string(&ParamValue::Fields(params)).map_err(|e| format!("{}", e))
 }
 
 async fn build_json_from_params(params_vec: Vec<&str>, abi_path: &str, method: &str, config: &Config) -> Result<String, String> {
     let abi_obj = load_ton_abi(abi_path, config).await?;
     let functions = abi_obj.functions();
 
+    check_abi_function(&abi_obj, method)?;
     let func_obj = functions.get(method).ok_or("failed to load function from abi")?;
     let inputs = func_obj.input_params();
+    let enums = load_enum_values(abi_path, config).await?;
 
-    let mut params_json = json!({ });
-    for input in inputs {
-        let mut iter = params_vec.iter();
-        let _param = iter.find(|x| x.starts_with('-') && (x.trim_start_matches('-') == input.name))
-            .ok_or(format!(r#"argument "{}" of type "{}" not found"#, input.name, input.kind))?;
+    // missing arguments are asked only in the interactive terminal session and never
+    // in json mode, so the prompts can't be mixed with the output
+    let interactive = config.interactive && !config.is_json && std::io::stdin().is_terminal();
+    if interactive {
+        build_params_json(&params_vec, inputs, &enums, Some(&mut std::io::stdin().lock()))
+    } else {
+        build_params_json(&params_vec, inputs, &enums, None)
+    }
+}
 
-        let value = iter.next()
-            .ok_or(format!(r#"argument "{}" of type "{}" has no value"#, input.name, input.kind))?
-            .to_string();
+fn dummy_account_boc(addr: &str) -> Result<String, String> {
+    let addr = ton_block::MsgAddressInt::from_str(addr)
+        .map_err(|e| format!("couldn't decode address: {}", e))?;
+    Ok(base64::encode(
+        &ton_types::write_boc(
+            &Account::with_address(addr)
+                .serialize()
+                .map_err(|e| format!("couldn't create dummy account for deploy emulation: {}", e))?
+        ).map_err(|e| format!("failed to serialize account cell: {}", e))?
+    ))
+}
 
-        let value = match input.kind {
-            ParamType::Uint(_) | ParamType::Int(_) => {
-                json!(parse_integer_param(&value)?)
-            },
-            ParamType::Array(ref _x) => {
-                let mut result_vec: Vec<String> = vec![];
-                for i in value.split(|c| c == ',' || c == '[' || c == ']') {
-                    if !i.is_empty() {
-                        result_vec.push(parse_integer_param(i)?)
-                    }
-                }
-                json!(result_vec)
-            },
-            _ => {
-                json!(value)
-            }
-        };
-        params_json[input.name.clone()] = value;
-    }
+fn message_has_state_init(msg: &str) -> Result<bool, String> {
+    let message = Message::construct_from_base64(msg)
+        .map_err(|e| format!("failed to construct message from boc: {}", e))?;
+    Ok(message.state_init().is_some())
+}
 
-    serde_json::to_string(&params_json).map_err(|e| format!("{}", e))
+async fn run_local_emulation(
+    ton: TonClient,
+    addr: &str,
+    msg: String,
+    abi: Option<Abi>,
+    is_fee: bool,
+    libs: Option<&str>,
+) -> Result<ResultOfRunExecutor, String> {
+    run_local_emulation_with_balance(ton, addr, msg, abi, is_fee, is_fee, libs).await
 }
 
-pub async fn emulate_locally(
+async fn run_local_emulation_with_balance(
     ton: TonClient,
     addr: &str,
     msg: String,
+    abi: Option<Abi>,
     is_fee: bool,
-) -> Result<(), String> {
-    let state: String;
-    let state_boc = query_account_field(ton.clone(), addr, "boc").await;
-    if state_boc.is_err() {
-        if is_fee {
-            let addr = ton_block::MsgAddressInt::from_str(addr)
-                .map_err(|e| format!("couldn't decode address: {}", e))?;
-            state = base64::encode(
-                &ton_types::write_boc(
-                    &Account::with_address(addr)
-                        .serialize()
-                        .map_err(|e| format!("couldn't create dummy account for deploy emulation: {}", e))?
-                ).map_err(|e| format!("failed to serialize account cell: {}", e))?
-            );
-        } else {
-            return Err(state_boc.err().unwrap());
-        }
-    } else {
-        state = state_boc.unwrap();
-    }
-    let res = run_executor(
+    unlimited_balance: bool,
+    libs: Option<&str>,
+) -> Result<ResultOfRunExecutor, String> {
+    let state = match query_account_field(ton.clone(), addr, "boc").await {
+        Ok(boc) => {
+            let account = Account::construct_from_base64(&boc)
+                .map_err(|e| format!("failed to load account from the boc: {}", e))?;
+            match account.status() {
+                AccountStatus::AccStateUninit if !message_has_state_init(&msg)? => {
+                    if !is_fee {
+                        return Err(format!("account {} is not initialized. Deploy the contract or attach its state init to the message before calling it.", addr));
+                    }
+                    dummy_account_boc(addr)?
+                },
+                AccountStatus::AccStateFrozen => {
+                    if !is_fee {
+                        return Err(format!("account {} is frozen. Top up its balance to pay the storage debt and unfreeze it with a message carrying its state init.", addr));
+                    }
+                    dummy_account_boc(addr)?
+                },
+                _ => boc,
+            }
+        },
+        Err(e) => {
+            if !is_fee {
+                return Err(e);
+            }
+            dummy_account_boc(addr)?
+        },
+    };
+    let state = match libs {
+        Some(path) => add_account_libs(&state, &load_state_libs(path)?)?,
+        None => state,
+    };
+    execute_message(ton, state, msg, abi, unlimited_balance).await
+}
+
+/// Executes the message on the account state locally, nothing is sent to the network.
+pub async fn execute_message(
+    ton: TonClient,
+    account_boc: String,
+    msg: String,
+    abi: Option<Abi>,
+    unlimited_balance: bool,
+) -> Result<ResultOfRunExecutor, String> {
+    run_executor(
         ton.clone(),
         ParamsOfRunExecutor {
-            message: msg.clone(),
+            message: msg,
             account: AccountForExecutor::Account {
-                boc: state,
-                unlimited_balance: if is_fee {
+                boc: account_boc,
+                unlimited_balance: if unlimited_balance {
                     Some(true)
                 } else {
                     None
                 },
             },
+            abi,
             ..Default::default()
         },
     )
-    .await;
+    .await
+    .map_err(|e| format!("{:#}", e))
+}
 
-    if res.is_err() {
-        return Err(format!("{:#}", res.err().unwrap()));
-    }
+pub async fn emulate_locally(
+    ton: TonClient,
+    addr: &str,
+    msg: String,
+    abi: Option<Abi>,
+    is_fee: bool,
+    config: &Config,
+) -> Result<Option<Value>, String> {
+    let is_json = config.is_json;
     if is_fee {
-        let fees = res.unwrap().fees;
-        println!("{{");
-        println!("  \"in_msg_fwd_fee\": \"{}\",", fees.in_msg_fwd_fee);
-        println!("  \"storage_fee\": \"{}\",", fees.storage_fee);
-        println!("  \"gas_fee\": \"{}\",", fees.gas_fee);
-        println!("  \"out_msgs_fwd_fee\": \"{}\",", fees.out_msgs_fwd_fee);
-        println!("  \"total_account_fees\": \"{}\",", fees.total_account_fees);
-        println!("  \"total_output\": \"{}\"", fees.total_output);
-        println!("}}");
+        let result = estimate_fees(ton, addr, msg, abi, config).await?;
+        println!("{}", format_json_result(&result, config));
+        return Ok(None);
+    }
+    let res = run_local_emulation(ton.clone(), addr, msg.clone(), abi.clone(), is_fee, config.libs.as_deref()).await?;
+    let (gas_used, vm_steps) = compute_phase_stats(&res.transaction);
+    let output = res.decoded.and_then(|d| d.output);
+    if !is_json {
+        println!("{}", output::success("Local run succeeded."));
+        if let (Some(gas_used), Some(vm_steps)) = (gas_used, vm_steps) {
+            println!("Gas used: {}, VM steps: {}", gas_used, vm_steps);
+        }
+        if let Some(output) = &output {
+            println!("Local run result: {}", format_json_result(output, config));
+        } else if !res.out_messages.is_empty() {
+            println!("Local run out messages: {:?}", res.out_messages);
+        }
+        println!("Executing onchain.");
+    }
+    Ok(output)
+}
+
+/// Emulates the message for `--dry-run` and prints its fees and output instead of sending it.
+/// For the deploy message the missing account is emulated with unlimited balance.
+pub async fn dry_run_message(
+    ton: TonClient,
+    addr: &str,
+    msg: String,
+    abi: Option<Abi>,
+    is_deploy: bool,
+    config: &Config,
+) -> Result<(), String> {
+    let message_id = message_id(ton.clone(), &msg)?;
+    let result = run_local_emulation(ton, addr, msg, abi, is_deploy, config.libs.as_deref()).await?;
+    let result = CallResult::from_emulation(result);
+    if !config.is_json {
+        println!("MessageId: {}", message_id);
+        println!("{}", output::success("Dry run succeeded. Message was not sent."));
+        if let Some(fees) = &result.fees {
+            println!("Fees: {}", format_json_result(fees, config));
+        }
+        if !result.output.is_null() {
+            println!("Result: {}", format_json_result(&result.output, config));
+        }
     } else {
-        println!("Local run succeeded. Executing onchain."); // TODO: check is_json
+        let result = json!({
+            "message_id": message_id,
+            "fees": result.fees,
+            "output": result.output,
+        });
+        println!("{}", format_json_result(&result, config));
+    }
+    Ok(())
+}
+
+/// Emulates the message execution and returns the fees of the transaction.
+async fn estimate_fees(
+    ton: TonClient,
+    addr: &str,
+    msg: String,
+    abi: Option<Abi>,
+    config: &Config,
+) -> Result<Value, String> {
+    let res = run_local_emulation(ton.clone(), addr, msg.clone(), abi.clone(), true, config.libs.as_deref()).await?;
+    let fees = res.fees;
+    let mut result = json!({
+        "in_msg_fwd_fee": fees.in_msg_fwd_fee.to_string(),
+        "storage_fee": fees.storage_fee.to_string(),
+        "gas_fee": fees.gas_fee.to_string(),
+        "out_msgs_fwd_fee": fees.out_msgs_fwd_fee.to_string(),
+        "total_account_fees": fees.total_account_fees.to_string(),
+        "total_output": fees.total_output.to_string(),
+    });
+    let (gas_used, vm_steps) = compute_phase_stats(&res.transaction);
+    result["gas_used"] = json!(gas_used);
+    result["vm_steps"] = json!(vm_steps);
+    if config.real_balance {
+        match run_local_emulation_with_balance(ton, addr, msg, abi, true, false, config.libs.as_deref()).await {
+            Ok(_) => {
+                result["sufficient_funds"] = json!(true);
+            },
+            Err(e) if is_insufficient_funds_error(&e) => {
+                result["sufficient_funds"] = json!(false);
+                result["error"] = serde_json::from_str(&e).unwrap_or(Value::String(e));
+            },
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(result)
+}
+
+/// Aborts the call if the estimated fee exceeds `--max-fee`.
+async fn check_max_fee(
+    ton: TonClient,
+    addr: &str,
+    msg: String,
+    abi: Abi,
+    max_fee: u64,
+    config: &Config,
+) -> Result<(), String> {
+    let fees = estimate_fees(ton, addr, msg, Some(abi), config).await?;
+    let fee = fees["total_account_fees"].as_str()
+        .and_then(|fee| fee.parse::<u64>().ok())
+        .ok_or("failed to estimate the fee of the call".to_string())?;
+    if fee > max_fee {
+        return Err(format!(
+            "estimated fee {} ({} tokens) exceeds --max-fee {} ({} tokens), the message is not sent",
+            fee, convert::convert_u64_to_tokens(fee), max_fee, convert::convert_u64_to_tokens(max_fee),
+        ));
+    }
+    if !config.is_json {
+        println!("Estimated fee: {}", fee);
     }
     Ok(())
 }
 
+/// Collects the differences between the output of the local run and the onchain output
+/// as `path: emulated -> onchain` lines.
+fn output_drift(path: &str, emulated: &Value, onchain: &Value, diff: &mut Vec<String>) {
+    match (emulated, onchain) {
+        (Value::Object(emulated), Value::Object(onchain)) => {
+            let mut keys: Vec<&String> = emulated.keys().chain(onchain.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                output_drift(
+                    &format!("{}.{}", path, key),
+                    emulated.get(key).unwrap_or(&Value::Null),
+                    onchain.get(key).unwrap_or(&Value::Null),
+                    diff,
+                );
+            }
+        },
+        (Value::Array(emulated), Value::Array(onchain)) if emulated.len() == onchain.len() => {
+            for (i, (emulated, onchain)) in emulated.iter().zip(onchain).enumerate() {
+                output_drift(&format!("{}[{}]", path, i), emulated, onchain, diff);
+            }
+        },
+        _ if emulated != onchain => {
+            diff.push(format!("{}: {} -> {}", path, emulated, onchain));
+        },
+        _ => {},
+    }
+}
+
+/// Warns if the onchain result differs from the output of the local run, e.g. because
+/// the account state was changed between the emulation and the execution.
+fn warn_drift(config: &Config, emulated: Option<&Value>, result: &CallResult) {
+    let warning = match emulated {
+        Some(emulated) if config.warn_drift => match drift_warning(emulated, result) {
+            Some(warning) => warning,
+            None => return,
+        },
+        _ => return,
+    };
+    // stdout carries the result json, so the warning goes to stderr in json mode
+    if config.is_json {
+        eprintln!("{}", warning);
+    } else {
+        println!("{}", output::warning(&warning));
+    }
+}
+
+/// Describes the difference between the output of the local run and the decoded onchain output.
+/// The result without a transaction (e.g. of the async call which was not awaited) carries
+/// the sent message instead of the output, so it is not compared.
+fn drift_warning(emulated: &Value, result: &CallResult) -> Option<String> {
+    if result.transaction.is_none() || result.output.is_null() {
+        return None;
+    }
+    let mut diff = vec![];
+    output_drift("output", emulated, &result.output, &mut diff);
+    if diff.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Onchain result differs from the local run (local -> onchain):\n  {}",
+        diff.join("\n  ")
+    ))
+}
+
+/// Returns gas used and the number of VM steps from the compute phase of the emulated transaction.
+fn compute_phase_stats(transaction: &Value) -> (Option<u64>, Option<u64>) {
+    let number = |value: &Value| value.as_u64().or_else(|| {
+        let value = value.as_str()?;
+        match value.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => value.parse().ok(),
+        }
+    });
+    let compute = &transaction["compute"];
+    (number(&compute["gas_used"]), number(&compute["vm_steps"]))
+}
+
+/// Checks whether the executor error is caused by the lack of funds on the account:
+/// the balance is too low to pay for the compute phase or for the outbound messages.
+fn is_insufficient_funds_error(err: &str) -> bool {
+    const LOW_BALANCE: i64 = 407;
+    const ACTION_PHASE_FAILED: i64 = 405;
+    // action phase result codes for "not enough funds" to create or send a message
+    const NOT_ENOUGH_FUNDS: [i64; 2] = [37, 38];
+    match serde_json::from_str::<Value>(err) {
+        Ok(e) => match e["code"].as_i64() {
+            Some(LOW_BALANCE) => true,
+            Some(ACTION_PHASE_FAILED) => e["data"]["result_code"].as_i64()
+                .map(|code| NOT_ENOUGH_FUNDS.contains(&code))
+                .unwrap_or(false),
+            _ => false,
+        },
+        Err(_) => false,
+    }
+}
+
 pub async fn send_message_and_wait(
     ton: TonClient,
     abi: Option<Abi>,
     msg: String,
     config: &Config,
-) -> Result<Value, String> {
+) -> Result<CallResult, String> {
 
     if !config.is_json {
         println!("Processing... ");
     }
+    // in async mode the transaction is awaited only on demand
+    let wait = !config.async_call || config.await_result;
+    let since_lt = match config.since_lt {
+        Some(Some(lt)) => Some(lt),
+        Some(None) if wait => Some(destination_last_lt(ton.clone(), &msg).await),
+        _ => None,
+    };
     let callback = |_| {
         async move {}
     };
+    let message_id = message_id(ton.clone(), &msg)?;
     let result = send_message(
         ton.clone(),
         ParamsOfSendMessage {
@@ -191,41 +858,184 @@ pub async fn send_message_and_wait(
     ).await
         .map_err(|e| format!("{:#}", e))?;
 
-    if !config.async_call {
-        let result = wait_for_transaction(
+    if wait {
+        let wait_result = wait_for_transaction(
             ton.clone(),
             ParamsOfWaitForTransaction {
-                abi,
+                abi: abi.clone(),
                 message: msg.clone(),
                 shard_block_id: result.shard_block_id,
                 send_events: true,
                 ..Default::default()
             },
             callback,
-        ).await
-            .map_err(|e| format!("{:#}", e))?;
-        Ok(result.decoded.and_then(|d| d.output).unwrap_or(json!({})))
+        );
+        let result = match config.wait_timeout {
+            Some(secs) => tokio::time::timeout(std::time::Duration::from_secs(secs), wait_result).await
+                .map_err(|_| wait_timeout_error(secs, &message_id))?,
+            None => wait_result.await,
+        }.map_err(|e| format!("{:#}", e))?;
+        match since_lt {
+            // the found transaction was made before the message was sent, wait for the new one
+            Some(since_lt) if transaction_lt(&result.transaction) <= since_lt =>
+                wait_for_transaction_since(ton, abi, &message_id, since_lt, config).await,
+            _ => Ok(CallResult::from_processing(result)),
+        }
     } else {
-        Ok(json!({}))
+        Ok(CallResult {
+            output: json!({
+                "message_id": message_id,
+                "message": msg,
+                "shard_block_id": result.shard_block_id,
+            }),
+            ..Default::default()
+        })
+    }
+}
+
+/// Returns the id of the message returned from the async call: the hash of the message BOC.
+fn message_id(ton: TonClient, msg: &str) -> Result<String, String> {
+    get_boc_hash(ton, ParamsOfGetBocHash { boc: msg.to_owned() })
+        .map(|result| result.hash)
+        .map_err(|e| format!("failed to calculate message id: {}", e))
+}
+
+fn wait_timeout_error(secs: u64, message_id: &str) -> String {
+    format!(
+        "timeout of {} seconds elapsed while waiting for the transaction of message {}. The message may still be processed, check it later by its id.",
+        secs, message_id,
+    )
+}
+
+/// Returns the logical time of the last transaction of the message destination account,
+/// or 0 if it is unknown (e.g. the account is not deployed yet).
+async fn destination_last_lt(ton: TonClient, msg: &str) -> u64 {
+    let dst = Message::construct_from_base64(msg).ok()
+        .and_then(|msg| msg.dst_ref().map(|dst| dst.to_string()));
+    match dst {
+        Some(dst) => query_account_field(ton, &dst, "last_trans_lt").await
+            .map(|lt| parse_lt(&Value::String(lt)))
+            .unwrap_or(0),
+        None => 0,
+    }
+}
+
+fn transaction_lt(transaction: &Value) -> u64 {
+    parse_lt(&transaction["lt"])
+}
+
+/// Parses logical time returned by the SDK: hex string with `0x` prefix, decimal string or number.
+fn parse_lt(value: &Value) -> u64 {
+    match value {
+        Value::Number(n) => n.as_u64().unwrap_or(0),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).unwrap_or(0),
+            None => s.parse().unwrap_or(0),
+        },
+        _ => 0,
+    }
+}
+
+/// Waits for the transaction of the message with logical time greater than `since_lt`.
+async fn wait_for_transaction_since(
+    ton: TonClient,
+    abi: Option<Abi>,
+    message_id: &str,
+    since_lt: u64,
+    config: &Config,
+) -> Result<CallResult, String> {
+    let timeout = match config.wait_timeout {
+        Some(secs) => secs.checked_mul(1000).and_then(|ms| u32::try_from(ms).ok())
+            .ok_or(format!("wait timeout {} is too large, the maximum is {} seconds", secs, u32::MAX / 1000))?,
+        None => config.timeout,
+    };
+    let transaction = ton_client::net::wait_for_collection(
+        ton.clone(),
+        ParamsOfWaitForCollection {
+            collection: "transactions".to_owned(),
+            filter: Some(json!({
+                "in_msg": { "eq": message_id },
+                "lt": { "gt": format!("0x{:x}", since_lt) },
+            })),
+            result: "id lt aborted compute { exit_code exit_arg } out_messages { boc msg_type }".to_owned(),
+            timeout: Some(timeout),
+            ..Default::default()
+        },
+    ).await
+        .map_err(|e| match config.wait_timeout {
+            Some(secs) if e.code == ton_client::net::ErrorCode::WaitForTimeout as u32 =>
+                wait_timeout_error(secs, message_id),
+            _ => format!("failed to wait for the transaction: {}", e),
+        })?
+        .result;
+
+    let mut output = json!({});
+    let mut events = vec![];
+    let out_message_bocs: Vec<String> = transaction["out_messages"].as_array()
+        .map(|messages| messages.iter().filter_map(|m| m["boc"].as_str()).map(|boc| boc.to_owned()).collect())
+        .unwrap_or_default();
+    if let Some(abi) = abi {
+        for out_msg in &out_message_bocs {
+            let decoded = decode_message(
+                ton.clone(),
+                ParamsOfDecodeMessage { abi: abi.clone(), message: out_msg.to_owned(), ..Default::default() },
+            );
+            if let Ok(decoded) = decoded {
+                if matches!(decoded.body_type, MessageBodyType::Output) {
+                    output = decoded.value.clone().unwrap_or(json!({}));
+                }
+                events.extend(decoded_event(&decoded));
+            }
+        }
     }
+    Ok(CallResult {
+        transaction_id: transaction["id"].as_str().map(|id| id.to_owned()),
+        exit_code: transaction["compute"]["exit_code"].as_i64().map(|code| code as i32),
+        exit_arg: compute_exit_arg(&transaction),
+        fees: None,
+        output,
+        events: if events.is_empty() { None } else { Some(events) },
+        out_messages: None,
+        out_message_bocs,
+        transaction: Some(transaction),
+    })
 }
 
 pub async fn process_message(
     ton: TonClient,
     msg: ParamsOfEncodeMessage,
     config: &Config,
-) -> Result<Value, ClientError> {
+) -> Result<CallResult, ClientError> {
     let callback = |event| { async move {
-        if let ProcessingEvent::DidSend {
-            shard_block_id: _,
-            message_id,
-            message_dst: _,
-            message: _,
-        } = event {
-            println!("MessageId: {}", message_id)
-        }
-    }};
-    let res = if !config.is_json {
+        match event {
+            ProcessingEvent::WillFetchFirstBlock { .. } => {
+                println!("Fetching current shard block...");
+            },
+            ProcessingEvent::FetchFirstBlockFailed { error, .. } => {
+                println!("{}", output::warning(&format!("Failed to fetch current shard block: {}", error.message)));
+            },
+            ProcessingEvent::WillSend { .. } => {
+                println!("Sending message...");
+            },
+            ProcessingEvent::DidSend { message_id, .. } => {
+                println!("MessageId: {}", message_id)
+            },
+            ProcessingEvent::SendFailed { error, .. } => {
+                println!("{}", output::warning(&format!("Failed to send message: {}", error.message)));
+            },
+            ProcessingEvent::WillFetchNextBlock { .. } => {
+                println!("Waiting for the next shard block...");
+            },
+            ProcessingEvent::FetchNextBlockFailed { error, .. } => {
+                println!("{}", output::warning(&format!("Failed to fetch next shard block: {}", error.message)));
+            },
+            ProcessingEvent::MessageExpired { .. } => {
+                println!("{}", output::warning("Message expired."));
+            },
+            _ => {},
+        }
+    }};
+    let res = if !config.is_json {
         ton_client::processing::process_message(
             ton.clone(),
             ParamsOfProcessMessage {
@@ -247,7 +1057,7 @@ pub async fn process_message(
         ).await
     }?;
 
-    Ok(res.decoded.and_then(|d| d.output).unwrap_or(json!({})))
+    Ok(CallResult::from_processing(res))
 }
 
 pub async fn call_contract_with_result(
@@ -258,14 +1068,17 @@ pub async fn call_contract_with_result(
     params: &str,
     keys: Option<String>,
     is_fee: bool,
-) -> Result<Value, String> {
+    lifetime: Option<u32>,
+) -> Result<CallResult, String> {
     let ton = if config.debug_fail != "None".to_string() {
-        init_debug_logger(&format!("call_{}_{}.log", addr, method))?;
+        let trace_path = config.trace_output.clone()
+            .unwrap_or(format!("call_{}_{}.log", addr, method));
+        init_debug_logger(&trace_path)?;
         create_client(config)?
     } else {
         create_client_verbose(config)?
     };
-    call_contract_with_client(ton, config, addr, abi_path, method, params, keys, is_fee).await
+    call_contract_with_client(ton, config, addr, abi_path, method, params, keys, is_fee, lifetime).await
 }
 
 pub async fn call_contract_with_client(
@@ -277,58 +1090,208 @@ pub async fn call_contract_with_client(
     params: &str,
     keys: Option<String>,
     is_fee: bool,
-) -> Result<Value, String> {
+    lifetime: Option<u32>,
+) -> Result<CallResult, String> {
     let abi = load_abi(abi_path, config).await?;
+    let error_map = config.error_map.as_deref()
+        .map(load_error_map)
+        .transpose()?;
 
+    // the message saved for later submission is valid for its own ttl
+    let lifetime = match config.output_message {
+        Some(_) => config.message_ttl.or(lifetime),
+        None => lifetime,
+    };
+    let header = match (config.message_time, lifetime) {
+        (Some(time), lifetime) => Some(FunctionHeader {
+            time: Some(time),
+            expire: Some((time / 1000) as u32 + lifetime.unwrap_or(config.lifetime)),
+            ..Default::default()
+        }),
+        (None, Some(lifetime)) => Some(FunctionHeader {
+            expire: Some(now() + lifetime),
+            ..Default::default()
+        }),
+        (None, None) => None,
+    };
+    let header = match (header, &abi) {
+        (Some(header), Abi::Json(abi_str)) => filter_header(&*parse_ton_abi(abi_str)?, header),
+        (header, _) => header,
+    };
+    let header_expire = header.as_ref().and_then(|h| h.expire);
+    if let Some(expire) = header_expire {
+        if !config.is_json {
+            let expire_at = Local.timestamp_opt(expire as i64, 0).single()
+                .ok_or("failed to convert expiration time".to_string())?;
+            println!("Expire at: {}", expire_at.to_rfc2822());
+        }
+    }
+
+    let signed_params = if config.signers.len() > 1 {
+        Some(add_signatures(ton.clone(), &abi, method, params, &config.signers)?)
+    } else {
+        None
+    };
     let msg_params = prepare_message_params(
         addr,
         abi.clone(),
         method,
-        params,
-        None,
+        signed_params.as_deref().unwrap_or(params),
+        header,
         keys.clone(),
         None,
+        config.nonce,
     )?;
 
+    if let Some(getter) = &config.verify_key {
+        let keys = keys.as_deref()
+            .ok_or("signing keys are required to verify the key".to_string())?;
+        verify_signing_key(ton.clone(), addr, abi.clone(), getter, keys).await?;
+    }
+    if let (Some(nonce), Some(getter)) = (config.nonce, &config.nonce_getter) {
+        check_nonce(ton.clone(), addr, abi.clone(), getter, nonce).await?;
+    }
+
+    if let Some(src) = &config.emulate_src {
+        return emulate_internal_call(ton, config, addr, abi, msg_params.call_set, src).await;
+    }
+
+    let sends_message = !is_fee && !config.dry_run && config.output_message.is_none();
+    if sends_message {
+        if let Some(result) = check_once(ton.clone(), config, addr, method, params).await? {
+            return Ok(result);
+        }
+    }
+    if sends_message {
+        save_account_snapshot(ton.clone(), config, addr, "before.boc").await;
+    }
+
     let needs_encoded_msg = is_fee ||
+        config.dry_run ||
+        config.output_message.is_some() ||
         config.async_call ||
+        config.wait_timeout.is_some() ||
+        config.since_lt.is_some() ||
+        config.once.is_some() ||
         config.local_run ||
+        config.max_fee.is_some() ||
         config.debug_fail != "None".to_string();
 
+    let mut emulated_output = None;
     let message = if needs_encoded_msg {
         let msg = encode_message(ton.clone(), msg_params.clone()).await
             .map_err(|e| format!("failed to create inbound message: {}", e))?;
 
+        if let Some(path) = &config.output_message {
+            std::fs::write(path, &msg.message)
+                .map_err(|e| format!("cannot write message to file: {}", e))?;
+            if !config.is_json {
+                println!("MessageId: {}", msg.message_id);
+                println!("Message saved to file {}", path);
+            }
+            return Ok(CallResult {
+                output: json!({ "message_id": msg.message_id }),
+                ..Default::default()
+            });
+        }
+        if config.dry_run && !is_fee {
+            let result = run_local_emulation(
+                ton.clone(),
+                addr,
+                msg.message.clone(),
+                Some(abi.clone()),
+                false,
+                config.libs.as_deref(),
+            ).await?;
+            if !config.is_json {
+                println!("MessageId: {}", msg.message_id);
+                println!("{}", output::success("Dry run succeeded. Message was not sent."));
+            }
+            return Ok(CallResult::from_emulation(result));
+        }
+        if let (Some(max_fee), false) = (config.max_fee, is_fee) {
+            check_max_fee(ton.clone(), addr, msg.message.clone(), abi.clone(), max_fee, config).await?;
+        }
         if config.local_run || is_fee {
-            emulate_locally(ton.clone(), addr, msg.message.clone(), is_fee).await?;
+            emulated_output = emulate_locally(ton.clone(), addr, msg.message.clone(), Some(abi.clone()), is_fee, config).await?;
             if is_fee {
-                return Ok(Value::Null);
+                return Ok(CallResult { output: Value::Null, ..Default::default() });
             }
         }
         if config.async_call {
-            return send_message_and_wait(ton,
+            record_once_sent(config, addr, method, params, &msg.message_id, header_expire)?;
+            let result = send_message_and_wait(ton.clone(),
                                          Some(abi),
                                          msg.message.clone(),
-                                         config).await;
+                                         config).await?;
+            record_once(config, addr, method, params, &result)?;
+            // without --await the transaction may not be executed yet
+            if result.transaction.is_some() {
+                save_account_snapshot(ton.clone(), config, addr, "after.boc").await;
+            }
+            warn_drift(config, emulated_output.as_ref(), &result);
+            return Ok(result);
         }
-        Some(msg.message)
+        Some(msg)
     } else {
         None
     };
 
+    let confirm = config.confirm.unwrap_or_else(|| std::io::stdin().is_terminal());
+    if confirm && !config.is_json && !config.async_call && !config.local_run {
+        let expire = header_expire.unwrap_or(now() + config.lifetime);
+        let expire_at = Local.timestamp_opt(expire as i64, 0).single()
+            .ok_or("failed to convert expiration time".to_string())?;
+        println!();
+        println!("Address: {}", addr);
+        println!("Method: {}", method);
+        println!("Parameters: {}", params);
+        println!("Expire at: {}", expire_at.to_rfc2822());
+        if !ask_confirmation("Send the message?")? {
+            return Err("aborted by user".to_string());
+        }
+    }
+
+    // the encoded message is sent as is when the wait for its transaction is customized
+    // or its id must be recorded before sending
+    let custom_wait = config.wait_timeout.is_some() || config.since_lt.is_some() || config.once.is_some();
+    if let (true, Some(msg)) = (custom_wait, &message) {
+        record_once_sent(config, addr, method, params, &msg.message_id, header_expire)?;
+        let result = send_message_and_wait(ton.clone(), Some(abi.clone()), msg.message.clone(), config).await?;
+        record_once(config, addr, method, params, &result)?;
+        save_account_snapshot(ton.clone(), config, addr, "after.boc").await;
+        warn_drift(config, emulated_output.as_ref(), &result);
+        return Ok(result);
+    }
+
     match process_message(ton.clone(), msg_params, config).await {
-        Ok(result) => Ok(result),
-        Err(e) => {
+        Ok(result) => {
+            record_once(config, addr, method, params, &result)?;
+            save_account_snapshot(ton.clone(), config, addr, "after.boc").await;
+            warn_drift(config, emulated_output.as_ref(), &result);
+            Ok(result)
+        },
+        Err(mut e) => {
+            add_exit_arg(&mut e);
+            add_signature_hint(&mut e, keys.is_some() || !config.signers.is_empty());
+            let exit_message = e.data["exit_code"].as_i64()
+                .and_then(|code| error_map.as_ref()?.get(&code).map(|msg| (code, msg)));
+            if let Some((code, msg)) = exit_message {
+                if !config.is_json {
+                    println!("Contract exit code {}: {}", code, msg);
+                }
+                e.data["exit_message"] = json!(msg);
+            }
             let acc_boc = query_account_field(
                 ton.clone(),
                 addr,
                 "boc",
             ).await?;
             let now = now_ms();
-            let bc_config = get_blockchain_config(config, None).await?;
+            let bc_config = get_blockchain_config(config, config.config_boc.as_deref()).await?;
             let debug_params = DebugParams {
                 account: &acc_boc,
-                message: message.as_deref(),
+                message: message.as_ref().map(|msg| msg.message.as_str()),
                 time_in_ms: now,
                 block_lt: now,
                 last_tr_lt: now,
@@ -340,13 +1303,588 @@ pub async fn call_contract_with_client(
     }
 }
 
+/// Saves the current state of the account to the `--account-snapshot` directory.
+/// The failure is only reported, as the call itself is not affected by it.
+async fn save_account_snapshot(ton: TonClient, config: &Config, addr: &str, name: &str) {
+    let dir = match &config.account_snapshot {
+        Some(dir) => std::path::Path::new(dir),
+        None => return,
+    };
+    if let Err(e) = write_account_snapshot(ton, config, addr, dir, name).await {
+        let warning = format!("Failed to save the account snapshot: {}", e);
+        // stdout carries the result json, so the warning goes to stderr in json mode
+        if config.is_json {
+            eprintln!("{}", warning);
+        } else {
+            println!("{}", output::warning(&warning));
+        }
+    }
+}
+
+async fn write_account_snapshot(
+    ton: TonClient,
+    config: &Config,
+    addr: &str,
+    dir: &std::path::Path,
+    name: &str,
+) -> Result<(), String> {
+    let boc = query_account_field(ton, addr, "boc").await?;
+    let boc = base64::decode(&boc)
+        .map_err(|e| format!("failed to decode account boc: {}", e))?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("failed to create directory {}: {}", dir.display(), e))?;
+    let path = dir.join(name);
+    std::fs::write(&path, boc)
+        .map_err(|e| format!("failed to write account snapshot {}: {}", path.display(), e))?;
+    if !config.is_json {
+        println!("Account state saved to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Call sent with the `--once` idempotency key.
+#[derive(Serialize, Deserialize)]
+struct OnceRecord {
+    address: String,
+    method: String,
+    params: String,
+    transaction_id: Option<String>,
+    /// Id of the sent message, recorded before sending.
+    #[serde(default)]
+    message_id: Option<String>,
+    #[serde(default)]
+    expire: Option<u32>,
+}
+
+fn load_once_ledger(path: &str) -> Result<HashMap<String, OnceRecord>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(data) => serde_json::from_str(&data)
+            .map_err(|e| format!("failed to parse the once ledger {}: {}", path, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(format!("failed to read the once ledger {}: {}", path, e)),
+    }
+}
+
+/// Returns the result of the previous call if the call with the same `--once` key was already sent.
+/// If the message of the previous call has no transaction yet, fails until the message expires.
+async fn check_once(
+    ton: TonClient,
+    config: &Config,
+    addr: &str,
+    method: &str,
+    params: &str,
+) -> Result<Option<CallResult>, String> {
+    let key = match &config.once {
+        Some(key) => key,
+        None => return Ok(None),
+    };
+    let mut ledger = load_once_ledger(&config.once_ledger)?;
+    let record = match ledger.get_mut(key) {
+        Some(record) => record,
+        None => return Ok(None),
+    };
+    if record.address != addr || record.method != method || record.params != params {
+        return Err(format!(
+            "key \"{}\" was already used for another call: {} {} {}", key, record.address, record.method, record.params
+        ));
+    }
+    if let (None, Some(message_id)) = (&record.transaction_id, record.message_id.clone()) {
+        match find_message_transaction(ton, &message_id).await? {
+            Some(transaction_id) => {
+                record.transaction_id = Some(transaction_id);
+                save_once_ledger(&config.once_ledger, &ledger)?;
+                return check_once_record(config, key, &ledger[key]);
+            },
+            None if record.expire.map(|expire| expire < now()).unwrap_or(false) => {
+                if !config.is_json {
+                    println!("{}", output::warning(&format!(
+                        "Message {} of the call with key \"{}\" expired without a transaction. Sending again.",
+                        message_id, key
+                    )));
+                }
+                return Ok(None);
+            },
+            None => return Err(format!(
+                "call with key \"{}\" was already sent in message {}, but its transaction is not found yet. \
+                Check the message later",
+                key, message_id
+            )),
+        }
+    }
+    check_once_record(config, key, record)
+}
+
+fn check_once_record(config: &Config, key: &str, record: &OnceRecord) -> Result<Option<CallResult>, String> {
+    let transaction_id = record.transaction_id.clone().unwrap_or("unknown".to_string());
+    if !config.is_json {
+        println!("{}", output::warning(&format!(
+            "Call with key \"{}\" was already sent in transaction {}. Skipping.", key, transaction_id
+        )));
+    }
+    Ok(Some(CallResult {
+        output: json!({ "skipped": true }),
+        transaction_id: record.transaction_id.clone(),
+        ..Default::default()
+    }))
+}
+
+async fn find_message_transaction(ton: TonClient, message_id: &str) -> Result<Option<String>, String> {
+    let query = ton_client::net::query_collection(
+        ton,
+        ParamsOfQueryCollection {
+            collection: "transactions".to_owned(),
+            filter: Some(json!({ "in_msg": { "eq": message_id } })),
+            result: "id".to_owned(),
+            limit: Some(1),
+            ..Default::default()
+        },
+    ).await.map_err(|e| format!("failed to query the transaction of message {}: {}", message_id, e))?;
+    Ok(query.result.get(0).and_then(|t| t["id"].as_str()).map(|id| id.to_owned()))
+}
+
+fn save_once_ledger(path: &str, ledger: &HashMap<String, OnceRecord>) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(ledger)
+        .map_err(|e| format!("failed to serialize the once ledger: {}", e))?;
+    std::fs::write(path, data)
+        .map_err(|e| format!("failed to write the once ledger {}: {}", path, e))
+}
+
+/// Records the message of the `--once` call before it is sent.
+fn record_once_sent(
+    config: &Config,
+    addr: &str,
+    method: &str,
+    params: &str,
+    message_id: &str,
+    expire: Option<u32>,
+) -> Result<(), String> {
+    let key = match &config.once {
+        Some(key) => key,
+        None => return Ok(()),
+    };
+    let mut ledger = load_once_ledger(&config.once_ledger)?;
+    ledger.insert(key.clone(), OnceRecord {
+        address: addr.to_owned(),
+        method: method.to_owned(),
+        params: params.to_owned(),
+        transaction_id: None,
+        message_id: Some(message_id.to_owned()),
+        expire: Some(expire.unwrap_or(now() + config.lifetime)),
+    });
+    save_once_ledger(&config.once_ledger, &ledger)
+}
+
+fn record_once(config: &Config, addr: &str, method: &str, params: &str, result: &CallResult) -> Result<(), String> {
+    let key = match &config.once {
+        Some(key) => key,
+        None => return Ok(()),
+    };
+    let mut ledger = load_once_ledger(&config.once_ledger)?;
+    let (message_id, expire) = match ledger.remove(key) {
+        Some(record) => (record.message_id, record.expire),
+        None => (None, None),
+    };
+    ledger.insert(key.clone(), OnceRecord {
+        address: addr.to_owned(),
+        method: method.to_owned(),
+        params: params.to_owned(),
+        transaction_id: result.transaction_id.clone(),
+        message_id,
+        expire,
+    });
+    save_once_ledger(&config.once_ledger, &ledger)
+}
+
+/// Default value attached to the emulated internal message: 1 token.
+const DEFAULT_EMULATION_VALUE: &str = "1000000000";
+
+/// Executes the call locally as an internal message from `src` (so that `msg.sender` and
+/// `msg.value` are set) and returns the decoded output together with the outbound messages.
+async fn emulate_internal_call(
+    ton: TonClient,
+    config: &Config,
+    addr: &str,
+    abi: Abi,
+    call_set: Option<CallSet>,
+    src: &str,
+) -> Result<CallResult, String> {
+    let value = match &config.emulate_value {
+        Some(value) => parse_integer_param(value)?,
+        None => DEFAULT_EMULATION_VALUE.to_owned(),
+    };
+    let message = encode_internal_message(
+        ton.clone(),
+        ParamsOfEncodeInternalMessage {
+            abi: Some(abi.clone()),
+            address: Some(addr.to_owned()),
+            src_address: Some(src.to_owned()),
+            call_set,
+            value,
+            ..Default::default()
+        },
+    )
+    .map_err(|e| format!("failed to create internal message: {}", e))?
+    .message;
+
+    let result = run_local_emulation(ton.clone(), addr, message, Some(abi), false, config.libs.as_deref()).await?;
+    let mut out_messages = vec![];
+    for boc in &result.out_messages {
+        let msg = Message::construct_from_base64(boc)
+            .map_err(|e| format!("failed to parse outbound message: {}", e))?;
+        out_messages.push(json!({
+            "dst": msg.dst().map(|dst| dst.to_string()),
+            "value": msg.get_value().map(|value| value.grams.as_u128().to_string()),
+        }));
+    }
+    if !config.is_json {
+        println!("Internal message from {} executed locally. Message was not sent.", src);
+    }
+    let mut call_result = CallResult::from_emulation(result);
+    call_result.output = json!({
+        "output": call_result.output,
+        "out_messages": out_messages,
+    });
+    Ok(call_result)
+}
+
+/// Leaves in the message header only the fields declared in the ABI header,
+/// contracts reject messages with the header fields they don't expect.
+fn filter_header(abi: &ton_abi::Contract, header: FunctionHeader) -> Option<FunctionHeader> {
+    let declares = |name: &str| abi.header().iter().any(|param| param.name == name);
+    let header = FunctionHeader {
+        time: header.time.filter(|_| declares("time")),
+        expire: header.expire.filter(|_| declares("expire")),
+        pubkey: header.pubkey.filter(|_| declares("pubkey")),
+    };
+    if header.time.is_none() && header.expire.is_none() && header.pubkey.is_none() {
+        None
+    } else {
+        Some(header)
+    }
+}
+
+/// Loads the json file which maps contract exit codes to error messages: `{"101": "message", ...}`.
+pub fn load_error_map(path: &str) -> Result<HashMap<i64, String>, String> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read error map file {}: {}", path, e))?;
+    let map: HashMap<String, String> = serde_json::from_str(&data)
+        .map_err(|e| format!("failed to parse error map file {}: {}", path, e))?;
+    map.into_iter()
+        .map(|(code, msg)| {
+            i64::from_str(code.trim())
+                .map(|code| (code, msg))
+                .map_err(|e| format!("invalid exit code \"{}\" in the error map: {}", code, e))
+        })
+        .collect()
+}
+
+/// Reformats decoded function output using the types of the function output params from the ABI:
+/// token amounts (`uint128`, `varuint16` and `token`) are shown both in nano and in tokens,
+/// `bytes` containing valid UTF-8 are shown as strings.
+pub fn decode_output_types(abi: &ton_abi::Contract, method: &str, mut output: Value) -> Result<Value, String> {
+    let function = abi.function(method)
+        .map_err(|e| format!("failed to find function \"{}\" in the ABI: {}", method, e))?;
+    if let Value::Object(map) = &mut output {
+        for param in &function.outputs {
+            if let Some(value) = map.get_mut(&param.name) {
+                *value = decode_value_type(&param.kind, value.take());
+            }
+        }
+    }
+    Ok(output)
+}
+
+fn decode_value_type(kind: &ParamType, value: Value) -> Value {
+    match (kind, value) {
+        (ParamType::Uint(128) | ParamType::VarUint(16) | ParamType::Token, Value::String(nano)) => {
+            match convert::convert_nanotoken(&nano) {
+                Ok(tokens) => json!({ "nano": nano, "tokens": tokens }),
+                Err(_) => Value::String(nano),
+            }
+        },
+        (ParamType::Bytes, Value::String(bytes)) => {
+            match hex::decode(&bytes).ok().and_then(|b| String::from_utf8(b).ok()) {
+                Some(string) => Value::String(string),
+                None => Value::String(bytes),
+            }
+        },
+        (ParamType::Tuple(components), Value::Object(mut map)) => {
+            for param in components {
+                if let Some(value) = map.get_mut(&param.name) {
+                    *value = decode_value_type(&param.kind, value.take());
+                }
+            }
+            Value::Object(map)
+        },
+        (ParamType::Array(inner) | ParamType::FixedArray(inner, _), Value::Array(items)) => {
+            Value::Array(items.into_iter().map(|item| decode_value_type(inner, item)).collect())
+        },
+        (ParamType::Map(_, inner), Value::Object(map)) => {
+            Value::Object(map.into_iter().map(|(k, v)| (k, decode_value_type(inner, v))).collect())
+        },
+        (ParamType::Optional(inner) | ParamType::Ref(inner), value) if !value.is_null() => {
+            decode_value_type(inner, value)
+        },
+        (_, value) => value,
+    }
+}
+
+fn normalize_pubkey(key: &str) -> String {
+    let key = key.trim_start_matches("0x").to_lowercase();
+    format!("{:0>64}", key)
+}
+
+fn contains_pubkey(value: &Value, pubkey: &str) -> bool {
+    match value {
+        Value::String(s) => normalize_pubkey(s) == pubkey,
+        Value::Array(items) => items.iter().any(|item| contains_pubkey(item, pubkey)),
+        Value::Object(map) => map.values().any(|item| contains_pubkey(item, pubkey)),
+        _ => false,
+    }
+}
+
+/// Runs the getter of the contract and checks that its output contains the public key
+/// of the signing keys, so the call with wrong keys is aborted before sending.
+async fn verify_signing_key(
+    ton: TonClient,
+    addr: &str,
+    abi: Abi,
+    getter: &str,
+    keys: &str,
+) -> Result<(), String> {
+    let pubkey = normalize_pubkey(&load_keypair(keys)?.public);
+    let output = run_getter(ton, addr, abi, getter, "{}").await?;
+    if !contains_pubkey(&output, &pubkey) {
+        return Err(format!(
+            "public key of the signing keys 0x{} does not match the key returned by \"{}\": {}",
+            pubkey, getter, output
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the nonce was not used yet: it must not be less than the value returned by the getter.
+async fn check_nonce(ton: TonClient, addr: &str, abi: Abi, getter: &str, nonce: u64) -> Result<(), String> {
+    let output = run_getter(ton, addr, abi, getter, "{}").await?;
+    let current = output.as_object()
+        .and_then(|obj| obj.values().next())
+        .and_then(parse_nonce_value)
+        .ok_or(format!("getter \"{}\" returned unexpected output: {}", getter, output))?;
+    if nonce < current {
+        return Err(format!("nonce {} is already used: current contract nonce is {}", nonce, current));
+    }
+    Ok(())
+}
+
+fn parse_nonce_value(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => s.parse().ok(),
+        },
+        _ => None,
+    }
+}
+
+/// Runs a getter of the deployed contract locally and returns its decoded output.
+pub async fn run_getter(
+    ton: TonClient,
+    addr: &str,
+    abi: Abi,
+    getter: &str,
+    params: &str,
+) -> Result<Value, String> {
+    let account = query_account_field(ton.clone(), addr, "boc").await?;
+    let msg_params = prepare_message_params(addr, abi.clone(), getter, params, None, None, None, None)?;
+    let msg = encode_message(ton.clone(), msg_params).await
+        .map_err(|e| format!("failed to create getter message: {}", e))?;
+    let result = run_tvm(
+        ton,
+        ParamsOfRunTvm {
+            message: msg.message,
+            account,
+            abi: Some(abi),
+            ..Default::default()
+        },
+    ).await
+    .map_err(|e| format!("failed to run getter \"{}\": {}", getter, e))?;
+    Ok(result.decoded.and_then(|d| d.output).unwrap_or(Value::Null))
+}
+
+/// ABIs fetched from contracts with `--abi-from-contract`, keyed by the address and getter name.
+static CONTRACT_ABI_CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+/// Headers tried in turn when calling the ABI getter: the contract header is not known before
+/// its ABI is fetched.
+const ABI_GETTER_HEADERS: [&[&str]; 4] = [&["pubkey", "time", "expire"], &["time", "expire"], &["time"], &[]];
+
+/// Fetches the contract ABI returned by the `getter`. The getter must take no arguments and
+/// return the ABI json (or hex encoded ABI json, as saved by `setABI`) as its first output of
+/// type `string` or `bytes`. Other outputs are ignored.
+pub async fn abi_from_contract(config: &Config, addr: &str, getter: &str) -> Result<String, String> {
+    let key = format!("{}:{}", addr, getter);
+    let cache = CONTRACT_ABI_CACHE.get_or_init(Default::default);
+    if let Some(abi) = cache.lock().unwrap().get(&key) {
+        return Ok(abi.clone());
+    }
+    let ton = create_client(config)?;
+    let account = query_account_field(ton.clone(), addr, "boc").await?;
+    let mut last_error = String::new();
+    for header in ABI_GETTER_HEADERS {
+        match run_abi_getter(ton.clone(), addr, &account, getter, header).await {
+            Ok(value) => {
+                let abi = parse_contract_abi(&value)?;
+                cache.lock().unwrap().insert(key, abi.clone());
+                return Ok(abi);
+            },
+            Err(e) => last_error = e,
+        }
+    }
+    Err(format!("failed to fetch ABI from the contract with getter \"{}\": {}", getter, last_error))
+}
+
+async fn run_abi_getter(
+    ton: TonClient,
+    addr: &str,
+    account: &str,
+    getter: &str,
+    header: &[&str],
+) -> Result<String, String> {
+    let abi = Abi::Json(json!({
+        "ABI version": 2,
+        "version": "2.2",
+        "header": header,
+        "functions": [{ "name": getter, "inputs": [], "outputs": [{ "name": "abi", "type": "bytes" }] }],
+        "events": [],
+        "data": [],
+    }).to_string());
+    let msg_params = prepare_message_params(addr, abi.clone(), getter, "{}", None, None, None, None)?;
+    let msg = encode_message(ton.clone(), msg_params).await
+        .map_err(|e| format!("failed to create getter message: {}", e))?;
+    let result = run_tvm(
+        ton.clone(),
+        ParamsOfRunTvm {
+            message: msg.message,
+            account: account.to_owned(),
+            ..Default::default()
+        },
+    ).await
+        .map_err(|e| format!("{}", e))?;
+    for out_msg in result.out_messages {
+        // string and bytes are encoded the same way, the rest of the outputs is skipped
+        let decoded = decode_message(
+            ton.clone(),
+            ParamsOfDecodeMessage { abi: abi.clone(), message: out_msg, allow_partial: true, ..Default::default() },
+        );
+        if let Ok(decoded) = decoded {
+            if matches!(decoded.body_type, MessageBodyType::Output) {
+                return decoded.value.as_ref()
+                    .and_then(|v| v["abi"].as_str())
+                    .map(|v| v.to_owned())
+                    .ok_or("getter output is empty".to_string());
+            }
+        }
+    }
+    Err("getter returned no output".to_string())
+}
+
+/// Turns the getter output (hex of the string or bytes value) into the ABI json.
+fn parse_contract_abi(value: &str) -> Result<String, String> {
+    let bytes = hex::decode(value).map_err(|e| format!("failed to decode getter output: {}", e))?;
+    let mut abi = String::from_utf8(bytes)
+        .map_err(|e| format!("ABI returned by the getter is not a valid UTF-8 string: {}", e))?;
+    // the ABI may be saved in hex, as debots do with setABI
+    if let Ok(decoded) = hex::decode(abi.trim()) {
+        if let Ok(decoded) = String::from_utf8(decoded) {
+            abi = decoded;
+        }
+    }
+    serde_json::from_str::<ton_client::abi::AbiContract>(&abi)
+        .map_err(|e| format!("getter returned an invalid ABI: {}", e))?;
+    Ok(abi)
+}
+
+/// Writes the result json to the `--json-output-file`. Returns false if the file is not set.
+fn write_json_output(result: &Value, config: &Config) -> Result<bool, String> {
+    let path = match &config.json_output_file {
+        Some(path) => path,
+        None => return Ok(false),
+    };
+    std::fs::write(path, format!("{:#}", result))
+        .map_err(|e| format!("failed to write result to file {}: {}", path, e))?;
+    if !config.is_json {
+        println!("Result saved to file {}", path);
+    }
+    Ok(true)
+}
+
+/// Serializes the result according to `--pretty`/`--compact`: compact in json mode and pretty otherwise by default.
+pub fn format_json_result(result: &Value, config: &Config) -> String {
+    if config.pretty_json.unwrap_or(!config.is_json) {
+        format!("{:#}", result)
+    } else {
+        result.to_string()
+    }
+}
+
 pub fn print_json_result(result: Value, config: &Config) -> Result<(), String> {
     if !result.is_null() {
+        let saved = write_json_output(&result, config)?;
         if !config.is_json {
-            println!("Result: {:#}", result);
+            if !saved {
+                println!("Result: {}", format_json_result(&result, config));
+            }
         } else {
-            println!("{:#}", result);
+            println!("{}", format_json_result(&result, config));
+        }
+    }
+    Ok(())
+}
+
+pub fn print_call_result(result: CallResult, config: &Config) -> Result<(), String> {
+    if result.output.is_null() && result.out_messages.is_none() {
+        return Ok(());
+    }
+    if config.raw_transaction {
+        if let Some(transaction) = &result.transaction {
+            let saved = write_json_output(transaction, config)?;
+            if !config.is_json {
+                if !saved {
+                    println!("Transaction: {}", format_json_result(transaction, config));
+                }
+            } else {
+                println!("{}", format_json_result(transaction, config));
+            }
+            return Ok(());
+        }
+    }
+    let json_result = serde_json::to_value(&result)
+        .map_err(|e| format!("failed to serialize result: {}", e))?;
+    let saved = write_json_output(&json_result, config)?;
+    if !config.is_json {
+        if let Some(transaction_id) = &result.transaction_id {
+            println!("TransactionId: {}", transaction_id);
+        }
+        if let Some(fees) = &result.fees {
+            if result.transaction_id.is_none() {
+                println!("Fees: {}", format_json_result(fees, config));
+            }
+        }
+        if let Some(events) = &result.events {
+            println!("Events: {}", format_json_result(&json!(events), config));
         }
+        if let Some(out_messages) = &result.out_messages {
+            println!("Out messages: {}", format_json_result(&json!(out_messages), config));
+        }
+        if let (Some(exit_code), Some(exit_arg)) = (result.exit_code, result.exit_arg) {
+            println!("Exit code: {}, exit argument: {}", exit_code, exit_arg);
+        }
+        if !saved && !result.output.is_null() {
+            println!("Result: {}", format_json_result(&result.output, config));
+        }
+    } else {
+        println!("{}", format_json_result(&json_result, config));
     }
     Ok(())
 }
@@ -359,49 +1897,532 @@ pub async fn call_contract(
     params: &str,
     keys: Option<String>,
     is_fee: bool,
+    lifetime: Option<u32>,
+) -> Result<(), String> {
+    if let Some(amount) = &config.tonconnect_amount {
+        let request = tonconnect_request(config, addr, abi_path, method, params, amount, lifetime).await?;
+        return print_json_result(request, config);
+    }
+    let mut result = call_contract_with_result(config, addr, abi_path, method, params, keys, is_fee, lifetime).await?;
+    if config.fail_on_empty_output && !is_fee && is_empty_output(&result.output) {
+        return Err(format!(
+            "function \"{}\" returned empty output (transaction {})",
+            method,
+            result.transaction_id.as_deref().unwrap_or("unknown"),
+        ));
+    }
+    if !config.is_json {
+        println!("{}", output::success("Succeeded."));
+    }
+    if config.decode_types && !result.output.is_null() {
+        let abi = load_ton_abi(abi_path, config).await?;
+        result.output = decode_output_types(&abi, method, result.output)?;
+    }
+    // functions sending messages often have no outputs, so the messages are decoded anyway
+    if !config.out_abis.is_empty() {
+        let abis = load_out_abis(config).await?;
+        result.out_messages = Some(decode_out_messages(&result.out_message_bocs, &abis).await?);
+    }
+    print_call_result(result, config)?;
+    Ok(())
+}
+
+/// Builds the TON Connect `SendTransaction` request with the internal call of the function,
+/// so that a wallet app can sign and send it.
+async fn tonconnect_request(
+    config: &Config,
+    addr: &str,
+    abi_path: &str,
+    method: &str,
+    params: &str,
+    amount: &str,
+    lifetime: Option<u32>,
+) -> Result<Value, String> {
+    ton_block::MsgAddressInt::from_str(addr)
+        .map_err(|e| format!("invalid destination address {}: {}", addr, e))?;
+    let amount = amount.parse::<u64>()
+        .map_err(|e| format!("invalid amount {}: {}", amount, e))?;
+    if amount == 0 {
+        return Err("amount of the TON Connect request must be positive".to_owned());
+    }
+    let params = serde_json::from_str(params)
+        .map_err(|e| format!("arguments are not in json format: {}", e))?;
+    let payload = encode_message_body(
+        create_client_local()?,
+        ParamsOfEncodeMessageBody {
+            abi: load_abi(abi_path, config).await?,
+            call_set: CallSet::some_with_function_and_input(method, params)
+                .ok_or("failed to create CallSet with specified parameters.")?,
+            is_internal: true,
+            ..Default::default()
+        },
+    ).await
+        .map_err(|e| format!("failed to encode body: {}", e))?
+        .body;
+    Ok(json!({
+        "valid_until": now() + lifetime.unwrap_or(config.lifetime),
+        "messages": [{
+            "address": addr,
+            "amount": amount.to_string(),
+            "payload": payload,
+        }],
+    }))
+}
+
+fn is_empty_output(output: &Value) -> bool {
+    match output {
+        Value::Null => true,
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+/// Sends an external message with the provided body cell without ABI encoding. If keys are
+/// specified, the body is prefixed with the ed25519 signature of the body cell hash.
+pub async fn call_raw_body(
+    config: &Config,
+    addr: &str,
+    body: &str,
+    keys: Option<String>,
+) -> Result<(), String> {
+    let body = base64::decode(load_body_input(body)?)
+        .map_err(|e| format!("body is not a valid base64 string: {}", e))?;
+    let body = ton_types::read_single_root_boc(body)
+        .map_err(|e| format!("failed to load body cell: {}", e))?;
+    let body = match keys {
+        Some(keys) => sign_raw_body(body, &keys)?,
+        None => body,
+    };
+    let dst = ton_block::MsgAddressInt::from_str(addr)
+        .map_err(|e| format!("couldn't decode address: {}", e))?;
+    let header = ExternalInboundMessageHeader::new(MsgAddressExt::AddrNone, dst);
+    let body = SliceData::load_cell(body)
+        .map_err(|e| format!("failed to load body cell: {}", e))?;
+    let message = Message::with_ext_in_header_and_body(header, body);
+    let message = message.serialize()
+        .and_then(|cell| ton_types::write_boc(&cell))
+        .map_err(|e| format!("failed to serialize message: {}", e))?;
+
+    let ton = create_client_verbose(config)?;
+    if config.dry_run {
+        return dry_run_message(ton, addr, base64::encode(message), None, false, config).await;
+    }
+    let result = send_message_and_wait(ton, None, base64::encode(message), config).await?;
+    match result.transaction_id {
+        Some(transaction_id) => {
+            if !config.is_json {
+                println!("{}", output::success("Succeeded."));
+                println!("TransactionId: {}", transaction_id);
+            } else {
+                println!("{}", format_json_result(&json!({ "transaction_id": transaction_id }), config));
+            }
+            Ok(())
+        },
+        None => print_call_result(result, config),
+    }
+}
+
+fn sign_raw_body(body: Cell, keys: &str) -> Result<Cell, String> {
+    let keys = load_keypair(keys)?;
+    let secret = hex::decode(&keys.secret)
+        .map_err(|e| format!("failed to decode secret key: {}", e))?;
+    let signature = ed25519_sign_with_secret(&secret, body.repr_hash().as_slice())
+        .map_err(|e| format!("failed to sign the body: {}", e))?;
+    let mut builder = BuilderData::default();
+    builder.append_raw(&signature, 512)
+        .and_then(|b| b.checked_append_references_and_data(&SliceData::load_cell(body)?))
+        .map_err(|e| format!("body is too large to be signed: {}", e))?;
+    builder.into_cell()
+        .map_err(|e| format!("failed to build signed body: {}", e))
+}
+
+/// Single call of the batch file.
+#[derive(Deserialize)]
+struct BatchCall {
+    address: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    keys: Option<String>,
+    abi: Option<String>,
+}
+
+async fn execute_batch_call(
+    ton: TonClient,
+    config: &Config,
+    call: &BatchCall,
+    abi: Option<&str>,
+    keys: Option<String>,
+    is_fee: bool,
+    lifetime: Option<u32>,
+) -> Result<CallResult, String> {
+    let abi_path = call.abi.as_deref().or(abi)
+        .ok_or("ABI is not specified neither for the call nor in the command options or config".to_string())?;
+    let address = load_ton_address(&call.address, config)?;
+    let params = match &call.params {
+        Value::Null => "{}".to_owned(),
+        Value::String(params) => load_params(params)?,
+        params => params.to_string(),
+    };
+    call_contract_with_client(
+        ton,
+        config,
+        &address,
+        abi_path,
+        &call.method,
+        &params,
+        call.keys.clone().or(keys),
+        is_fee,
+        lifetime,
+    ).await
+}
+
+/// Executes calls from the batch file sequentially and prints results of all calls.
+pub async fn call_batch(
+    config: &Config,
+    batch_path: &str,
+    abi: Option<String>,
+    keys: Option<String>,
+    is_fee: bool,
+    lifetime: Option<u32>,
+    stop_on_error: bool,
 ) -> Result<(), String> {
-    let result = call_contract_with_result(config, addr, abi_path, method, params, keys, is_fee).await?;
+    let batch = std::fs::read_to_string(batch_path)
+        .map_err(|e| format!("failed to read batch file: {}", e))?;
+    let calls: Vec<BatchCall> = serde_json::from_str(&batch)
+        .map_err(|e| format!("failed to parse batch file: {}", e))?;
+
+    let ton = create_client_verbose(config)?;
+    let mut results = vec![];
+    let mut failed = 0;
+    for (i, call) in calls.iter().enumerate() {
+        if !config.is_json {
+            println!("Call {}/{}: {} {}", i + 1, calls.len(), call.address, call.method);
+        }
+        let result = execute_batch_call(
+            ton.clone(), config, call, abi.as_deref(), keys.clone(), is_fee, lifetime,
+        ).await;
+        match result {
+            Ok(result) => {
+                let result = serde_json::to_value(&result)
+                    .map_err(|e| format!("failed to serialize result: {}", e))?;
+                results.push(json!({
+                    "address": call.address,
+                    "method": call.method,
+                    "result": result,
+                }));
+            },
+            Err(e) => {
+                failed += 1;
+                if !config.is_json {
+                    println!("{}", output::error(&format!("Call failed: {}", e)));
+                }
+                results.push(json!({
+                    "address": call.address,
+                    "method": call.method,
+                    "error": serde_json::from_str::<Value>(&e).unwrap_or(Value::String(e)),
+                }));
+                if stop_on_error {
+                    break;
+                }
+            },
+        }
+    }
     if !config.is_json {
-        println!("Succeeded.");
+        println!("Batch results:");
+    }
+    println!("{}", format_json_result(&Value::Array(results), config));
+    if failed > 0 {
+        return Err(format!("{} of {} calls failed", failed, calls.len()));
     }
-    print_json_result(result, config)?;
     Ok(())
 }
 
+/// Latency statistics of the calls sent with `--count`.
+#[derive(Serialize, Debug, PartialEq)]
+struct RepeatedCallStats {
+    count: usize,
+    succeeded: usize,
+    failed: usize,
+    min_ms: u128,
+    max_ms: u128,
+    avg_ms: u128,
+    transactions: Vec<String>,
+}
 
-pub async fn call_contract_with_msg(config: &Config, str_msg: String, abi_path: &str) -> Result<(), String> {
-    let ton = create_client_verbose(&config)?;
-    let abi = load_abi(abi_path, config).await?;
+fn repeated_call_stats(results: &[(Duration, Result<CallResult, String>)]) -> RepeatedCallStats {
+    let latencies: Vec<u128> = results.iter().map(|(latency, _)| latency.as_millis()).collect();
+    let transactions: Vec<String> = results.iter()
+        .filter_map(|(_, result)| result.as_ref().ok()?.transaction_id.clone())
+        .collect();
+    let succeeded = results.iter().filter(|(_, result)| result.is_ok()).count();
+    RepeatedCallStats {
+        count: results.len(),
+        succeeded,
+        failed: results.len() - succeeded,
+        min_ms: latencies.iter().copied().min().unwrap_or(0),
+        max_ms: latencies.iter().copied().max().unwrap_or(0),
+        avg_ms: latencies.iter().sum::<u128>() / (latencies.len().max(1) as u128),
+        transactions,
+    }
+}
 
-    let (msg, _) = unpack_message(&str_msg)?;
-    if config.is_json {
-        println!("{{");
+/// Sends the call `count` times with up to `concurrency` calls at once and prints latency
+/// statistics. `{i}` in the parameters is replaced with the number of the call.
+pub async fn call_repeated(
+    config: &Config,
+    addr: &str,
+    abi_path: &str,
+    method: &str,
+    params: &str,
+    keys: Option<String>,
+    lifetime: Option<u32>,
+    count: usize,
+    concurrency: usize,
+) -> Result<(), String> {
+    let mut config = config.clone();
+    let confirm = config.confirm.unwrap_or_else(|| std::io::stdin().is_terminal());
+    if confirm && !config.is_json && !config.async_call && !config.local_run {
+        println!();
+        println!("Address: {}", addr);
+        println!("Method: {}", method);
+        println!("Parameters: {}", params);
+        if !ask_confirmation(&format!("Send the message {} times?", count))? {
+            return Err("aborted by user".to_string());
+        }
     }
-    print_encoded_message(&msg, config.is_json);
+    config.confirm = Some(false);
+    let config = &config;
 
-    let params = decode_call_parameters(ton.clone(), &msg, abi.clone()).await?;
+    let pool = ClientPool::new(config, concurrency, true)?;
+    let calls = (0..count).map(|i| {
+        let (pool, keys) = (pool.clone(), keys.clone());
+        let params = params.replace("{i}", &i.to_string());
+        async move {
+            let result = match pool.acquire().await {
+                Ok((ton, _permit)) => {
+                    // the time spent waiting for a free slot is not counted
+                    let start = Instant::now();
+                    let result = call_contract_with_client(ton, config, addr, abi_path, method, &params, keys, false, lifetime).await;
+                    (start.elapsed(), result)
+                },
+                Err(e) => (Duration::default(), Err(e)),
+            };
+            if !config.is_json {
+                match &result.1 {
+                    Ok(call) => println!("Call {}: transaction {} in {} ms",
+                        i, call.transaction_id.as_deref().unwrap_or("unknown"), result.0.as_millis()),
+                    Err(e) => println!("{}", output::error(&format!("Call {} failed: {}", i, e))),
+                }
+            }
+            result
+        }
+    });
+    let results = futures::future::join_all(calls).await;
+    let stats = repeated_call_stats(&results);
+    if !config.is_json {
+        println!("Calls: {}, succeeded: {}, failed: {}", stats.count, stats.succeeded, stats.failed);
+        println!("Latency: min {} ms, max {} ms, avg {} ms", stats.min_ms, stats.max_ms, stats.avg_ms);
+    } else {
+        println!("{}", format_json_result(&json!(stats), config));
+    }
+    if stats.failed > 0 {
+        return Err(format!("{} of {} calls failed", stats.failed, stats.count));
+    }
+    Ok(())
+}
+
+/// Converts message given in one of the supported forms into `EncodedMessage`:
+/// output of the `message` command, BOC encoded in hex or base64 or path to the file
+/// with any of them (or with raw BOC bytes).
+fn load_message_input(input: &str) -> Result<EncodedMessage, String> {
+    let input = input.trim();
+    if std::path::Path::new(input).is_file() {
+        let bytes = std::fs::read(input)
+            .map_err(|e| format!("failed to read message file {}: {}", input, e))?;
+        if let Ok(msg) = encoded_message_from_boc(&bytes) {
+            return Ok(msg);
+        }
+        let text = String::from_utf8(bytes)
+            .map_err(|_| format!("file {} contains neither a message BOC nor a text message", input))?;
+        let text = text.trim();
+        if text.starts_with('{') {
+            return unpack_message(&hex::encode(text)).map(|(msg, _)| msg);
+        }
+        return decode_message_text(text)
+            .map_err(|e| format!("failed to load message from file {}: {}", input, e));
+    }
+    decode_message_text(input)
+}
+
+fn decode_message_text(input: &str) -> Result<EncodedMessage, String> {
+    if let Ok(bytes) = hex::decode(input) {
+        if bytes.first() == Some(&b'{') {
+            return unpack_message(input).map(|(msg, _)| msg);
+        }
+        return encoded_message_from_boc(&bytes)
+            .map_err(|e| format!("input is a hex string but not a valid message BOC: {}", e));
+    }
+    if let Ok(bytes) = base64::decode(input) {
+        return encoded_message_from_boc(&bytes)
+            .map_err(|e| format!("input is a base64 string but not a valid message BOC: {}", e));
+    }
+    Err("failed to parse message: expected output of the message command, BOC in hex or base64 or path to the message file".to_owned())
+}
+
+fn encoded_message_from_boc(bytes: &[u8]) -> Result<EncodedMessage, String> {
+    let cell = ton_types::read_single_root_boc(bytes)
+        .map_err(|e| format!("failed to deserialize BOC: {}", e))?;
+    let message_id = cell.repr_hash().as_hex_string();
+    let message = Message::construct_from_cell(cell)
+        .map_err(|e| format!("failed to parse message: {}", e))?;
+    let address = message.dst().map(|addr| addr.to_string()).unwrap_or_default();
+    Ok(EncodedMessage {
+        message_id,
+        message: base64::encode(bytes),
+        expire: None,
+        address,
+    })
+}
+
+pub async fn call_contract_with_msg(
+    config: &Config,
+    str_msg: String,
+    abi_paths: &[String],
+    offline: bool,
+) -> Result<(), String> {
+    let mut abis = Vec::new();
+    for abi_path in abi_paths {
+        abis.push(load_abi(abi_path, config).await?);
+    }
+
+    let msg = load_message_input(&str_msg)?;
+    let (index, body_type, method, params) = decode_call_parameters_with_abis(&msg, &abis).await?;
+    let abi = abis.swap_remove(index);
+    let is_event = matches!(body_type, MessageBodyType::Event);
 
     if !config.is_json {
-        println!("Calling method {} with parameters:", params.0);
-        println!("{}", params.1);
-        println!("Processing... ");
+        print_encoded_message(&msg, Map::new(), false);
+        if abi_paths.len() > 1 {
+            println!("Matched ABI: {}", abi_paths[index]);
+        }
+        if is_event {
+            println!("Event {} with fields:", method);
+        } else {
+            println!("Calling method {} with parameters:", method);
+        }
+        println!("{}", params);
     } else {
-        println!("  \"Method\": \"{}\",", params.0);
-        println!("  \"Parameters\": {},", params.1);
-        println!("}}");
+        let mut extra = Map::new();
+        if abi_paths.len() > 1 {
+            extra.insert("ABI".to_owned(), json!(abi_paths[index]));
+        }
+        let (name_key, params_key) = if is_event { ("Event", "Fields") } else { ("Method", "Parameters") };
+        extra.insert(name_key.to_owned(), json!(method));
+        extra.insert(params_key.to_owned(), serde_json::from_str(&params).unwrap_or(Value::String(params)));
+        print_encoded_message(&msg, extra, true);
+    }
+    if offline {
+        return Ok(());
+    }
+    if is_event {
+        return Err(format!("the message is the event {} emitted by the contract, it can't be sent", method));
+    }
+    let ton = create_client_verbose(&config)?;
+    if config.dry_run {
+        return dry_run_message(ton, &msg.address, msg.message, Some(abi), false, config).await;
+    }
+    if !config.is_json {
+        println!("Processing... ");
     }
     let result = send_message_and_wait(ton, Some(abi), msg.message,  config).await?;
 
     if !config.is_json {
-        println!("Succeeded.");
-        if !result.is_null() {
-            println!("Result: {:#}", result);
+        println!("{}", output::success("Succeeded."));
+        if !result.output.is_null() {
+            println!("Result: {}", format_json_result(&result.output, config));
+        }
+    }
+    Ok(())
+}
+
+/// Substitutes `${VAR}` references in the parameter value with the environment variables.
+/// Undefined variables are an error unless `allow_undefined` is set, then they are kept as is.
+/// `$${` is kept as the literal `${`.
+fn expand_env_vars(
+    value: &str,
+    allow_undefined: bool,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            result.push_str(&rest[..start - 1]);
+            result.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
         }
+        result.push_str(&rest[..start]);
+        let reference = &rest[start..];
+        let name = reference[2..].find('}')
+            .map(|end| &reference[2..2 + end])
+            .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+        let name = match name {
+            Some(name) => name,
+            None => {
+                result.push_str("${");
+                rest = &reference[2..];
+                continue;
+            }
+        };
+        match var(name) {
+            Some(value) => result.push_str(&value),
+            None if allow_undefined => result.push_str(&reference[..name.len() + 3]),
+            None => return Err(format!(
+                "environment variable {} used in the parameters is not defined (use --allow-undefined to keep it as is)", name
+            )),
+        }
+        rest = &reference[name.len() + 3..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Substitutes `${VAR}` references in the parameter. In the JSON parameter only the string values
+/// are substituted, so the values of the variables can't change the structure of the JSON.
+fn expand_env_params<F: Fn(&str) -> Option<String>>(param: &str, allow_undefined: bool, var: F) -> Result<String, String> {
+    if !param.contains("${") {
+        return Ok(param.to_owned());
+    }
+    match serde_json::from_str::<Value>(param) {
+        Ok(mut json) if json.is_object() || json.is_array() => {
+            expand_json_strings(&mut json, allow_undefined, &var)?;
+            Ok(json.to_string())
+        },
+        _ => expand_env_vars(param, allow_undefined, var),
+    }
+}
+
+fn expand_json_strings<F: Fn(&str) -> Option<String>>(value: &mut Value, allow_undefined: bool, var: &F) -> Result<(), String> {
+    match value {
+        Value::String(s) => *s = expand_env_vars(s, allow_undefined, var)?,
+        Value::Array(items) => for item in items {
+            expand_json_strings(item, allow_undefined, var)?;
+        },
+        Value::Object(map) => for item in map.values_mut() {
+            expand_json_strings(item, allow_undefined, var)?;
+        },
+        _ => {},
     }
     Ok(())
 }
 
 pub async fn parse_params(params_vec: Vec<&str>, abi_path: &str, method: &str, config: &Config) -> Result<String, String> {
+    let params_vec = params_vec.iter()
+        .map(|param| expand_env_params(param, config.allow_undefined_env, |name| std::env::var(name).ok()))
+        .collect::<Result<Vec<String>, String>>()?;
+    let params_vec: Vec<&str> = params_vec.iter().map(|param| param.as_str()).collect();
     if params_vec.len() == 1 {
         // if there is only 1 parameter it must be a json string with arguments
         Ok(params_vec[0].to_owned())
@@ -409,3 +2430,756 @@ pub async fn parse_params(params_vec: Vec<&str>, abi_path: &str, method: &str, c
         build_json_from_params(params_vec, abi_path, method, config).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::pack_message;
+
+    #[test]
+    fn test_parse_contract_abi() {
+        let abi = std::fs::read_to_string("tests/samples/giver_v2.abi.json").unwrap();
+        // the getter returns the ABI json as is
+        assert_eq!(parse_contract_abi(&hex::encode(&abi)).unwrap(), abi);
+        // or hex encoded, as it is saved with setABI
+        assert_eq!(parse_contract_abi(&hex::encode(hex::encode(&abi))).unwrap(), abi);
+        assert!(parse_contract_abi(&hex::encode("not an abi")).unwrap_err().contains("invalid ABI"));
+    }
+
+    #[test]
+    fn test_filter_header() {
+        let header = FunctionHeader {
+            time: Some(1700000000000),
+            expire: Some(1700000060),
+            pubkey: None,
+        };
+        let abi = parse_ton_abi(r#"{"ABI version": 2, "version": "2.2", "header": ["expire"], "functions": []}"#).unwrap();
+        let filtered = filter_header(&abi, header.clone()).unwrap();
+        assert_eq!(filtered.time, None);
+        assert_eq!(filtered.expire, Some(1700000060));
+
+        let abi = parse_ton_abi(r#"{"ABI version": 2, "version": "2.2", "header": ["pubkey"], "functions": []}"#).unwrap();
+        assert!(filter_header(&abi, header.clone()).is_none());
+
+        let abi = parse_ton_abi(r#"{"ABI version": 2, "version": "2.2", "header": ["pubkey", "time", "expire"], "functions": []}"#).unwrap();
+        let filtered = filter_header(&abi, header).unwrap();
+        assert_eq!(filtered.time, Some(1700000000000));
+        assert_eq!(filtered.expire, Some(1700000060));
+    }
+
+    #[test]
+    fn test_parse_integer_param_units() {
+        assert_eq!(parse_integer_param("1ever").unwrap(), "1000000000");
+        assert_eq!(parse_integer_param("1000mever").unwrap(), "1000000000");
+        assert_eq!(parse_integer_param("1000000000nano").unwrap(), "1000000000");
+        assert_eq!(parse_integer_param("1T").unwrap(), "1000000000");
+        assert_eq!(parse_integer_param("1.5EVER").unwrap(), "1500000000");
+        assert_eq!(parse_integer_param("500mever").unwrap(), "500000000");
+        assert_eq!(parse_integer_param("2micro").unwrap(), "2000");
+        assert_eq!(parse_integer_param("0x1ae").unwrap(), "0x1ae");
+        assert_eq!(parse_integer_param("123").unwrap(), "123");
+        let err = parse_integer_param("1kever").unwrap_err();
+        assert!(err.contains("unknown unit \"kever\""));
+        assert!(err.contains("nano"));
+        assert!(parse_integer_param("1.5nano").is_err());
+    }
+
+    #[test]
+    fn test_parse_integer_value_range() {
+        let value = (BigInt::one() << 200u32).to_string();
+        assert_eq!(parse_integer_value(&ParamType::Uint(256), &value).unwrap(), value);
+        assert_eq!(parse_integer_value(&ParamType::Uint(8), "0xff").unwrap(), "255");
+        assert_eq!(parse_integer_value(&ParamType::Int(8), "-128").unwrap(), "-128");
+        assert_eq!(parse_integer_value(&ParamType::Uint(128), "1ever").unwrap(), "1000000000");
+
+        let err = parse_integer_value(&ParamType::Uint(8), "256").unwrap_err();
+        assert_eq!(err, "value 256 is out of range of type uint8");
+        assert!(parse_integer_value(&ParamType::Uint(8), "-1").is_err());
+        assert!(parse_integer_value(&ParamType::Int(8), "128").is_err());
+        assert!(parse_integer_value(&ParamType::Int(8), "-129").is_err());
+        assert!(parse_integer_value(&ParamType::Uint(256), &(BigInt::one() << 256u32).to_string()).is_err());
+
+        let array = ParamType::Array(Box::new(ParamType::Uint(8)));
+        let enums = EnumValues::new();
+        assert_eq!(parse_param_value(&array, "[1,0x10]".to_owned(), &enums).unwrap(), json!(["1", "16"]));
+        assert!(parse_param_value(&array, "[1,300]".to_owned(), &enums).is_err());
+    }
+
+    #[test]
+    fn test_parse_array_param_non_integer() {
+        let enums = EnumValues::new();
+        let array = ParamType::Array(Box::new(ParamType::Bool));
+        assert_eq!(parse_param_value(&array, "[true,false]".to_owned(), &enums).unwrap(), json!(["true", "false"]));
+
+        // elements ending with a hex letter are not taken for amounts with units
+        let array = ParamType::Array(Box::new(ParamType::Address));
+        let addr1 = format!("0:{}def", "1".repeat(61));
+        let addr2 = format!("-1:{}", "a".repeat(64));
+        assert_eq!(
+            parse_param_value(&array, format!(r#"["{}",{}]"#, addr1, addr2), &enums).unwrap(),
+            json!([addr1, addr2]),
+        );
+    }
+
+    #[test]
+    fn test_parse_integer_param_separators() {
+        assert_eq!(parse_integer_param("1,000,000.5T").unwrap(), "1000000500000000");
+        assert_eq!(parse_integer_param("\"1 000T\"").unwrap(), "1000000000000");
+        assert_eq!(parse_integer_param("1 000 000.000 000 001T").unwrap(), "1000000000000001");
+        assert_eq!(parse_integer_param("1.5T").unwrap(), "1500000000");
+        assert!(parse_integer_param("1,5T").is_err());
+        assert!(parse_integer_param("1,0000T").is_err());
+        assert!(parse_integer_param("1.000,5T").is_err());
+        assert!(parse_integer_param("1..5T").is_err());
+    }
+
+    #[test]
+    fn test_load_error_map() {
+        let path = "test_load_error_map.json";
+        std::fs::write(path, r#"{"101": "Not enough balance", " 102 ": "Not an owner"}"#).unwrap();
+        let map = load_error_map(path).unwrap();
+        assert_eq!(map.get(&101).map(|s| s.as_str()), Some("Not enough balance"));
+        assert_eq!(map.get(&102).map(|s| s.as_str()), Some("Not an owner"));
+        assert!(map.get(&103).is_none());
+
+        std::fs::write(path, r#"{"abc": "message"}"#).unwrap();
+        assert!(load_error_map(path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_decode_call_parameters_offline() {
+        let msg_boc = std::fs::read("tests/samples/wallet.boc").unwrap();
+        let msg = EncodedMessage {
+            message_id: String::new(),
+            message: base64::encode(&msg_boc),
+            expire: None,
+            address: String::new(),
+        };
+        let abi = load_abi("tests/samples/wallet.abi.json", &Config::default()).await.unwrap();
+        let (method, params) = decode_call_parameters_offline(&msg, abi).await.unwrap();
+        assert_eq!(method, "sendTransaction");
+        let params: Value = serde_json::from_str(&params).unwrap();
+        assert!(params.get("dest").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_output_message() {
+        let path = "test_output_message.boc_tmp";
+        let config = Config {
+            output_message: Some(path.to_owned()),
+            ..Default::default()
+        };
+        let address = "0:ece57bcc6c530283becbbd8a3b24d3c5987cdddc3c8b7b33be6e4a6312490415";
+        let result = call_contract_with_client(
+            create_client_local().unwrap(),
+            &config,
+            address,
+            "tests/samples/giver_v2.abi.json",
+            "sendTransaction",
+            &format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, address),
+            Some("tests/samples/giver_v2.key".to_owned()),
+            false,
+            None,
+        ).await.unwrap();
+
+        let boc = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        let msg = EncodedMessage {
+            message_id: result.output["message_id"].as_str().unwrap().to_owned(),
+            message: boc,
+            expire: None,
+            address: address.to_owned(),
+        };
+        let packed = hex::encode(pack_message(&msg, "sendTransaction", false).unwrap());
+        let (msg, method) = unpack_message(&packed).unwrap();
+        assert_eq!(method, "sendTransaction");
+        let abi = load_abi("tests/samples/giver_v2.abi.json", &config).await.unwrap();
+        let (method, params) = decode_call_parameters_offline(&msg, abi).await.unwrap();
+        assert_eq!(method, "sendTransaction");
+        let params: Value = serde_json::from_str(&params).unwrap();
+        assert_eq!(params["dest"], address);
+    }
+
+    #[tokio::test]
+    async fn test_call_with_nonce() {
+        let abi_str = r#"{
+            "ABI version": 2,
+            "version": "2.2",
+            "header": ["time", "expire"],
+            "functions": [{
+                "name": "submit",
+                "inputs": [{"name":"nonce","type":"uint64"}, {"name":"value","type":"uint128"}],
+                "outputs": []
+            }, {
+                "name": "ping",
+                "inputs": [],
+                "outputs": []
+            }],
+            "data": [],
+            "events": []
+        }"#;
+        let abi = Abi::Json(abi_str.to_owned());
+        let address = "0:ece57bcc6c530283becbbd8a3b24d3c5987cdddc3c8b7b33be6e4a6312490415";
+        let ton = create_client_local().unwrap();
+        let msg_params = prepare_message_params(
+            address, abi.clone(), "submit", r#"{"value":100}"#, None, None, None, Some(42),
+        ).unwrap();
+        let msg = encode_message(ton, msg_params).await.unwrap();
+        let msg = EncodedMessage {
+            message_id: msg.message_id,
+            message: msg.message,
+            expire: None,
+            address: address.to_owned(),
+        };
+        let (method, params) = decode_call_parameters_offline(&msg, abi.clone()).await.unwrap();
+        assert_eq!(method, "submit");
+        let params: Value = serde_json::from_str(&params).unwrap();
+        assert_eq!(params["nonce"], "42");
+
+        assert!(prepare_message_params(
+            address, abi.clone(), "submit", r#"{"nonce":41,"value":100}"#, None, None, None, Some(42),
+        ).is_err());
+        assert!(prepare_message_params(
+            address, abi, "ping", "{}", None, None, None, Some(42),
+        ).is_err());
+        assert_eq!(parse_nonce_value(&json!("0x2a")), Some(42));
+        assert_eq!(parse_nonce_value(&json!("42")), Some(42));
+    }
+
+    #[test]
+    fn test_message_id() {
+        let msg = load_message_input("tests/samples/wallet.boc").unwrap();
+        let ton = create_client_local().unwrap();
+        let id = message_id(ton.clone(), &msg.message).unwrap();
+        assert_eq!(id, msg.message_id);
+        assert_eq!(id.len(), 64);
+        assert!(message_id(ton, "not a boc").is_err());
+    }
+
+    #[test]
+    fn test_transaction_lt() {
+        assert_eq!(parse_lt(&json!("0x2a")), 42);
+        assert_eq!(parse_lt(&json!("42")), 42);
+        assert_eq!(parse_lt(&json!(42)), 42);
+        assert_eq!(parse_lt(&Value::Null), 0);
+        let stale = json!({ "id": "a", "lt": "0x10" });
+        let fresh = json!({ "id": "b", "lt": "0x20" });
+        assert!(transaction_lt(&stale) <= 0x10);
+        assert!(transaction_lt(&fresh) > 0x10);
+    }
+
+    #[test]
+    fn test_decode_output_types() {
+        let abi = ton_abi::Contract::load(r#"{
+            "ABI version": 2,
+            "version": "2.2",
+            "header": ["time"],
+            "functions": [{
+                "name": "getBalance",
+                "inputs": [],
+                "outputs": [
+                    {"name": "balance", "type": "uint128"},
+                    {"name": "count", "type": "uint32"},
+                    {"name": "comment", "type": "bytes"}
+                ]
+            }],
+            "events": [],
+            "data": []
+        }"#.as_bytes()).unwrap();
+        let output = json!({
+            "balance": "1500000000",
+            "count": "7",
+            "comment": hex::encode("hello"),
+        });
+        let output = decode_output_types(&abi, "getBalance", output).unwrap();
+        assert_eq!(output["balance"], json!({ "nano": "1500000000", "tokens": "1.500000000" }));
+        assert_eq!(output["count"], "7");
+        assert_eq!(output["comment"], "hello");
+    }
+
+    #[test]
+    fn test_load_message_input() {
+        let path = "tests/samples/wallet.boc";
+        let boc = std::fs::read(path).unwrap();
+        let from_hex = load_message_input(&hex::encode(&boc)).unwrap();
+        let from_base64 = load_message_input(&base64::encode(&boc)).unwrap();
+        let from_file = load_message_input(path).unwrap();
+        for msg in [&from_base64, &from_file] {
+            assert_eq!(msg.message_id, from_hex.message_id);
+            assert_eq!(msg.message, from_hex.message);
+            assert_eq!(msg.address, from_hex.address);
+        }
+        assert_eq!(from_hex.message, base64::encode(&boc));
+
+        let packed = hex::encode(pack_message(&from_hex, "sendTransaction", false).unwrap());
+        assert_eq!(load_message_input(&packed).unwrap().message, from_hex.message);
+
+        assert!(load_message_input("not a message").unwrap_err().contains("expected output of the message command"));
+        assert!(load_message_input("abcd").unwrap_err().contains("not a valid message BOC"));
+    }
+
+    #[tokio::test]
+    async fn test_decode_with_multiple_abis() {
+        let msg_boc = std::fs::read("tests/samples/wallet.boc").unwrap();
+        let msg = EncodedMessage {
+            message_id: String::new(),
+            message: base64::encode(&msg_boc),
+            expire: None,
+            address: String::new(),
+        };
+        let config = Config::default();
+        let abis = vec![
+            load_abi("tests/samples/sample1.abi.json", &config).await.unwrap(),
+            load_abi("tests/samples/wallet.abi.json", &config).await.unwrap(),
+        ];
+        let (index, _, method, _) = decode_call_parameters_with_abis(&msg, &abis).await.unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(method, "sendTransaction");
+
+        let err = decode_call_parameters_with_abis(&msg, &abis[..1]).await;
+        assert!(err.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tuple_params() {
+        let abi = r#"{
+            "ABI version": 2,
+            "version": "2.2",
+            "header": ["time"],
+            "functions": [{
+                "name": "setTuple",
+                "inputs": [
+                    {"name": "t", "type": "tuple", "components": [
+                        {"name": "a", "type": "uint32"},
+                        {"name": "b", "type": "address"}
+                    ]},
+                    {"name": "c", "type": "uint8"}
+                ],
+                "outputs": []
+            }],
+            "data": [],
+            "events": [],
+            "fields": []
+        }"#;
+        let config = Config::default();
+        let addr = format!("0:{}", "1".repeat(64));
+        let params = build_json_from_params(
+            vec!["--t.a", "5", "--t.b", &addr, "--c", "1"], abi, "setTuple", &config
+        ).await.unwrap();
+        let params: Value = serde_json::from_str(&params).unwrap();
+        assert_eq!(params, json!({"t": {"a": "5", "b": addr}, "c": "1"}));
+
+        let err = build_json_from_params(
+            vec!["--t.a", "5", "--t.b", &addr, "--t.x", "1", "--c", "1"], abi, "setTuple", &config
+        ).await.unwrap_err();
+        assert!(err.contains("t.x"));
+
+        let err = build_json_from_params(vec!["--t.a", "5", "--c", "1"], abi, "setTuple", &config)
+            .await.unwrap_err();
+        assert!(err.contains("t.b"));
+    }
+
+    #[tokio::test]
+    async fn test_params_order() {
+        let config = Config::default();
+        let abi_path = "tests/samples/wallet.abi.json";
+        let addr = format!("0:{}", "1".repeat(64));
+        let params = build_json_from_params(
+            vec!["--bounce", "false", "--value", "1000", "--dest", &addr], abi_path, "sendTransaction", &config
+        ).await.unwrap();
+        assert_eq!(params, format!(r#"{{"dest":"{}","value":"1000","bounce":"false"}}"#, addr));
+
+        let abi = load_abi(abi_path, &config).await.unwrap();
+        let header = FunctionHeader {
+            time: Some(1700000000000),
+            expire: Some(1700000060),
+            pubkey: None,
+        };
+        let mut messages = vec![];
+        for _ in 0..2 {
+            let msg_params = prepare_message_params(
+                &addr,
+                abi.clone(),
+                "sendTransaction",
+                &params,
+                Some(header.clone()),
+                Some("tests/samples/test.keys.json".to_owned()),
+                None,
+                None,
+            ).unwrap();
+            let msg = encode_message(create_client_local().unwrap(), msg_params).await.unwrap();
+            messages.push(base64::decode(msg.message).unwrap());
+        }
+        assert_eq!(messages[0], messages[1]);
+    }
+
+    #[tokio::test]
+    async fn test_method_suggestion() {
+        let config = Config::default();
+        let err = build_json_from_params(vec!["-abi", "{}"], "tests/samples/sample1.abi.json", "setAbi", &config)
+            .await.unwrap_err();
+        assert!(err.contains("did you mean `setABI`?"));
+
+        let err = build_json_from_params(vec!["-abi", "{}"], "tests/samples/sample1.abi.json", "unknownFunction", &config)
+            .await.unwrap_err();
+        assert!(err.contains("Available functions:"));
+        assert!(err.contains("setABI"));
+    }
+
+    #[tokio::test]
+    async fn test_enum_params() {
+        let abi = r#"{
+            "ABI version": 2,
+            "version": "2.2",
+            "header": ["time", "expire"],
+            "functions": [{
+                "name": "setStatus",
+                "inputs": [
+                    {"name": "status", "type": "uint8"},
+                    {"name": "history", "type": "uint8[]"}
+                ],
+                "outputs": []
+            }],
+            "data": [],
+            "events": [],
+            "fields": [],
+            "enums": {"Status": {"Inactive": 0, "Active": 2}}
+        }"#;
+        async fn encode_body(ton: TonClient, abi: &Abi, params: &str) -> String {
+            let addr = format!("0:{}", "1".repeat(64));
+            let header = FunctionHeader { time: Some(1700000000000), expire: Some(1700000060), pubkey: None };
+            let msg_params = prepare_message_params(
+                &addr, abi.clone(), "setStatus", params, Some(header), None, None, None,
+            ).unwrap();
+            encode_message(ton, msg_params).await.unwrap().message
+        }
+        let mut config = Config::default();
+        let ton = create_client_local().unwrap();
+        let abi_obj = load_abi(abi, &config).await.unwrap();
+
+        let params = build_json_from_params(
+            vec!["--status", "Status.Active", "--history", "[Status.Inactive,1]"], abi, "setStatus", &config
+        ).await.unwrap();
+        assert_eq!(params, r#"{"status":"2","history":["0","1"]}"#);
+        let numeric = build_json_from_params(
+            vec!["--status", "2", "--history", "[0,1]"], abi, "setStatus", &config
+        ).await.unwrap();
+        assert_eq!(params, numeric);
+        // the variant is encoded as its integer value
+        assert_eq!(
+            encode_body(ton.clone(), &abi_obj, &params).await,
+            encode_body(ton.clone(), &abi_obj, r#"{"status":2,"history":[0,1]}"#).await,
+        );
+        assert_ne!(
+            encode_body(ton.clone(), &abi_obj, &params).await,
+            encode_body(ton.clone(), &abi_obj, r#"{"status":0,"history":[0,1]}"#).await,
+        );
+
+        let err = build_json_from_params(vec!["--status", "Status.Unknown", "--history", "[]"], abi, "setStatus", &config)
+            .await.unwrap_err();
+        assert!(err.contains("Status.Unknown"));
+
+        let enums_path = "test_enum_params.json";
+        std::fs::write(enums_path, r#"{"Status": {"Active": 3}}"#).unwrap();
+        config.enums = Some(enums_path.to_owned());
+        let params = build_json_from_params(
+            vec!["--status", "Status.Active", "--history", "[]"], abi, "setStatus", &config
+        ).await;
+        std::fs::remove_file(enums_path).unwrap();
+        let params = params.unwrap();
+        assert_eq!(params, r#"{"status":"3","history":[]}"#);
+        assert_eq!(
+            encode_body(ton.clone(), &abi_obj, &params).await,
+            encode_body(ton, &abi_obj, r#"{"status":3,"history":[]}"#).await,
+        );
+    }
+
+    #[test]
+    fn test_output_drift() {
+        let emulated = json!({"value0": "1", "info": {"owner": "0:1", "flags": [true, false]}});
+        let mut diff = vec![];
+        output_drift("output", &emulated, &emulated.clone(), &mut diff);
+        assert!(diff.is_empty());
+
+        // the state was changed between the emulation and the execution
+        let onchain = json!({"value0": "2", "info": {"owner": "0:1", "flags": [true, true]}, "extra": 1});
+        output_drift("output", &emulated, &onchain, &mut diff);
+        assert_eq!(diff, vec![
+            r#"output.extra: null -> 1"#,
+            r#"output.info.flags[1]: false -> true"#,
+            r#"output.value0: "1" -> "2""#,
+        ]);
+    }
+
+    #[test]
+    fn test_drift_warning() {
+        let emulated = json!({"value0": "1"});
+        // the state of the account was changed after the local run
+        let onchain = CallResult {
+            output: json!({"value0": "2"}),
+            transaction_id: Some("a".repeat(64)),
+            transaction: Some(json!({"id": "a".repeat(64)})),
+            ..Default::default()
+        };
+        let warning = drift_warning(&emulated, &onchain).unwrap();
+        assert!(warning.contains(r#"output.value0: "1" -> "2""#));
+
+        let same = CallResult { output: emulated.clone(), ..onchain.clone() };
+        assert_eq!(drift_warning(&emulated, &same), None);
+
+        // the async call which was not awaited
+        let sent = CallResult {
+            output: json!({"message_id": "b".repeat(64), "message": "te6", "shard_block_id": "c".repeat(64)}),
+            ..Default::default()
+        };
+        assert_eq!(drift_warning(&emulated, &sent), None);
+
+        // the transaction without the decoded output
+        let no_output = CallResult { output: Value::Null, ..onchain };
+        assert_eq!(drift_warning(&emulated, &no_output), None);
+    }
+
+    #[test]
+    fn test_compute_phase_stats() {
+        let transaction = json!({"compute": {"gas_used": 2891, "vm_steps": 67}});
+        assert_eq!(compute_phase_stats(&transaction), (Some(2891), Some(67)));
+        let transaction = json!({"compute": {"gas_used": "0xb4b", "vm_steps": "67"}});
+        assert_eq!(compute_phase_stats(&transaction), (Some(2891), Some(67)));
+        assert_eq!(compute_phase_stats(&json!({"compute": {"skipped_reason": 0}})), (None, None));
+    }
+
+    #[tokio::test]
+    async fn test_interactive_params() {
+        let config = Config::default();
+        let abi = load_ton_abi("tests/samples/wallet.abi.json", &config).await.unwrap();
+        let inputs = abi.functions()["sendTransaction"].input_params();
+        let addr = format!("0:{}", "1".repeat(64));
+        let enums = EnumValues::new();
+
+        let mut input = std::io::Cursor::new("1T\nfalse\n");
+        let params = build_params_json(&["--dest", &addr], inputs, &enums, Some(&mut input)).unwrap();
+        assert_eq!(params, format!(r#"{{"dest":"{}","value":"1000000000","bounce":"false"}}"#, addr));
+
+        // the input ends before all arguments are entered
+        let mut input = std::io::Cursor::new("1000\n");
+        let err = build_params_json(&["--dest", &addr], inputs, &enums, Some(&mut input)).unwrap_err();
+        assert!(err.contains("bounce"));
+
+        let err = build_params_json(&["--dest", &addr], inputs, &enums, None).unwrap_err();
+        assert!(err.contains("value"));
+    }
+
+    #[test]
+    fn test_exit_arg() {
+        let transaction = json!({"id": "ab", "compute": {"exit_code": 105, "exit_arg": 77}});
+        assert_eq!(compute_exit_arg(&transaction), Some(77));
+        assert_eq!(compute_exit_arg(&json!({"compute": {"exit_arg": "-5"}})), Some(-5));
+        assert_eq!(compute_exit_arg(&json!({"compute": {"exit_code": 0}})), None);
+
+        let result = CallResult { exit_code: Some(105), exit_arg: compute_exit_arg(&transaction), ..Default::default() };
+        let result = serde_json::to_value(&result).unwrap();
+        assert_eq!(result["exit_code"], json!(105));
+        assert_eq!(result["exit_arg"], json!(77));
+
+        let mut e = ClientError::with_code_message(414, "Contract execution was terminated with error: Unknown error, exit code: 105.".to_owned());
+        e.data = json!({"exit_code": 105, "exit_arg": 77});
+        add_exit_arg(&mut e);
+        assert_eq!(e.message, "Contract execution was terminated with error: Unknown error, exit code: 105, exit argument: 77");
+        add_exit_arg(&mut e);
+        assert!(e.message.ends_with("exit argument: 77"));
+        assert!(serde_json::to_string(&e).unwrap().contains(r#""exit_arg":77"#));
+    }
+
+    #[test]
+    fn test_signature_hint() {
+        let mut e = ClientError::with_code_message(414, "Contract execution was terminated with error: Unknown error, exit code: 40.".to_owned());
+        e.data = json!({"exit_code": 40});
+        add_signature_hint(&mut e, true);
+        assert!(e.message.starts_with("the provided signing key does not match the contract's required public key"));
+        assert!(e.message.contains("exit code: 40"));
+        add_signature_hint(&mut e, true);
+        assert_eq!(e.message.matches("--sign").count(), 1);
+
+        let mut e = ClientError::with_code_message(414, "exit code: 40".to_owned());
+        e.data = json!({"exit_code": 40});
+        add_signature_hint(&mut e, false);
+        assert!(e.message.starts_with("the contract requires a signed message"));
+
+        let mut e = ClientError::with_code_message(414, "exit code: 100".to_owned());
+        e.data = json!({"exit_code": 100});
+        add_signature_hint(&mut e, true);
+        assert_eq!(e.message, "exit code: 100");
+    }
+
+    #[tokio::test]
+    async fn test_env_params() {
+        let var = |name: &str| (name == "DEST").then(|| "0:1234".to_owned());
+        assert_eq!(expand_env_vars("${DEST}/${DEST}", false, var).unwrap(), "0:1234/0:1234");
+        assert_eq!(expand_env_vars("$DEST ${} ${A-B}", false, var).unwrap(), "$DEST ${} ${A-B}");
+        assert!(expand_env_vars("${MISSING}", false, var).unwrap_err().contains("MISSING"));
+        assert_eq!(expand_env_vars("x${MISSING}y", true, var).unwrap(), "x${MISSING}y");
+        assert_eq!(expand_env_vars("$${DEST} ${DEST}", false, var).unwrap(), "${DEST} 0:1234");
+
+        // the value of the variable stays inside the json string
+        let var = |name: &str| (name == "DEST").then(|| r#"0:1","value":"999"#.to_owned());
+        let params = expand_env_params(r#"{"dest":"${DEST}","items":["${DEST}"]}"#, false, var).unwrap();
+        let params: Value = serde_json::from_str(&params).unwrap();
+        assert_eq!(params, json!({"dest": r#"0:1","value":"999"#, "items": [r#"0:1","value":"999"#]}));
+        assert_eq!(expand_env_params(r#"{"dest":"$${DEST}"}"#, false, var).unwrap(), r#"{"dest":"${DEST}"}"#);
+        assert_eq!(expand_env_params("${DEST}", false, var).unwrap(), r#"0:1","value":"999"#);
+
+        let addr = format!("0:{}", "2".repeat(64));
+        std::env::set_var("EVS_CLI_TEST_ENV_PARAMS_DEST", &addr);
+        let config = Config::default();
+        let params = parse_params(
+            vec!["--dest", "${EVS_CLI_TEST_ENV_PARAMS_DEST}", "--value", "1", "--bounce", "false"],
+            "tests/samples/wallet.abi.json", "sendTransaction", &config,
+        ).await.unwrap();
+        assert_eq!(params, format!(r#"{{"dest":"{}","value":"1","bounce":"false"}}"#, addr));
+        let params = parse_params(
+            vec![r#"{"dest":"${EVS_CLI_TEST_ENV_PARAMS_DEST}"}"#], "tests/samples/wallet.abi.json", "sendTransaction", &config,
+        ).await.unwrap();
+        assert_eq!(params, format!(r#"{{"dest":"{}"}}"#, addr));
+    }
+
+    #[tokio::test]
+    async fn test_decode_event_message() {
+        let abi = r#"{
+            "ABI version": 2,
+            "version": "2.2",
+            "header": ["time", "expire"],
+            "functions": [],
+            "events": [{"name": "Transfer", "id": "0x12345678", "inputs": [{"name": "value", "type": "uint64"}]}],
+            "data": [],
+            "fields": []
+        }"#;
+        let mut body = BuilderData::default();
+        body.append_u32(0x12345678).unwrap();
+        body.append_u64(1000).unwrap();
+        let src = ton_block::MsgAddressInt::from_str(&format!("0:{}", "1".repeat(64))).unwrap();
+        let header = ton_block::ExtOutMessageHeader::with_addresses(src, MsgAddressExt::AddrNone);
+        let message = Message::with_ext_out_header_and_body(header, SliceData::load_builder(body).unwrap());
+        let boc = ton_types::write_boc(&message.serialize().unwrap()).unwrap();
+        let msg = encoded_message_from_boc(&boc).unwrap();
+
+        let abis = vec![Abi::Json(abi.to_owned())];
+        let (_, body_type, name, fields) = decode_call_parameters_with_abis(&msg, &abis).await.unwrap();
+        assert!(matches!(body_type, MessageBodyType::Event));
+        assert_eq!(name, "Transfer");
+        assert_eq!(serde_json::from_str::<Value>(&fields).unwrap(), json!({"value": "1000"}));
+
+        // the event is printed but never sent
+        let abi_path = "test_decode_event_message.abi.json";
+        std::fs::write(abi_path, abi).unwrap();
+        let config = Config { is_json: true, ..Config::default() };
+        let result = call_contract_with_msg(&config, base64::encode(&boc), &[abi_path.to_owned()], false).await;
+        std::fs::remove_file(abi_path).unwrap();
+        assert!(result.unwrap_err().contains("event Transfer"));
+    }
+
+    #[test]
+    fn test_json_result_format() {
+        let result = json!({"value0": "1"});
+        let pretty = "{\n  \"value0\": \"1\"\n}";
+        let compact = r#"{"value0":"1"}"#;
+        for (is_json, pretty_json, expected) in [
+            (true, None, compact),
+            (false, None, pretty),
+            (true, Some(true), pretty),
+            (false, Some(true), pretty),
+            (true, Some(false), compact),
+            (false, Some(false), compact),
+        ] {
+            let config = Config { is_json, pretty_json, ..Config::default() };
+            assert_eq!(format_json_result(&result, &config), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_out_messages() {
+        // a router transaction sending two internal messages: the first one is a call of the wallet,
+        // the second one has a body unknown to the ABIs
+        let ton = create_client_local().unwrap();
+        let config = Config::default();
+        let wallet_abi = load_abi("tests/samples/wallet.abi.json", &config).await.unwrap();
+        let wallet = format!("0:{}", "1".repeat(64));
+        let other = format!("0:{}", "2".repeat(64));
+        let call = encode_internal_message(
+            ton.clone(),
+            ParamsOfEncodeInternalMessage {
+                abi: Some(wallet_abi.clone()),
+                address: Some(wallet.clone()),
+                call_set: CallSet::some_with_function_and_input(
+                    "sendTransaction",
+                    json!({"dest": other, "value": 5, "bounce": false}),
+                ),
+                value: "1000".to_owned(),
+                ..Default::default()
+            },
+        ).unwrap().message;
+        let transfer = encode_internal_message(
+            ton.clone(),
+            ParamsOfEncodeInternalMessage {
+                address: Some(other.clone()),
+                value: "2000".to_owned(),
+                ..Default::default()
+            },
+        ).unwrap().message;
+
+        let abis = vec![load_abi("tests/samples/giver_v2.abi.json", &config).await.unwrap(), wallet_abi];
+        let out_messages = decode_out_messages(&[call, transfer.clone()], &abis).await.unwrap();
+        assert_eq!(out_messages.len(), 2);
+        assert_eq!(out_messages[0]["dst"], json!(wallet));
+        assert_eq!(out_messages[0]["value"], json!("1000"));
+        assert_eq!(out_messages[0]["function"], json!("sendTransaction"));
+        assert_eq!(out_messages[0]["body"]["dest"], json!(other));
+        assert_eq!(out_messages[1]["dst"], json!(other));
+        assert_eq!(out_messages[1]["value"], json!("2000"));
+        assert_eq!(out_messages[1]["boc"], json!(transfer));
+        assert!(out_messages[1].get("function").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tonconnect_request() {
+        let config = Config::default();
+        let address = format!("0:{}", "1".repeat(64));
+        let params = format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, address);
+        let request = tonconnect_request(
+            &config, &address, "tests/samples/giver_v2.abi.json", "sendTransaction", &params, "1500000000", None,
+        ).await.unwrap();
+        let message = &request["messages"][0];
+        assert_eq!(message["address"], json!(address));
+        assert_eq!(message["amount"], json!("1500000000"));
+        assert!(!message["payload"].as_str().unwrap().is_empty());
+        assert!(request["valid_until"].as_u64().unwrap() > now() as u64);
+
+        let err = tonconnect_request(
+            &config, &address, "tests/samples/giver_v2.abi.json", "sendTransaction", &params, "0", None,
+        ).await.unwrap_err();
+        assert!(err.contains("must be positive"));
+        assert!(tonconnect_request(
+            &config, "0:12", "tests/samples/giver_v2.abi.json", "sendTransaction", &params, "1", None,
+        ).await.is_err());
+    }
+
+    #[test]
+    fn test_repeated_call_stats() {
+        let call = |id: &str| CallResult { transaction_id: Some(id.to_owned()), ..Default::default() };
+        let results = vec![
+            (Duration::from_millis(30), Ok(call("a"))),
+            (Duration::from_millis(10), Err("failed".to_owned())),
+            (Duration::from_millis(20), Ok(call("b"))),
+        ];
+        assert_eq!(repeated_call_stats(&results), RepeatedCallStats {
+            count: 3,
+            succeeded: 2,
+            failed: 1,
+            min_ms: 10,
+            max_ms: 30,
+            avg_ms: 20,
+            transactions: vec!["a".to_owned(), "b".to_owned()],
+        });
+        assert_eq!(repeated_call_stats(&[]).avg_ms, 0);
+    }
+}
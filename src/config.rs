@@ -67,6 +67,10 @@ fn default_endpoints_map() -> BTreeMap<String, Vec<String>> {
 
 fn default_trace() -> String { "None".to_string() }
 
+fn default_confirm_yes() -> String { "y".to_string() }
+
+fn default_confirm_no() -> String { "n".to_string() }
+
 fn default_config() -> Config {
     Config::new()
 }
@@ -114,8 +118,160 @@ pub struct Config {
     pub access_key: Option<String>,
     ////////////////////////////////
 
+    /// External program used by DeBot signing boxes instead of local keys.
+    pub signing_command: Option<String>,
+
+    // Giver used to prepay accounts before deploy
+    pub giver_address: Option<String>,
+    pub giver_abi: Option<String>,
+    pub giver_keys: Option<String>,
+
+    /// Contract used by DeBot address input to resolve names into addresses.
+    pub address_resolver: Option<String>,
+
     #[serde(default = "default_endpoints")]
     pub endpoints: Vec<String>,
+
+    // Per-invocation flags, not stored in the config file
+    #[serde(skip)]
+    pub dry_run: bool,
+    /// Confirmation before sending a call: `None` - ask only in terminal, `Some(false)` - never ask,
+    /// `Some(true)` - always ask.
+    #[serde(skip)]
+    pub confirm: Option<bool>,
+    /// Path to the file where all DeBot interface calls are logged.
+    #[serde(skip)]
+    pub debot_log: Option<String>,
+    /// Timeout in seconds for a single user input in DeBot interfaces.
+    #[serde(skip)]
+    pub debot_input_timeout: Option<u64>,
+    /// Names or ids of DeBot interfaces which are not provided to DeBots.
+    #[serde(skip)]
+    pub disabled_interfaces: Vec<String>,
+    /// Maximum number of DeBot interface calls in one session.
+    #[serde(skip)]
+    pub debot_max_steps: Option<u64>,
+    /// Timeout in seconds for the whole DeBot session.
+    #[serde(skip)]
+    pub debot_session_timeout: Option<u64>,
+    /// Fixed `time` header (in milliseconds) for the call message.
+    #[serde(skip)]
+    pub message_time: Option<u64>,
+    /// Path to the file where the signed call message is saved instead of sending it.
+    #[serde(skip)]
+    pub output_message: Option<String>,
+    /// Reformat decoded output according to the ABI types.
+    #[serde(skip)]
+    pub decode_types: bool,
+    /// Name of the getter used to check that the signing key matches the contract key.
+    #[serde(skip)]
+    pub verify_key: Option<String>,
+    /// Print the undecoded transaction instead of the decoded call output.
+    #[serde(skip)]
+    pub raw_transaction: bool,
+    /// Value in nanotokens sent from the giver to the account before deploy.
+    #[serde(skip)]
+    pub prepay: Option<String>,
+    /// Path to the json file mapping contract exit codes to error messages.
+    #[serde(skip)]
+    pub error_map: Option<String>,
+    /// Estimate fees against the real account balance instead of the unlimited one.
+    #[serde(skip)]
+    pub real_balance: bool,
+    /// Path to the config contract account boc used by the debug executor instead of the network one.
+    #[serde(skip)]
+    pub config_boc: Option<String>,
+    /// Source address of the internal message used to emulate the call instead of an external one.
+    #[serde(skip)]
+    pub emulate_src: Option<String>,
+    /// Value in nanotokens attached to the emulated internal message.
+    #[serde(skip)]
+    pub emulate_value: Option<String>,
+    /// Idempotency key of the call: the call is skipped if it was already sent with this key.
+    #[serde(skip)]
+    pub once: Option<String>,
+    /// Path to the file with the calls sent with idempotency keys.
+    #[serde(skip)]
+    pub once_ledger: String,
+    /// Value of the `nonce` function parameter pinned by the user.
+    #[serde(skip)]
+    pub nonce: Option<u64>,
+    /// Getter returning the current contract nonce, used to validate `nonce` before sending.
+    #[serde(skip)]
+    pub nonce_getter: Option<String>,
+    /// Logical time after which the transaction of the sent message is expected. `Some(None)` means
+    /// the last transaction lt of the account captured before sending.
+    #[serde(skip)]
+    pub since_lt: Option<Option<u64>>,
+    /// Path to the file with library cells used in local emulation and debug.
+    #[serde(skip)]
+    pub libs: Option<String>,
+    /// All keys specified with repeated `--sign`: the call is signed by each of them.
+    #[serde(skip)]
+    pub signers: Vec<String>,
+    /// Wait for the transaction and decode the output even if `async_call` is enabled.
+    #[serde(skip)]
+    pub await_result: bool,
+    /// Path to the file where the result json is written.
+    #[serde(skip)]
+    pub json_output_file: Option<String>,
+    /// Path where the debug trace is saved instead of the default per-call log file.
+    #[serde(skip)]
+    pub trace_output: Option<String>,
+    /// Maximum time in seconds to wait for the transaction of the sent message.
+    #[serde(skip)]
+    pub wait_timeout: Option<u64>,
+    /// Path to the json file with enum values which can be passed by name (`Status.Active`) as integer arguments.
+    #[serde(skip)]
+    pub enums: Option<String>,
+    /// Name of the function called on deploy instead of `constructor`.
+    #[serde(skip)]
+    pub constructor: Option<String>,
+    /// Compare the output of the local run with the onchain result and warn if they differ.
+    #[serde(skip)]
+    pub warn_drift: bool,
+    /// Validity period in seconds of the message saved for later submission, used instead of the lifetime.
+    #[serde(skip)]
+    pub message_ttl: Option<u32>,
+    /// Network DeBot interface is available only when explicitly allowed.
+    #[serde(skip)]
+    pub debot_allow_network: bool,
+    /// Prompt for the function arguments missing in the command line.
+    #[serde(skip)]
+    pub interactive: bool,
+    /// HTTP headers (`Name: value`) for the endpoint requests.
+    #[serde(skip)]
+    pub headers: Vec<String>,
+    /// Fail the call if the decoded output is empty.
+    #[serde(skip)]
+    pub fail_on_empty_output: bool,
+    /// Query the blockchain config from the network even if a fresh cached copy exists.
+    #[serde(skip)]
+    pub refresh_config: bool,
+    /// Token accepted as an affirmative answer by the DeBot ConfirmInput interface.
+    #[serde(skip, default = "default_confirm_yes")]
+    pub debot_confirm_yes: String,
+    /// Token accepted as a negative answer by the DeBot ConfirmInput interface.
+    #[serde(skip, default = "default_confirm_no")]
+    pub debot_confirm_no: String,
+    /// Directory where the account state is saved before (before.boc) and after (after.boc) the call.
+    #[serde(skip)]
+    pub account_snapshot: Option<String>,
+    /// Keep `${VAR}` references to undefined environment variables in the parameters instead of failing.
+    #[serde(skip)]
+    pub allow_undefined_env: bool,
+    /// Serialize results pretty (`--pretty`) or compact (`--compact`). By default results are compact in json mode and pretty otherwise.
+    #[serde(skip)]
+    pub pretty_json: Option<bool>,
+    /// ABIs (`--out-abi`) used to decode the internal out messages of calls and get-methods.
+    #[serde(skip)]
+    pub out_abis: Vec<String>,
+    /// Maximum estimated fee in nanotokens (`--max-fee`), the call is aborted if the emulated fee exceeds it.
+    #[serde(skip)]
+    pub max_fee: Option<u64>,
+    /// Amount in nanotokens of the TON Connect request (`--tonconnect`) printed instead of sending the call.
+    #[serde(skip)]
+    pub tonconnect_amount: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -164,6 +320,57 @@ impl Default for Config {
             debug_fail: default_trace(),
             project_id: None,
             access_key: None,
+            signing_command: None,
+            giver_address: None,
+            giver_abi: None,
+            giver_keys: None,
+            address_resolver: None,
+            dry_run: false,
+            confirm: None,
+            debot_log: None,
+            debot_input_timeout: None,
+            disabled_interfaces: vec![],
+            debot_max_steps: None,
+            debot_session_timeout: None,
+            message_time: None,
+            output_message: None,
+            decode_types: false,
+            verify_key: None,
+            raw_transaction: false,
+            prepay: None,
+            error_map: None,
+            real_balance: false,
+            config_boc: None,
+            emulate_src: None,
+            emulate_value: None,
+            once: None,
+            once_ledger: String::new(),
+            nonce: None,
+            nonce_getter: None,
+            since_lt: None,
+            libs: None,
+            signers: vec![],
+            await_result: false,
+            json_output_file: None,
+            trace_output: None,
+            wait_timeout: None,
+            enums: None,
+            constructor: None,
+            warn_drift: false,
+            message_ttl: None,
+            debot_allow_network: false,
+            interactive: false,
+            headers: vec![],
+            fail_on_empty_output: false,
+            refresh_config: false,
+            debot_confirm_yes: default_confirm_yes(),
+            debot_confirm_no: default_confirm_no(),
+            account_snapshot: None,
+            allow_undefined_env: false,
+            pretty_json: None,
+            out_abis: vec![],
+            max_fee: None,
+            tonconnect_amount: None,
         }
     }
 }
@@ -208,6 +415,57 @@ impl Config {
             debug_fail: default_trace(),
             project_id: None,
             access_key: None,
+            signing_command: None,
+            giver_address: None,
+            giver_abi: None,
+            giver_keys: None,
+            address_resolver: None,
+            dry_run: false,
+            confirm: None,
+            debot_log: None,
+            debot_input_timeout: None,
+            disabled_interfaces: vec![],
+            debot_max_steps: None,
+            debot_session_timeout: None,
+            message_time: None,
+            output_message: None,
+            decode_types: false,
+            verify_key: None,
+            raw_transaction: false,
+            prepay: None,
+            error_map: None,
+            real_balance: false,
+            config_boc: None,
+            emulate_src: None,
+            emulate_value: None,
+            once: None,
+            once_ledger: String::new(),
+            nonce: None,
+            nonce_getter: None,
+            since_lt: None,
+            libs: None,
+            signers: vec![],
+            await_result: false,
+            json_output_file: None,
+            trace_output: None,
+            wait_timeout: None,
+            enums: None,
+            constructor: None,
+            warn_drift: false,
+            message_ttl: None,
+            debot_allow_network: false,
+            interactive: false,
+            headers: vec![],
+            fail_on_empty_output: false,
+            refresh_config: false,
+            debot_confirm_yes: default_confirm_yes(),
+            debot_confirm_no: default_confirm_no(),
+            account_snapshot: None,
+            allow_undefined_env: false,
+            pretty_json: None,
+            out_abis: vec![],
+            max_fee: None,
+            tonconnect_amount: None,
         }
     }
 }
@@ -306,6 +564,30 @@ impl FullConfig {
         full_config
     }
 
+    /// Applies config layers on top of the stored config: values from the TOML or YAML
+    /// `config_file` and then `TONOSCLI_<PARAMETER>` environment variables. Explicit
+    /// command line flags are applied later and take precedence over both.
+    pub fn apply_layers(&mut self, config_file: &str) -> Result<(), String> {
+        let values = load_config_layer(config_file)?;
+        let mut values = match values {
+            serde_json::Value::Object(values) => values,
+            _ => return Err(format!("config file {} must contain a table of parameters", config_file)),
+        };
+        apply_env_layer(&mut values, |key| std::env::var(key).ok());
+
+        let url = self.config.url.clone();
+        let endpoints = self.config.endpoints.clone();
+        for (key, value) in values {
+            set_stored_parameter(&mut self.config, &key, value)
+                .map_err(|e| format!("{} in config file {}", e, config_file))?;
+        }
+        if self.config.url != url && self.config.endpoints == endpoints {
+            self.config.url = resolve_net_name(&self.config.url).unwrap_or(self.config.url.clone());
+            self.config.endpoints = self.endpoints_map.get(&self.config.url).cloned().unwrap_or_default();
+        }
+        Ok(())
+    }
+
     pub fn to_file(&self, path: &str) -> Result<(), String>{
         let conf_str = serde_json::to_string_pretty(self)
             .map_err(|_| "failed to serialize config object".to_string())?;
@@ -455,6 +737,21 @@ pub fn clear_config(
     if matches.is_present("ACCESS_KEY") {
         config.access_key = None;
     }
+    if matches.is_present("SIGNING_COMMAND") {
+        config.signing_command = None;
+    }
+    if matches.is_present("GIVER_ADDRESS") {
+        config.giver_address = None;
+    }
+    if matches.is_present("GIVER_ABI") {
+        config.giver_abi = None;
+    }
+    if matches.is_present("GIVER_KEYS") {
+        config.giver_keys = None;
+    }
+    if matches.is_present("ADDRESS_RESOLVER") {
+        config.address_resolver = None;
+    }
 
     if matches.args.is_empty() {
         *config = Config::new();
@@ -579,6 +876,21 @@ pub fn set_config(
             println!("Warning: You have access_key set without project_id. It has no sense in case of authentication.");
         }
     }
+    if let Some(s) = matches.value_of("SIGNING_COMMAND") {
+        config.signing_command = Some(s.to_string());
+    }
+    if let Some(s) = matches.value_of("GIVER_ADDRESS") {
+        config.giver_address = Some(s.to_string());
+    }
+    if let Some(s) = matches.value_of("GIVER_ABI") {
+        config.giver_abi = Some(s.to_string());
+    }
+    if let Some(s) = matches.value_of("GIVER_KEYS") {
+        config.giver_keys = Some(s.to_string());
+    }
+    if let Some(s) = matches.value_of("ADDRESS_RESOLVER") {
+        config.address_resolver = Some(s.to_string());
+    }
 
     full_config.to_file(&full_config.path)?;
     if !(full_config.config.is_json || is_json) {
@@ -587,6 +899,63 @@ pub fn set_config(
     Ok(())
 }
 
+/// Loads config parameters from the TOML or YAML file, the format is chosen by extension.
+fn load_config_layer(path: &str) -> Result<serde_json::Value, String> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read config file {}: {}", path, e))?;
+    let extension = std::path::Path::new(path).extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    match extension.as_deref() {
+        Some("toml") => toml::from_str(&data)
+            .map_err(|e| format!("failed to parse config file {}: {}", path, e)),
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&data)
+            .map_err(|e| format!("failed to parse config file {}: {}", path, e)),
+        _ => Err(format!("unsupported format of config file {}: expected .toml, .yaml or .yml", path)),
+    }
+}
+
+/// Declares the parameters stored in the config file which can be set by config layers.
+macro_rules! stored_parameters {
+    ($($field:ident),* $(,)?) => {
+        const STORED_PARAMETERS: &[&str] = &[$(stringify!($field)),*];
+
+        fn set_stored_parameter(config: &mut Config, key: &str, value: serde_json::Value) -> Result<(), String> {
+            match key {
+                $(stringify!($field) => config.$field = serde_json::from_value(value)
+                    .map_err(|e| format!("invalid value of \"{}\": {}", key, e))?,)*
+                _ => return Err(format!("unknown parameter \"{}\"", key)),
+            }
+            Ok(())
+        }
+    };
+}
+
+stored_parameters!(
+    url, wc, addr, method, parameters, wallet, pubkey, abi_path, keys_path, retries, timeout,
+    message_processing_timeout, out_of_sync_threshold, is_json, depool_fee, lifetime, no_answer,
+    balance_in_tons, local_run, async_call, debug_fail, project_id, access_key, signing_command,
+    giver_address, giver_abi, giver_keys, address_resolver, endpoints,
+);
+
+/// Overrides config parameters with `TONOSCLI_<PARAMETER>` environment variables, e.g.
+/// `TONOSCLI_URL` or `TONOSCLI_RETRIES`. The value is interpreted by the type of the parameter
+/// default: strings and optional parameters are taken as is, others are parsed as json.
+fn apply_env_layer(
+    values: &mut serde_json::Map<String, serde_json::Value>,
+    var: impl Fn(&str) -> Option<String>,
+) {
+    let defaults = serde_json::to_value(Config::default()).unwrap_or_default();
+    for key in STORED_PARAMETERS {
+        if let Some(env_value) = var(&format!("TONOSCLI_{}", key.to_uppercase())) {
+            let value = match defaults[*key] {
+                serde_json::Value::String(_) | serde_json::Value::Null => serde_json::Value::String(env_value),
+                _ => serde_json::from_str(&env_value).unwrap_or(serde_json::Value::String(env_value)),
+            };
+            values.insert(key.to_string(), value);
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -632,4 +1001,61 @@ mod tests {
         assert_eq!(resolve_net_name("devnet"), Some(TESTNET.to_owned()));
         assert_eq!(resolve_net_name("net.ton.com"), None);
     }
+
+    #[test]
+    fn test_config_layers() {
+        use super::{apply_env_layer, FullConfig};
+
+        let path = "test_config_layers.toml";
+        std::fs::write(path, "url = \"https://example.com\"\nretries = 3\ntimeout = 20000\n").unwrap();
+        let mut full_config = FullConfig::default();
+        let result = full_config.apply_layers(path);
+        std::fs::remove_file(path).unwrap();
+        result.unwrap();
+        assert_eq!(full_config.config.url, "https://example.com");
+        assert_eq!(full_config.config.retries, 3);
+        assert_eq!(full_config.config.timeout, 20000);
+        assert!(full_config.config.endpoints.is_empty());
+
+        let path = "test_config_layers.yaml";
+        std::fs::write(path, "url: localhost\nwc: -1\n").unwrap();
+        let mut full_config = FullConfig::default();
+        let result = full_config.apply_layers(path);
+        std::fs::remove_file(path).unwrap();
+        result.unwrap();
+        assert_eq!(full_config.config.url, LOCALNET);
+        assert_eq!(full_config.config.wc, -1);
+        assert_eq!(full_config.config.endpoints, FullConfig::default_map()[LOCALNET]);
+
+        let path = "test_config_layers.yml";
+        std::fs::write(path, "unknown: 1\n").unwrap();
+        let result = FullConfig::default().apply_layers(path);
+        std::fs::remove_file(path).unwrap();
+        assert!(result.unwrap_err().contains("unknown"));
+
+        // environment variables override the file and are typed by the parameter defaults
+        let mut layered = serde_json::json!({"url": "https://example.com", "retries": 3});
+        apply_env_layer(layered.as_object_mut().unwrap(), |key| match key {
+            "TONOSCLI_URL" => Some("https://other.com".to_owned()),
+            "TONOSCLI_RETRIES" => Some("7".to_owned()),
+            "TONOSCLI_PUBKEY" => Some("1234".to_owned()),
+            "TONOSCLI_LOCAL_RUN" => Some("true".to_owned()),
+            _ => None,
+        });
+        assert_eq!(layered, serde_json::json!({
+            "url": "https://other.com", "retries": 7, "pubkey": "1234", "local_run": true,
+        }));
+
+        // the layers keep the per-invocation parameters
+        let path = "test_config_layers_skip.toml";
+        std::fs::write(path, "retries = 2\n").unwrap();
+        let mut full_config = FullConfig::default();
+        full_config.config.once_ledger = "ledger.json".to_owned();
+        let result = full_config.apply_layers(path);
+        std::fs::remove_file(path).unwrap();
+        result.unwrap();
+        assert_eq!(full_config.config.retries, 2);
+        assert_eq!(full_config.config.once_ledger, "ledger.json");
+        assert_eq!(full_config.config.debot_confirm_yes, "y");
+    }
 }
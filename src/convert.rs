@@ -11,10 +11,38 @@
  * limitations under the License.
  */
 
+use num_bigint::BigUint;
+use num_traits::Num;
 use ton_types::Sha256;
 
 pub fn convert_token(amount: &str) -> Result<String, String> {
-    convert_amount(amount, 9)
+    convert_units(amount, 9)
+}
+
+/// Converts human-readable amount (with optional separators) to the integer amount
+/// of the base units, `decimals` is the number of decimal places of the amount unit.
+pub fn convert_units(amount: &str, decimals: usize) -> Result<String, String> {
+    convert_amount(&remove_separators(amount)?, decimals)
+}
+
+/// Removes whitespace and comma thousands separators from the amount, e.g. `1 000,000.5`.
+/// Commas are accepted only between groups of three digits in the integer part,
+/// so that `1,5` is not silently treated as a decimal comma.
+fn remove_separators(amount: &str) -> Result<String, String> {
+    let amount: String = amount.chars().filter(|c| !c.is_whitespace()).collect();
+    let (integer, fraction) = amount.split_once('.').unwrap_or((&amount, ""));
+    if fraction.contains(',') {
+        return Err(format!("invalid amount \"{}\": unexpected comma in the fractional part", amount));
+    }
+    if integer.contains(',') {
+        let groups: Vec<&str> = integer.split(',').collect();
+        let valid = (1..=3).contains(&groups[0].len()) &&
+            groups[1..].iter().all(|group| group.len() == 3);
+        if !valid {
+            return Err(format!("invalid amount \"{}\": commas are allowed only as thousands separators", amount));
+        }
+    }
+    Ok(amount.replace(',', ""))
 }
 
 pub fn convert_amount(amount: &str, decimals: usize) -> Result<String, String> {
@@ -32,7 +60,8 @@ pub fn convert_amount(amount: &str, decimals: usize) -> Result<String, String> {
             result += &"0".repeat(decimals);
         }
         let result = result.trim_start_matches('0').to_string();
-        u64::from_str_radix(&result, 10)
+        let result = if result.is_empty() { "0".to_string() } else { result };
+        BigUint::from_str_radix(&result, 10)
             .map_err(|e| format!("failed to parse amount: {}", e))?;
 
         return Ok(result);
@@ -40,6 +69,20 @@ pub fn convert_amount(amount: &str, decimals: usize) -> Result<String, String> {
     Err("Invalid amount value".to_string())
 }
 
+pub fn convert_nanotoken(amount: &str) -> Result<String, String> {
+    convert_to_decimal(amount, 9)
+}
+
+/// Inverse of `convert_amount`: formats integer amount with the given number of decimals.
+pub fn convert_to_decimal(amount: &str, decimals: usize) -> Result<String, String> {
+    let value = BigUint::from_str_radix(amount, 10)
+        .map_err(|e| format!("failed to parse amount: {}", e))?;
+    let divisor = BigUint::from(10u32).pow(decimals as u32);
+    let integer = &value / &divisor;
+    let fraction = &value % &divisor;
+    Ok(format!("{}.{:0>width$}", integer, fraction.to_string(), width = decimals))
+}
+
 pub fn convert_u64_to_tokens(value: u64) -> String {
     let integer = value / 1_000_000_000;
     let float = value - integer * 1_000_000_000;
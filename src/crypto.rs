@@ -10,7 +10,7 @@
  * See the License for the specific TON DEV software governing permissions and
  * limitations under the License.
  */
-use crate::helpers::{create_client_local, read_keys, WORD_COUNT, HD_PATH, check_dir};
+use crate::helpers::{create_client_local, read_keys, keypair_from_keychain, WORD_COUNT, HD_PATH, check_dir};
 use ton_client::crypto::{
     KeyPair,
     mnemonic_from_random,
@@ -26,9 +26,18 @@ use ton_client::crypto::{
     ParamsOfMnemonicFromRandom
 };
 use crate::Config;
+use ton_types::ed25519_sign_with_secret;
+
+/// Prefix of the keys reference which is read from the OS keychain only when the keypair
+/// is loaded, so the secret is not passed around as a plain string.
+pub const KEYCHAIN_KEYS_PREFIX: &str = "keychain:";
 
 pub fn load_keypair(keys: &str) -> Result<KeyPair, String> {
-    if keys.find(' ').is_none() {
+    if let Some(name) = keys.strip_prefix(KEYCHAIN_KEYS_PREFIX) {
+        keypair_from_keychain(name)
+    } else if is_inline_keypair(keys) {
+        load_inline_keypair(keys)
+    } else if keys.find(' ').is_none() {
         let keys = read_keys(keys)?;
         Ok(keys)
     } else {
@@ -36,6 +45,55 @@ pub fn load_keypair(keys: &str) -> Result<KeyPair, String> {
     }
 }
 
+fn is_hex_key(key: &str) -> bool {
+    key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Checks whether keys are specified inline as a `secret:public` hex pair.
+pub fn is_inline_keypair(keys: &str) -> bool {
+    match keys.split_once(':') {
+        Some((secret, public)) => is_hex_key(secret) && is_hex_key(public),
+        None => false,
+    }
+}
+
+/// Replaces an inline keypair with a placeholder so the secret is never printed.
+pub fn hide_inline_keypair(keys: &str) -> &str {
+    if is_inline_keypair(keys) {
+        "<inline keypair>"
+    } else {
+        keys
+    }
+}
+
+fn load_inline_keypair(keys: &str) -> Result<KeyPair, String> {
+    let (secret, public) = keys.split_once(':')
+        .ok_or("inline keypair must be specified as \"secret:public\"".to_string())?;
+    let keypair = generate_keypair_from_secret(secret.to_lowercase())
+        .map_err(|_| "failed to load inline keypair: invalid secret key".to_string())?;
+    if keypair.public != public.to_lowercase() {
+        return Err("failed to load inline keypair: public key doesn't match the secret key".to_string());
+    }
+    Ok(keypair)
+}
+
+/// Produces the detached ED25519 signature of `data` the same way the signing box does.
+pub fn sign_data(keys: &KeyPair, data: &[u8]) -> Result<Vec<u8>, String> {
+    let key = keys.decode()
+        .map_err(|e| format!("cannot decode keypair {}", e))?;
+    let signature = ed25519_sign_with_secret(&key.to_bytes(), data)
+        .map_err(|e| format!("Failed to sign: {e}"))?;
+    Ok(signature.as_ref().to_vec())
+}
+
+/// Decodes the hash to be signed. Hex is checked first because a hex string is also valid base64.
+pub fn decode_hash(hash: &str) -> Result<Vec<u8>, String> {
+    let hash = hash.trim().trim_start_matches("0x");
+    hex::decode(hash)
+        .or_else(|_| base64::decode(hash))
+        .map_err(|_| "the hash should be hex or base64 encoded".to_string())
+}
+
 pub fn gen_seed_phrase() -> Result<String, String> {
     let client = create_client_local()?;
     mnemonic_from_random(
@@ -211,6 +269,81 @@ mod tests {
         assert_eq!(&keypair.secret, "f63d3d11e0dc91f730f22d5397f269e01f1a5f984879c8581ac87f099bfd3b3a");
     }
 
+    #[test]
+    fn test_inline_keypair() {
+        let secret = "30e3bc5e67af2b0a72971bcc11256e83d052c6cb861a69a19a8af88922fadf3a";
+        let public = "757221fe3d4992e44632e75e700aaf205d799cb7373ee929273daf26adf29e56";
+        let inline = format!("{}:{}", secret, public);
+        assert!(is_inline_keypair(&inline));
+        assert!(!is_inline_keypair("keys.json"));
+        assert_eq!(hide_inline_keypair(&inline), "<inline keypair>");
+
+        let keypair = load_keypair(&inline).unwrap();
+        assert_eq!(keypair.public, public);
+        assert_eq!(keypair.secret, secret);
+
+        let client = create_client_local().unwrap();
+        let signed = ton_client::crypto::sign(
+            client.clone(),
+            ton_client::crypto::ParamsOfSign {
+                unsigned: base64::encode("message"),
+                keys: keypair,
+            },
+        ).unwrap();
+        let verified = ton_client::crypto::verify_signature(
+            client,
+            ton_client::crypto::ParamsOfVerifySignature {
+                signed: signed.signed,
+                public: public.to_owned(),
+            },
+        ).unwrap();
+        assert_eq!(base64::decode(verified.unsigned).unwrap(), b"message");
+
+        let wrong_public = format!("{}:{}", secret, "0".repeat(64));
+        assert!(load_keypair(&wrong_public).is_err());
+    }
+
+    #[cfg(not(feature = "keychain"))]
+    #[test]
+    fn test_load_keychain_keypair_disabled() {
+        let err = load_keypair(&format!("{}test", KEYCHAIN_KEYS_PREFIX)).unwrap_err();
+        assert!(err.contains("keychain support is disabled"));
+    }
+
+    #[test]
+    fn test_sign_hash() {
+        let keypair = generate_keypair_from_mnemonic(
+            "multiply extra monitor fog rocket defy attack right night jaguar hollow enlist"
+        ).unwrap();
+        let hash = "9b0ae1b6cd2fc6c2a4d6f4c4e1ab2e52ec1f1d3b4a3d1c0ea3a9d5a0c5d5e6f7";
+        let data = decode_hash(hash).unwrap();
+        assert_eq!(data.len(), 32);
+        assert_eq!(decode_hash(&format!("0x{}", hash)).unwrap(), data);
+        assert_eq!(decode_hash(&base64::encode(&data)).unwrap(), data);
+
+        let signature = sign_data(&keypair, &data).unwrap();
+        assert_eq!(signature.len(), 64);
+        let mut signed = signature.clone();
+        signed.extend_from_slice(&data);
+        let verified = ton_client::crypto::verify_signature(
+            create_client_local().unwrap(),
+            ton_client::crypto::ParamsOfVerifySignature {
+                signed: base64::encode(&signed),
+                public: keypair.public.clone(),
+            },
+        ).unwrap();
+        assert_eq!(base64::decode(verified.unsigned).unwrap(), data);
+
+        let signed_by_client = ton_client::crypto::sign(
+            create_client_local().unwrap(),
+            ton_client::crypto::ParamsOfSign {
+                unsigned: base64::encode(&data),
+                keys: keypair,
+            },
+        ).unwrap();
+        assert_eq!(signed_by_client.signature, hex::encode(&signature));
+    }
+
     #[test]
     fn test_invalid_mnemonic() {
         let invalid_phrases = vec![
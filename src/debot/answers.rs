@@ -0,0 +1,86 @@
+/*
+* Copyright 2018-2023 EverX.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+
+/// Pseudo interface name used to answer DeBot Browser own questions.
+pub const BROWSER_KEY: &str = "browser";
+
+/// Prerecorded answers for DeBot interface calls.
+///
+/// Answers file is a json object with answers grouped by interface id and function name:
+/// `{ "<interface id>": { "<function>": [ {<output params>}, ... ] } }`.
+/// Answers for each function are consumed in the order they are listed in the file.
+#[derive(Default)]
+pub struct Answers {
+    queues: HashMap<String, VecDeque<Value>>,
+}
+
+impl Answers {
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let answers_raw = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read answers file: {}", e))?;
+        let answers: Value = serde_json::from_str(&answers_raw)
+            .map_err(|e| format!("failed to parse answers file: {}", e))?;
+        Self::from_json(answers)
+    }
+
+    pub fn from_json(answers: Value) -> Result<Self, String> {
+        let interfaces = answers.as_object()
+            .ok_or("answers must be a json object".to_string())?;
+        let mut queues = HashMap::new();
+        for (interface, functions) in interfaces {
+            let functions = functions.as_object()
+                .ok_or(format!("answers for interface \"{}\" must be a json object", interface))?;
+            for (function, values) in functions {
+                let values = values.as_array()
+                    .ok_or(format!("answers for \"{}.{}\" must be an array", interface, function))?;
+                queues.insert(Self::key(interface, function), values.iter().cloned().collect());
+            }
+        }
+        Ok(Self { queues })
+    }
+
+    /// Takes the next answer for interface function. Returns `None` if answers are exhausted.
+    pub fn next(&mut self, interface: &str, function: &str) -> Option<Value> {
+        self.queues.get_mut(&Self::key(interface, function))
+            .and_then(|queue| queue.pop_front())
+    }
+
+    fn key(interface: &str, function: &str) -> String {
+        format!("{}.{}", interface, function)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_answers_queue() {
+        let mut answers = Answers::from_json(json!({
+            "a56115147709ed3437efb89460b94a120b7fe94379c795d1ebb0435a847ee580": {
+                "getAccount": [{ "value": "0:1" }, { "value": "0:2" }]
+            },
+            "browser": { "run": [true] }
+        })).unwrap();
+        let id = "a56115147709ed3437efb89460b94a120b7fe94379c795d1ebb0435a847ee580";
+        assert_eq!(answers.next(id, "getAccount"), Some(json!({ "value": "0:1" })));
+        assert_eq!(answers.next(id, "getAccount"), Some(json!({ "value": "0:2" })));
+        assert_eq!(answers.next(id, "getAccount"), None);
+        assert_eq!(answers.next(id, "getPublicKey"), None);
+        assert_eq!(answers.next(BROWSER_KEY, "run"), Some(json!(true)));
+        assert!(Answers::from_json(json!([])).is_err());
+    }
+}
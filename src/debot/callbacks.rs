@@ -61,12 +61,13 @@ impl Callbacks {
         }
 
         loop {
-            let res = action_input(state.active_actions.len());
-            if res.is_err() {
-                println!("{}", res.unwrap_err());
-                continue;
-            }
-            let (n, _, _) = res.unwrap();
+            let (n, _, _) = match action_input(state.active_actions.len()) {
+                Ok(res) => res,
+                Err(e) => {
+                    println!("{}", e);
+                    return None;
+                }
+            };
             let act = state.active_actions.get(n - 1);
             if act.is_none() {
                 println!("Invalid action. Try again.");
@@ -191,14 +192,14 @@ impl BrowserCallbacks for Callbacks {
         self.processor.read().await.print(&info);
         approved = match result {
             Err(ProcessorError::InteractiveApproveNeeded) => {
-                let _ = terminal_input(prompt, |val| {
+                terminal_input(prompt, |val| {
                     approved = match val.as_str() {
                         "y" => true,
                         "n" => false,
                         _ => return Err("invalid enter".to_string()),
                     };
                     Ok(())
-                });
+                })?;
                 approved
             }
             Err(_) => false,
@@ -3,8 +3,13 @@ use crate::helpers::load_ton_address;
 use serde_json::{Value, json};
 use ton_client::abi::Abi;
 use ton_client::debot::{DebotInterface, InterfaceResult};
-use super::dinterface::{decode_answer_id, decode_prompt};
+use super::dinterface::{decode_answer_id, decode_num_arg, decode_prompt};
+use crate::call::run_getter;
 use crate::config::Config;
+use crate::helpers::TonClient;
+use std::future::Future;
+use std::str::FromStr;
+use ton_block::MsgAddressInt;
 
 const ID: &str = "d7ed1bd8e6230871116f4522e58df0a93c5520c56f4ade23ef3d8919a984653b";
 
@@ -46,30 +51,121 @@ pub const ABI: &str = r#"
 }
 "#;
 
+const RESOLVER_ABI: &str = r#"
+{
+    "ABI version": 2,
+    "version": "2.2",
+    "header": ["time"],
+    "functions": [
+        {
+            "name": "resolve",
+            "inputs": [
+                {"name":"name","type":"string"}
+            ],
+            "outputs": [
+                {"name":"addr","type":"address"}
+            ]
+        }
+    ],
+    "data": [],
+    "events": []
+}
+"#;
+
 pub struct AddressInput {
+    client: TonClient,
     config: Config
 }
 impl AddressInput {
-    pub fn new(config: Config) -> Self {
-        Self {config}
+    pub fn new(client: TonClient, config: Config) -> Self {
+        Self {client, config}
     }
-    fn get(&self, args: &Value) -> InterfaceResult {
+    async fn get(&self, args: &Value) -> InterfaceResult {
         let answer_id = decode_answer_id(args)?;
         let prompt = decode_prompt(args)?;
-        let value = terminal_input(&prompt, |val| {
-            let _ = load_ton_address(val, &self.config).map_err(|e| format!("Invalid address: {}", e))?;
-            Ok(())
-        });
+        let workchain = decode_workchain(args);
+        let value = self.input_address(&prompt, workchain).await?;
         Ok((answer_id, json!({ "value": value })))
     }
-    fn select(&self, args: &Value) -> InterfaceResult {
+    async fn select(&self, args: &Value) -> InterfaceResult {
         let answer_id = decode_answer_id(args)?;
-        let value = terminal_input("", |val| {
-            let _ = load_ton_address(val, &self.config).map_err(|e| format!("Invalid address: {}", e))?;
-            Ok(())
-        });
+        let workchain = decode_workchain(args);
+        let value = self.input_address("", workchain).await?;
         Ok((answer_id, json!({ "value": value })))
     }
+
+    /// Reads an address from the terminal. If a name resolver is configured, names are
+    /// accepted too and replaced with the resolved address.
+    async fn input_address(&self, prompt: &str, workchain: Option<i32>) -> Result<String, String> {
+        loop {
+            let value = terminal_input(prompt, |val| self.validate(val, workchain))?;
+            let resolved = resolve_input(&value, &self.config, |name| self.resolve(name)).await
+                .and_then(|addr| check_workchain(&addr, workchain).map(|_| addr));
+            match resolved {
+                Ok(addr) => return Ok(addr),
+                Err(e) => println!("{}. Try again.", e),
+            }
+        }
+    }
+
+    fn validate(&self, val: &str, workchain: Option<i32>) -> Result<(), String> {
+        match load_ton_address(val, &self.config) {
+            Ok(addr) => check_workchain(&addr, workchain),
+            // names are checked after resolution
+            Err(_) if self.config.address_resolver.is_some() && !val.trim().is_empty() => Ok(()),
+            Err(e) => Err(format!("Invalid address: {}", e)),
+        }
+    }
+
+    async fn resolve(&self, name: String) -> Result<String, String> {
+        let resolver = self.config.address_resolver.as_deref()
+            .ok_or("name resolver is not configured".to_owned())?;
+        let resolver = load_ton_address(resolver, &self.config)?;
+        let params = json!({ "name": name }).to_string();
+        let output = run_getter(
+            self.client.clone(), &resolver, Abi::Json(RESOLVER_ABI.to_owned()), "resolve", &params
+        ).await?;
+        output["addr"].as_str()
+            .map(|addr| addr.to_owned())
+            .ok_or(format!("resolver returned unexpected output: {}", output))
+    }
+}
+
+/// Converts user input into an address. Raw addresses are returned as is, other input is
+/// treated as a name and passed to `resolve` if the name resolver is configured.
+async fn resolve_input<F, Fut>(val: &str, config: &Config, resolve: F) -> Result<String, String>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    match load_ton_address(val, config) {
+        Ok(_) => Ok(val.to_owned()),
+        Err(e) if config.address_resolver.is_none() => Err(format!("Invalid address: {}", e)),
+        Err(_) => {
+            let name = val.trim().to_owned();
+            let addr = resolve(name.clone()).await
+                .map_err(|e| format!("Failed to resolve name \"{}\": {}", name, e))?;
+            load_ton_address(&addr, config)
+                .map_err(|e| format!("Failed to resolve name \"{}\": resolver returned invalid address: {}", name, e))
+        }
+    }
+}
+
+/// Reads optional workchain constraint from the interface arguments.
+fn decode_workchain(args: &Value) -> Option<i32> {
+    decode_num_arg::<i32>(args, "workchain").ok()
+        .or(args["workchain"].as_i64().map(|wc| wc as i32))
+}
+
+fn check_workchain(addr: &str, workchain: Option<i32>) -> Result<(), String> {
+    if let Some(wc) = workchain {
+        let addr = MsgAddressInt::from_str(addr)
+            .map_err(|e| format!("Invalid address: {}", e))?;
+        if addr.workchain_id() != wc {
+            return Err(format!("Address must belong to workchain {}", wc));
+        }
+    }
+    Ok(())
 }
 
 #[async_trait::async_trait]
@@ -84,9 +180,53 @@ impl DebotInterface for AddressInput {
 
     async fn call(&self, func: &str, args: &Value) -> InterfaceResult {
         match func {
-            "get" => self.get(args),
-            "select" => self.select(args),
+            "get" => self.get(args).await,
+            "select" => self.select(args).await,
             _ => Err(format!("function \"{}\" is not implemented", func)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workchain_filter() {
+        let mc_addr = format!("-1:{:064}", 1);
+        let wc_addr = format!("0:{:064}", 1);
+        assert!(check_workchain(&mc_addr, Some(0)).is_err());
+        assert!(check_workchain(&wc_addr, Some(0)).is_ok());
+        assert!(check_workchain(&mc_addr, None).is_ok());
+        assert_eq!(decode_workchain(&json!({ "workchain": "0" })), Some(0));
+        assert_eq!(decode_workchain(&json!({ "workchain": -1 })), Some(-1));
+        assert_eq!(decode_workchain(&json!({})), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_input() {
+        let resolved = format!("0:{:064}", 2);
+        let stub = |name: String| {
+            let resolved = resolved.clone();
+            async move {
+                match name.as_str() {
+                    "alice.ever" => Ok(resolved),
+                    "broken.ever" => Ok("not an address".to_owned()),
+                    _ => Err("name is not registered".to_owned()),
+                }
+            }
+        };
+        let raw = format!("0:{:064}", 1);
+
+        let mut config = Config::default();
+        assert_eq!(resolve_input(&raw, &config, stub).await.unwrap(), raw);
+        assert!(resolve_input("alice.ever", &config, stub).await.unwrap_err().starts_with("Invalid address"));
+
+        config.address_resolver = Some(format!("0:{:064}", 3));
+        assert_eq!(resolve_input(&raw, &config, stub).await.unwrap(), raw);
+        assert_eq!(resolve_input(" alice.ever", &config, stub).await.unwrap(), resolved);
+        let err = resolve_input("bob.ever", &config, stub).await.unwrap_err();
+        assert!(err.contains("Failed to resolve name \"bob.ever\": name is not registered"));
+        assert!(resolve_input("broken.ever", &config, stub).await.is_err());
+    }
+}
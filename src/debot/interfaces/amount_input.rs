@@ -72,7 +72,7 @@ impl AmountInput {
             format_amount(min, decimals),
             format_amount(max, decimals)
         );
-        let _ = terminal_input(&prompt, |val| {
+        terminal_input(&prompt, |val| {
             value = convert::convert_amount(val.as_str(), decimals)?;
             let number = decode_abi_number::<u128>(&value)
                 .map_err(|e| format!("input is not a valid amount: {}", e))?;
@@ -80,7 +80,7 @@ impl AmountInput {
                 return Err("amount is out of range".to_string());
             }
             Ok(())
-        });
+        })?;
         Ok((answer_id, json!({ "value": value })))
     }
 }
@@ -44,24 +44,41 @@ pub const ABI: &str = r#"
 }
 "#;
 
-pub struct ConfirmInput {}
+/// Asks the user a yes/no question. Besides the configured tokens (`y`/`n` by default)
+/// `yes`/`no` and `true`/`false` are accepted, all case-insensitive.
+pub struct ConfirmInput {
+    yes: String,
+    no: String,
+}
 
 impl ConfirmInput {
     pub fn new() -> Self {
-        Self {}
+        Self::with_tokens("y", "n")
+    }
+
+    pub fn with_tokens(yes: &str, no: &str) -> Self {
+        Self { yes: yes.trim().to_lowercase(), no: no.trim().to_lowercase() }
+    }
+
+    fn parse(&self, value: &str) -> Result<bool, String> {
+        let value = value.trim().to_lowercase();
+        if value == self.yes || value == "yes" || value == "true" {
+            Ok(true)
+        } else if value == self.no || value == "no" || value == "false" {
+            Ok(false)
+        } else {
+            Err(format!("invalid enter, expected {} or {}", self.yes, self.no))
+        }
     }
+
     fn get(&self, args: &Value) -> InterfaceResult {
         let answer_id = decode_answer_id(args)?;
         let prompt = decode_prompt(args)?;
         let mut yes_no = false;
-        let _ = terminal_input(&format!("{} (y/n)", prompt), |val| {
-            yes_no = match val.as_str() {
-                "y" => true,
-                "n" => false,
-                _ => return Err("invalid enter".to_string()),
-            };
+        terminal_input(&format!("{} ({}/{})", prompt, self.yes, self.no), |val| {
+            yes_no = self.parse(val)?;
             Ok(())
-        });
+        })?;
         Ok((answer_id, json!({ "value": yes_no })))
     }
 }
@@ -83,3 +100,23 @@ impl DebotInterface for ConfirmInput {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_tokens() {
+        let confirm = ConfirmInput::new();
+        assert_eq!(confirm.parse("yes"), Ok(true));
+        assert_eq!(confirm.parse("Y"), Ok(true));
+        assert_eq!(confirm.parse("False"), Ok(false));
+        assert!(confirm.parse("o").is_err());
+
+        let confirm = ConfirmInput::with_tokens("o", "N");
+        assert_eq!(confirm.parse("O"), Ok(true));
+        assert_eq!(confirm.parse("n"), Ok(false));
+        assert_eq!(confirm.parse("yes"), Ok(true));
+        assert!(confirm.parse("y").is_err());
+    }
+}
@@ -1,9 +1,10 @@
 use super::echo::Echo;
 use super::stdout::Stdout;
 use super::{
-    AddressInput, AmountInput, ConfirmInput, Menu, NumberInput, SigningBoxInput,
-    EncryptionBoxInput, Terminal, UserInfo, InputInterface
+    AddressInput, AmountInput, ConfirmInput, Menu, Network, NumberInput, QrCode, SigningBoxInput,
+    EncryptionBoxInput, Terminal, UserInfo, InputInterface, LoggingInterface
 };
+use super::logging_interface::open_debot_log;
 use crate::config::Config;
 use crate::debot::ChainProcessor;
 use crate::helpers::TonClient;
@@ -46,52 +47,59 @@ impl InterfaceWrapper {
 }
 
 impl SupportedInterfaces {
-    pub fn new(client: TonClient, config: &Config, processor: Arc<RwLock<ChainProcessor>>) -> Self {
+    pub fn new(client: TonClient, config: &Config, processor: Arc<RwLock<ChainProcessor>>) -> Result<Self, String> {
         let mut interfaces = HashMap::new();
 
         let iw = InterfaceWrapper { processor: processor.clone() };
 
-        let iface: Arc<dyn DebotInterface + Send + Sync> = iw.wrap(Arc::new(AddressInput::new(config.clone())));
-        interfaces.insert(iface.get_id(), iface);
+        let mut all_interfaces: Vec<(&str, Arc<dyn DebotInterface + Send + Sync>)> = vec![
+            ("addressInput", iw.wrap(Arc::new(AddressInput::new(client.clone(), config.clone())))),
+            ("amountInput", iw.wrap(Arc::new(AmountInput::new()))),
+            ("numberInput", iw.wrap(Arc::new(NumberInput::new()))),
+            ("confirmInput", iw.wrap(Arc::new(ConfirmInput::with_tokens(&config.debot_confirm_yes, &config.debot_confirm_no)))),
+            ("stdout", Arc::new(Stdout::new())),
+            ("echo", Arc::new(Echo::new())),
+            ("qrCode", Arc::new(QrCode::new(config.is_json))),
+            ("terminal", iw.wrap(Arc::new(Terminal::new(Printer {processor})))),
+            ("menu", iw.wrap(Arc::new(Menu::new()))),
+            ("signingBox", Arc::new(
+                SigningBoxInput::new(client.clone(), iw.processor.clone(), config.signing_command.clone())
+            )),
+            ("userInfo", iw.wrap(Arc::new(UserInfo::new(client.clone(), config.clone())))),
+            ("encryptionBox", Arc::new(EncryptionBoxInput::new(client.clone(), config.keys_path.clone(), config.is_json))),
+        ];
+        // network access is granted to DeBots only explicitly
+        if config.debot_allow_network {
+            all_interfaces.push(("network", Arc::new(Network::new())));
+        }
 
-        let iface: Arc<dyn DebotInterface + Send + Sync> = iw.wrap(Arc::new(AmountInput::new()));
-        interfaces.insert(iface.get_id(), iface);
+        // interfaces can be disabled either by name or by id
+        let matches = |disabled: &str, name: &str, id: &str| {
+            disabled.eq_ignore_ascii_case(name) || disabled == id
+        };
+        for disabled in &config.disabled_interfaces {
+            if !all_interfaces.iter().any(|(name, iface)| matches(disabled, name, &iface.get_id())) {
+                let names: Vec<&str> = all_interfaces.iter().map(|(name, _)| *name).collect();
+                return Err(format!(
+                    "unknown DeBot interface \"{}\". Available interfaces: {}", disabled, names.join(", ")
+                ));
+            }
+        }
+        for (name, iface) in all_interfaces {
+            let id = iface.get_id();
+            if !config.disabled_interfaces.iter().any(|d| matches(d, name, &id)) {
+                interfaces.insert(id, iface);
+            }
+        }
 
-        let iface: Arc<dyn DebotInterface + Send + Sync> = iw.wrap(Arc::new(NumberInput::new()));
-        interfaces.insert(iface.get_id(), iface);
+        if let Some(path) = &config.debot_log {
+            let log = open_debot_log(path)?;
+            for iface in interfaces.values_mut() {
+                *iface = Arc::new(LoggingInterface::new(iface.clone(), log.clone()));
+            }
+        }
 
-        let iface: Arc<dyn DebotInterface + Send + Sync> = iw.wrap(Arc::new(ConfirmInput::new()));
-        interfaces.insert(iface.get_id(), iface);
-
-        let iface: Arc<dyn DebotInterface + Send + Sync> = Arc::new(Stdout::new());
-        interfaces.insert(iface.get_id(), iface);
-
-        let iface: Arc<dyn DebotInterface + Send + Sync> = Arc::new(Echo::new());
-        interfaces.insert(iface.get_id(), iface);
-
-        let iface: Arc<dyn DebotInterface + Send + Sync> = iw.wrap(
-            Arc::new(Terminal::new(Printer {processor}))
-        );
-        interfaces.insert(iface.get_id(), iface);
-
-        let iface: Arc<dyn DebotInterface + Send + Sync> = iw.wrap(Arc::new(Menu::new()));
-        interfaces.insert(iface.get_id(), iface);
-
-        let iface: Arc<dyn DebotInterface + Send + Sync> = Arc::new(
-            SigningBoxInput::new(client.clone(), iw.processor.clone())
-        );
-        interfaces.insert(iface.get_id(), iface);
-
-        let iface: Arc<dyn DebotInterface + Send + Sync> = iw.wrap(
-            Arc::new(UserInfo::new(client.clone(), config.clone()))
-        );
-        interfaces.insert(iface.get_id(), iface);
-
-        let iface: Arc<dyn DebotInterface + Send + Sync> =
-            Arc::new(EncryptionBoxInput::new(client.clone()));
-        interfaces.insert(iface.get_id(), iface);
-
-        Self { client, interfaces }
+        Ok(Self { client, interfaces })
     }
 }
 
@@ -106,13 +114,33 @@ impl Printer {
 }
 
 pub fn decode_answer_id(args: &Value) -> Result<u32, String> {
-    u32::from_str_radix(
-        args["answerId"]
-            .as_str()
-            .ok_or("answer id not found in argument list".to_string())?,
-        10,
-    )
-    .map_err(|e| format!("{}", e))
+    match &args["answerId"] {
+        Value::String(id) => {
+            if id.starts_with('-') {
+                return Err(format!("answerId {} must not be negative", id));
+            }
+            let (digits, radix) = match id.strip_prefix("0x") {
+                Some(hex) => (hex, 16),
+                None => (id.as_str(), 10),
+            };
+            if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+                return Err(format!("answerId {} is not an integer", id));
+            }
+            // the digits are valid, so the only possible error is overflow
+            u32::from_str_radix(digits, radix)
+                .map_err(|_| format!("answerId {} exceeds u32 range", id))
+        },
+        Value::Number(id) => {
+            if let Some(id) = id.as_u64() {
+                u32::try_from(id).map_err(|_| format!("answerId {} exceeds u32 range", id))
+            } else if id.is_i64() {
+                Err(format!("answerId {} must not be negative", id))
+            } else {
+                Err(format!("answerId {} is not an integer", id))
+            }
+        },
+        _ => Err("answer id not found in argument list".to_string()),
+    }
 }
 
 pub fn decode_arg(args: &Value, name: &str) -> Result<String, String> {
@@ -128,10 +156,24 @@ pub fn decode_bool_arg(args: &Value, name: &str) -> Result<bool, String> {
         .ok_or(format!("\"{}\" not found", name))
 }
 
+/// Decodes argument of the ABI type `string`. The SDK passes such arguments as is, so they are
+/// never hex-decoded, even if they look like hex.
 pub fn decode_string_arg(args: &Value, name: &str) -> Result<String, String> {
     decode_arg(args, name)
 }
 
+/// Decodes argument of the ABI type `bytes` holding UTF-8 text. The SDK passes bytes hex-encoded,
+/// but plain strings are accepted too. Note that a plain string which is valid hex of printable
+/// text (e.g. "4142") is decoded ("AB"), hex payloads which don't decode to printable UTF-8 text
+/// are kept as is.
+pub fn decode_bytes_string_arg(args: &Value, name: &str) -> Result<String, String> {
+    let arg = decode_arg(args, name)?;
+    let decoded = hex::decode(&arg).ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .filter(|s| !s.chars().any(|c| c.is_control() && !c.is_whitespace()));
+    Ok(decoded.unwrap_or(arg))
+}
+
 pub fn decode_nonce(args: &Value) -> Result<String, String> {
     decode_arg(args, "nonce")
 }
@@ -163,8 +205,113 @@ where
         .as_array()
         .ok_or(format!("\"{}\" is invalid: must be array", name))?;
     let mut strings = vec![];
-    for elem in array {
-        strings.push(validator(elem).ok_or("invalid array element type".to_string())?);
+    for (i, elem) in array.iter().enumerate() {
+        strings.push(validator(elem).ok_or(format!(
+            "invalid array element type: \"{}\"[{}] = {}",
+            name, i, elem
+        ))?);
     }
     Ok(strings)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_decode_answer_id() {
+        assert_eq!(decode_answer_id(&json!({"answerId": "123"})), Ok(123));
+        assert_eq!(decode_answer_id(&json!({"answerId": 123})), Ok(123));
+        assert_eq!(decode_answer_id(&json!({"answerId": "0x7b"})), Ok(123));
+        assert_eq!(
+            decode_answer_id(&json!({"prompt": "abc"})),
+            Err("answer id not found in argument list".to_string())
+        );
+        assert!(decode_answer_id(&json!({"answerId": "abc"})).is_err());
+        assert!(decode_answer_id(&json!({"answerId": -1})).is_err());
+    }
+
+    #[test]
+    fn test_decode_answer_id_out_of_range() {
+        assert_eq!(decode_answer_id(&json!({"answerId": "4294967295"})), Ok(u32::MAX));
+        assert_eq!(
+            decode_answer_id(&json!({"answerId": "4294967296"})),
+            Err("answerId 4294967296 exceeds u32 range".to_string())
+        );
+        assert_eq!(
+            decode_answer_id(&json!({"answerId": 4294967296u64})),
+            Err("answerId 4294967296 exceeds u32 range".to_string())
+        );
+        assert_eq!(
+            decode_answer_id(&json!({"answerId": "0x100000000"})),
+            Err("answerId 0x100000000 exceeds u32 range".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_answer_id_negative() {
+        assert_eq!(
+            decode_answer_id(&json!({"answerId": "-1"})),
+            Err("answerId -1 must not be negative".to_string())
+        );
+        assert_eq!(
+            decode_answer_id(&json!({"answerId": -1})),
+            Err("answerId -1 must not be negative".to_string())
+        );
+        assert_eq!(
+            decode_answer_id(&json!({"answerId": "1.5"})),
+            Err("answerId 1.5 is not an integer".to_string())
+        );
+        assert_eq!(
+            decode_answer_id(&json!({"answerId": 1.5})),
+            Err("answerId 1.5 is not an integer".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_string_arg() {
+        let args = json!({"prompt": "Enter amount:"});
+        assert_eq!(decode_string_arg(&args, "prompt"), Ok("Enter amount:".to_string()));
+
+        // plain strings which look like hex are not decoded
+        let args = json!({"prompt": "4142"});
+        assert_eq!(decode_string_arg(&args, "prompt"), Ok("4142".to_string()));
+        let args = json!({"prompt": "7b7d"});
+        assert_eq!(decode_string_arg(&args, "prompt"), Ok("7b7d".to_string()));
+
+        assert!(decode_string_arg(&json!({}), "prompt").is_err());
+    }
+
+    #[test]
+    fn test_decode_bytes_string_arg() {
+        let args = json!({"message": hex::encode("Enter amount:")});
+        assert_eq!(decode_bytes_string_arg(&args, "message"), Ok("Enter amount:".to_string()));
+
+        let args = json!({"message": "Enter amount:"});
+        assert_eq!(decode_bytes_string_arg(&args, "message"), Ok("Enter amount:".to_string()));
+
+        // hex of printable text is decoded even if the string was meant as is
+        let args = json!({"message": "4142"});
+        assert_eq!(decode_bytes_string_arg(&args, "message"), Ok("AB".to_string()));
+
+        let args = json!({"message": "1234"});
+        assert_eq!(decode_bytes_string_arg(&args, "message"), Ok("1234".to_string()));
+
+        let args = json!({"message": "ff00"});
+        assert_eq!(decode_bytes_string_arg(&args, "message"), Ok("ff00".to_string()));
+
+        assert!(decode_bytes_string_arg(&json!({}), "message").is_err());
+    }
+
+    #[test]
+    fn test_decode_array() {
+        let args = json!({"keys": ["0x1", "0x2", "0x3"]});
+        let keys = decode_array(&args, "keys", |elem| elem.as_str().map(|s| s.to_owned()));
+        assert_eq!(keys, Ok(vec!["0x1".to_owned(), "0x2".to_owned(), "0x3".to_owned()]));
+
+        let args = json!({"keys": ["0x1", 2, "0x3"]});
+        let err = decode_array(&args, "keys", |elem| elem.as_str().map(|s| s.to_owned())).unwrap_err();
+        assert_eq!(err, r#"invalid array element type: "keys"[1] = 2"#);
+    }
+}
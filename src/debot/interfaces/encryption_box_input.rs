@@ -98,13 +98,18 @@ const ABI: &str = r#"
 pub struct EncryptionBoxInput {
     handles: RwLock<Vec<TerminalEncryptionBox>>,
     client: TonClient,
+    keys: Option<String>,
+    is_json: bool,
 }
 
 impl EncryptionBoxInput {
-    pub fn new(client: TonClient) -> Self {
+    /// If `keys` are not specified, the user is asked for the keys for every new box.
+    pub fn new(client: TonClient, keys: Option<String>, is_json: bool) -> Self {
         Self {
             handles: RwLock::new(vec![]),
             client,
+            keys,
+            is_json,
         }
     }
 
@@ -119,6 +124,8 @@ impl EncryptionBoxInput {
             box_type: EncryptionBoxType::NaCl,
             their_pubkey,
             nonce,
+            keys: self.keys.clone(),
+            is_json: self.is_json,
         }).await;
         Ok((answer_id, json!({ "handle": self.insert_box(result).await.0 })))
     }
@@ -132,6 +139,8 @@ impl EncryptionBoxInput {
             box_type: EncryptionBoxType::SecretNaCl,
             their_pubkey: String::new(),
             nonce,
+            keys: self.keys.clone(),
+            is_json: self.is_json,
         })
         .await;
         Ok((answer_id, json!({ "handle": self.insert_box(result).await.0})))
@@ -146,6 +155,8 @@ impl EncryptionBoxInput {
             box_type: EncryptionBoxType::ChaCha20,
             their_pubkey: String::new(),
             nonce,
+            keys: self.keys.clone(),
+            is_json: self.is_json,
         })
         .await;
         Ok((answer_id, json!({ "handle": self.insert_box(result).await.0})))
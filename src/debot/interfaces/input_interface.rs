@@ -39,7 +39,18 @@ impl DebotInterface for InputInterface {
         if self.get_id() == TERMINAL_ID && (func == "print" || func == "printf") {
             return self.inner_interface.call(func, args).await;
         }
-        let result = self.processor.write().await.next_input(&self.get_id(), func, args);
+        let result = {
+            let mut processor = self.processor.write().await;
+            match processor.next_input(&self.get_id(), func, args) {
+                Err(ProcessorError::InterfaceCallNeeded) => {
+                    match processor.next_answer(&self.get_id(), func) {
+                        Some(answer) => Ok(Some(answer)),
+                        None => Err(ProcessorError::InterfaceCallNeeded),
+                    }
+                },
+                result => result,
+            }
+        };
         match result {
             Err(ProcessorError::InterfaceCallNeeded) => {
                 let res = self.inner_interface.call(func, args).await?;
@@ -64,6 +75,7 @@ impl DebotInterface for InputInterface {
                 }
                 let answer_id = if self.get_id() == MENU_ID {
                     let n = params["index"].as_u64()
+                        .or(params["indices"][0].as_u64())
                         .ok_or("invalid arguments for menu callback".to_string())?;
                     let menu_items: Vec<MenuItem> = serde_json::from_value(args["items"].clone())
                         .map_err(|e| e.to_string())?;
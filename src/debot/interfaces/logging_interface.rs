@@ -0,0 +1,91 @@
+use crate::helpers::now_ms;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+use ton_client::abi::Abi;
+use ton_client::debot::{DebotInterface, InterfaceResult};
+
+/// Log file shared by all logging interfaces. Kept to flush it if the session is interrupted.
+static DEBOT_LOG: OnceLock<Arc<Mutex<File>>> = OnceLock::new();
+
+pub fn open_debot_log(path: &str) -> Result<Arc<Mutex<File>>, String> {
+    let log = File::create(path)
+        .map_err(|e| format!("failed to create debot log file: {}", e))?;
+    let log = Arc::new(Mutex::new(log));
+    let _ = DEBOT_LOG.set(log.clone());
+    Ok(log)
+}
+
+pub fn flush_debot_log() {
+    if let Some(log) = DEBOT_LOG.get() {
+        if let Ok(mut log) = log.lock() {
+            let _ = log.flush();
+            let _ = log.sync_all();
+        }
+    }
+}
+
+/// Decorator which records all calls of the inner interface and their answers
+/// to the log file in JSON Lines format. Returned values are not altered.
+pub struct LoggingInterface {
+    inner_interface: Arc<dyn DebotInterface + Send + Sync>,
+    log: Arc<Mutex<File>>,
+}
+
+impl LoggingInterface {
+    pub fn new(
+        inner_interface: Arc<dyn DebotInterface + Send + Sync>,
+        log: Arc<Mutex<File>>,
+    ) -> Self {
+        Self { inner_interface, log }
+    }
+
+    fn write_entry(&self, entry: Value) {
+        if let Ok(mut log) = self.log.lock() {
+            let _ = writeln!(log, "{}", entry);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DebotInterface for LoggingInterface {
+    fn get_id(&self) -> String {
+        self.inner_interface.get_id()
+    }
+
+    fn get_abi(&self) -> Abi {
+        self.inner_interface.get_abi()
+    }
+
+    async fn call(&self, func: &str, args: &Value) -> InterfaceResult {
+        let id = self.get_id();
+        self.write_entry(json!({
+            "timestamp": now_ms(),
+            "event": "call",
+            "interface": id,
+            "function": func,
+            "args": args,
+        }));
+        let result = self.inner_interface.call(func, args).await;
+        let entry = match &result {
+            Ok((answer_id, answer)) => json!({
+                "timestamp": now_ms(),
+                "event": "answer",
+                "interface": id,
+                "function": func,
+                "answerId": answer_id,
+                "answer": answer,
+            }),
+            Err(e) => json!({
+                "timestamp": now_ms(),
+                "event": "error",
+                "interface": id,
+                "function": func,
+                "error": e,
+            }),
+        };
+        self.write_entry(entry);
+        result
+    }
+}
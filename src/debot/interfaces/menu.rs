@@ -1,5 +1,6 @@
-use super::dinterface::{decode_string_arg};
-use crate::debot::term_browser::{action_input};
+use super::dinterface::{decode_bool_arg, decode_string_arg};
+use crate::debot::term_browser::{action_input, terminal_input};
+use std::collections::BTreeSet;
 use serde_json::{Value, json};
 use serde::{de, Deserialize, Deserializer};
 use ton_client::abi::Abi;
@@ -87,13 +88,18 @@ impl Menu {
                 println!("   {}", menu.description);
             }
         }
+        let multiselect = decode_bool_arg(args, "multiselect").unwrap_or(false);
+        if multiselect {
+            let mut indices = vec![];
+            terminal_input("Enter item numbers separated by commas:", |val| {
+                indices = parse_multi_select(val, menu_items.len())?;
+                Ok(())
+            })?;
+            let handler_id = menu_items[indices[0] as usize].handler_id;
+            return Ok(( handler_id, json!({ "indices": indices }) ));
+        }
         loop {
-            let res = action_input(menu_items.len());
-            if res.is_err() {
-                println!("{}", res.unwrap_err());
-                continue;
-            }
-            let (n, _, _) = res.unwrap();
+            let (n, _, _) = action_input(menu_items.len())?;
             let menu = menu_items.get(n - 1);
             if menu.is_none() {
                 println!("Invalid menu. Try again.");
@@ -106,6 +112,28 @@ impl Menu {
     }
 }
 
+/// Parses a comma-separated list of 1-based menu item numbers
+/// into a sorted list of unique 0-based item indices.
+fn parse_multi_select(input: &str, max: usize) -> Result<Vec<u32>, String> {
+    let mut indices = BTreeSet::new();
+    for item in input.split(',') {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+        let n = usize::from_str_radix(item, 10)
+            .map_err(|_| format!("invalid item number \"{}\"", item))?;
+        if n == 0 || n > max {
+            return Err(format!("item number {} is out of range", n));
+        }
+        indices.insert((n - 1) as u32);
+    }
+    if indices.is_empty() {
+        return Err("no items selected".to_string());
+    }
+    Ok(indices.into_iter().collect())
+}
+
 #[async_trait::async_trait]
 impl DebotInterface for Menu {
     fn get_id(&self) -> String {
@@ -123,3 +151,18 @@ impl DebotInterface for Menu {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_select() {
+        assert_eq!(parse_multi_select("1,3", 4).unwrap(), vec![0, 2]);
+        assert_eq!(parse_multi_select(" 3, 1 ,3", 4).unwrap(), vec![0, 2]);
+        assert!(parse_multi_select("1,5", 4).is_err());
+        assert!(parse_multi_select("0", 4).is_err());
+        assert!(parse_multi_select("a", 4).is_err());
+        assert!(parse_multi_select(",", 4).is_err());
+    }
+}
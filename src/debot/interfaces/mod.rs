@@ -17,20 +17,26 @@ pub mod confirm_input;
 pub mod dinterface;
 pub mod echo;
 pub mod menu;
+pub mod network;
 pub mod number_input;
+pub mod qr_code;
 pub mod signing_box_input;
 pub mod encryption_box_input;
 pub mod stdout;
 pub mod input_interface;
+pub mod logging_interface;
 pub mod terminal;
 pub mod userinfo;
 pub use address_input::AddressInput;
 pub use amount_input::AmountInput;
 pub use confirm_input::ConfirmInput;
 pub use menu::Menu;
+pub use network::Network;
 pub use number_input::NumberInput;
+pub use qr_code::QrCode;
 pub use signing_box_input::SigningBoxInput;
 pub use input_interface::InputInterface;
+pub use logging_interface::LoggingInterface;
 pub use encryption_box_input::EncryptionBoxInput;
 pub use terminal::Terminal;
 pub use userinfo::UserInfo;
\ No newline at end of file
@@ -0,0 +1,213 @@
+use super::dinterface::{decode_answer_id, decode_array, decode_string_arg};
+use serde_json::{json, Value};
+use std::time::Duration;
+use ton_client::abi::Abi;
+use ton_client::debot::{DebotInterface, InterfaceResult};
+
+const ID: &str = "e38aed5884dc3e4426a87c083faaf4fa08109189fbc0c79281112f52e062d8ee";
+
+/// Timeout of a single HTTP request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Maximum size of the response body returned to DeBot.
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+
+const ABI: &str = r#"
+{
+	"ABI version": 2,
+	"version": "2.2",
+	"header": ["time"],
+	"functions": [
+		{
+			"name": "get",
+			"inputs": [
+				{"name":"answerId","type":"uint32"},
+				{"name":"url","type":"string"},
+				{"name":"headers","type":"string[]"}
+			],
+			"outputs": [
+				{"name":"statusCode","type":"int32"},
+				{"name":"retHeaders","type":"string[]"},
+				{"name":"content","type":"string"}
+			]
+		},
+		{
+			"name": "post",
+			"inputs": [
+				{"name":"answerId","type":"uint32"},
+				{"name":"url","type":"string"},
+				{"name":"headers","type":"string[]"},
+				{"name":"body","type":"string"}
+			],
+			"outputs": [
+				{"name":"statusCode","type":"int32"},
+				{"name":"retHeaders","type":"string[]"},
+				{"name":"content","type":"string"}
+			]
+		},
+		{
+			"name": "constructor",
+			"inputs": [
+			],
+			"outputs": [
+			]
+		}
+	],
+	"data": [
+	],
+	"events": [
+	],
+	"fields": [
+		{"name":"_pubkey","type":"uint256"},
+		{"name":"_timestamp","type":"uint64"},
+		{"name":"_constructorFlag","type":"bool"}
+	]
+}
+"#;
+
+/// Performs HTTP requests on behalf of DeBot. Available only with `--allow-network`
+/// because it lets DeBots reach arbitrary URLs.
+pub struct Network {
+    timeout: Duration,
+    max_response_size: usize,
+}
+
+impl Network {
+    pub fn new() -> Self {
+        Self::with_limits(REQUEST_TIMEOUT, MAX_RESPONSE_SIZE)
+    }
+
+    pub fn with_limits(timeout: Duration, max_response_size: usize) -> Self {
+        Self { timeout, max_response_size }
+    }
+
+    async fn request(&self, method: reqwest::Method, args: &Value) -> InterfaceResult {
+        let answer_id = decode_answer_id(args)?;
+        let url = decode_string_arg(args, "url")?;
+        let headers = decode_array(args, "headers", |header| {
+            decode_string_arg(&json!({ "header": header }), "header").ok()
+        })?;
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| format!("failed to create http client: {}", e))?;
+        let mut request = client.request(method.clone(), &url);
+        for header in headers {
+            let (name, value) = header.split_once(':')
+                .ok_or(format!("invalid header \"{}\": must be \"Name: value\"", header))?;
+            request = request.header(name.trim(), value.trim());
+        }
+        if method == reqwest::Method::POST {
+            request = request.body(decode_string_arg(args, "body")?);
+        }
+        let mut response = request.send().await
+            .map_err(|e| format!("{} request to {} failed: {}", method, url, e))?;
+
+        let status_code = response.status().as_u16() as i32;
+        let ret_headers: Vec<String> = response.headers().iter()
+            .map(|(name, value)| format!("{}: {}", name, String::from_utf8_lossy(value.as_bytes())))
+            .collect();
+        let mut content = vec![];
+        while let Some(chunk) = response.chunk().await
+            .map_err(|e| format!("failed to read response from {}: {}", url, e))?
+        {
+            if content.len() + chunk.len() > self.max_response_size {
+                return Err(format!(
+                    "response from {} exceeds the maximum size of {} bytes", url, self.max_response_size
+                ));
+            }
+            content.extend_from_slice(&chunk);
+        }
+        Ok((answer_id, json!({
+            "statusCode": status_code,
+            "retHeaders": ret_headers,
+            "content": String::from_utf8_lossy(&content),
+        })))
+    }
+}
+
+#[async_trait::async_trait]
+impl DebotInterface for Network {
+    fn get_id(&self) -> String {
+        ID.to_string()
+    }
+
+    fn get_abi(&self) -> Abi {
+        Abi::Json(ABI.to_owned())
+    }
+
+    async fn call(&self, func: &str, args: &Value) -> InterfaceResult {
+        match func {
+            "get" => self.request(reqwest::Method::GET, args).await,
+            "post" => self.request(reqwest::Method::POST, args).await,
+            _ => Err(format!("function \"{}\" is not implemented", func)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves the single HTTP request with `body` and returns the received request.
+    async fn mock_server(body: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/data", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![];
+            let mut buf = [0u8; 4096];
+            // read the head and the body of the request up to its content length
+            loop {
+                let len = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..len]);
+                let text = String::from_utf8_lossy(&request).to_lowercase();
+                if let Some(head_end) = text.find("\r\n\r\n") {
+                    let content_length = text.lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .and_then(|len| len.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if request.len() >= head_end + 4 + content_length {
+                        break;
+                    }
+                }
+                if len == 0 {
+                    break;
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 201 Created\r\nContent-Length: {}\r\nX-Mock: 1\r\nConnection: close\r\n\r\n{}",
+                body.len(), body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_network_interface() {
+        let (url, server) = mock_server("hello").await;
+        let args = json!({
+            "answerId": "7",
+            "url": hex::encode(&url),
+            "headers": [hex::encode("X-Token: secret")],
+            "body": hex::encode("ping"),
+        });
+        let (answer_id, result) = Network::new().call("post", &args).await.unwrap();
+        assert_eq!(answer_id, 7);
+        assert_eq!(result["statusCode"], json!(201));
+        assert_eq!(result["content"], json!("hello"));
+        assert!(result["retHeaders"].as_array().unwrap().contains(&json!("x-mock: 1")));
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /data"));
+        assert!(request.to_lowercase().contains("x-token: secret"));
+        assert!(request.ends_with("ping"));
+
+        let (url, _server) = mock_server("too long response").await;
+        let args = json!({"answerId": "7", "url": url, "headers": []});
+        let err = Network::with_limits(REQUEST_TIMEOUT, 4).call("get", &args).await.unwrap_err();
+        assert!(err.contains("exceeds the maximum size"));
+    }
+}
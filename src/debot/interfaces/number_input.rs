@@ -66,7 +66,7 @@ impl NumberInput {
                 return Err("number is out of range".to_string());
             }
             Ok(())
-        });
+        })?;
         Ok((answer_id, json!({ "value": value })))
     }
 }
@@ -0,0 +1,116 @@
+use super::dinterface::{decode_answer_id, decode_string_arg};
+use serde_json::{Value, json};
+use ton_client::abi::Abi;
+use ton_client::debot::{DebotInterface, InterfaceResult};
+use qrcode::QrCode as QrEncoder;
+
+pub(super) const ID: &str = "1b3ed1a27b7c55e1a41fd0ad4b678d7386503b018af5282e5de9dd001a9401d3";
+
+const ABI: &str = r#"
+{
+	"ABI version": 2,
+	"version": "2.2",
+	"header": ["time"],
+	"functions": [
+		{
+			"name": "draw",
+			"inputs": [
+				{"name":"answerId","type":"uint32"},
+				{"name":"data","type":"string"}
+			],
+			"outputs": [
+			]
+		},
+		{
+			"name": "constructor",
+			"inputs": [
+			],
+			"outputs": [
+			]
+		}
+	],
+	"data": [
+	],
+	"events": [
+	],
+	"fields": [
+		{"name":"_pubkey","type":"uint256"},
+		{"name":"_timestamp","type":"uint64"},
+		{"name":"_constructorFlag","type":"bool"}
+	]
+}
+"#;
+
+pub struct QrCode {
+    is_json: bool,
+}
+
+impl QrCode {
+    pub fn new(is_json: bool) -> Self {
+        Self { is_json }
+    }
+
+    fn draw(&self, args: &Value) -> InterfaceResult {
+        let answer_id = decode_answer_id(args)?;
+        let data = decode_string_arg(args, "data")?;
+        if self.is_json {
+            println!("{:#}", json!({
+                "data": data,
+                "png": render_png(&data)?,
+            }));
+        } else {
+            println!();
+            qr2term::print_qr(&data).map_err(|e| format!("failed to print QR code: {}", e))?;
+            println!();
+        }
+        Ok((answer_id, json!({})))
+    }
+}
+
+/// Renders data as QR code PNG image encoded in base64.
+fn render_png(data: &str) -> Result<String, String> {
+    let code = QrEncoder::new(data.as_bytes())
+        .map_err(|e| format!("failed to encode QR code: {}", e))?;
+    let image = code.render::<image::Luma<u8>>().build();
+    let mut png = std::io::Cursor::new(vec![]);
+    image::DynamicImage::ImageLuma8(image)
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .map_err(|e| format!("failed to render QR code: {}", e))?;
+    Ok(base64::encode(png.into_inner()))
+}
+
+#[async_trait::async_trait]
+impl DebotInterface for QrCode {
+    fn get_id(&self) -> String {
+        ID.to_string()
+    }
+
+    fn get_abi(&self) -> Abi {
+        Abi::Json(ABI.to_owned())
+    }
+
+    async fn call(&self, func: &str, args: &Value) -> InterfaceResult {
+        match func {
+            "draw" => self.draw(args),
+            _ => Err(format!("function \"{}\" is not implemented", func)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_qrcode_call() {
+        let iface = QrCode::new(true);
+        assert_eq!(iface.get_id(), ID);
+        let args = json!({
+            "answerId": "7",
+            "data": "0:841288ed3b55d9cdafa806807f02a0ae0c169aa5edfe88a789a6482429756a94",
+        });
+        let (answer_id, _) = iface.call("draw", &args).await.unwrap();
+        assert_eq!(answer_id, 7);
+        assert!(iface.call("scan", &args).await.is_err());
+    }
+}
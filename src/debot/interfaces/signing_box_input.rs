@@ -56,10 +56,15 @@ pub struct SigningBoxInput {
     handles: RwLock<Vec<TerminalSigningBox>>,
     client: TonClient,
     processor: Arc<RwLock<ChainProcessor>>,
+    signing_command: Option<String>,
 }
 impl SigningBoxInput {
-    pub fn new(client: TonClient, processor: Arc<RwLock<ChainProcessor>>) -> Self {
-        Self { handles: RwLock::new(vec![]), client, processor }
+    pub fn new(
+        client: TonClient,
+        processor: Arc<RwLock<ChainProcessor>>,
+        signing_command: Option<String>,
+    ) -> Self {
+        Self { handles: RwLock::new(vec![]), client, processor, signing_command }
     }
 
     async fn get(&self, args: &Value) -> InterfaceResult {
@@ -77,9 +82,14 @@ impl SigningBoxInput {
         let result = self.processor.write().await.next_signing_box();
         match result {
             Err(ProcessorError::InterfaceCallNeeded) => {
-                let signing_box = TerminalSigningBox::new::<&[u8]>(
-                    self.client.clone(), possible_keys, None
-                ).await?;
+                let signing_box = match &self.signing_command {
+                    Some(command) => TerminalSigningBox::new_with_command(
+                        self.client.clone(), command.clone(), possible_keys
+                    ).await?,
+                    None => TerminalSigningBox::new::<&[u8]>(
+                        self.client.clone(), possible_keys, None
+                    ).await?,
+                };
                 let handle = signing_box.handle();
                 self.handles.write().await.push(signing_box);
                 Ok((answer_id, json!({ "handle": handle.0})))
@@ -1,6 +1,7 @@
 use serde_json::{Value, json};
 use ton_client::debot::{DebotInterface, InterfaceResult};
 use ton_client::abi::Abi;
+use super::dinterface::decode_bytes_string_arg;
 
 const STDOUT_ID: &str = "c91dcc3fddb30485a3a07eb7c1e5e2aceaf75f4bc2678111de1f25291cdda80b";
 
@@ -37,8 +38,7 @@ impl Stdout {
 		Self {}
 	}
     pub fn print(&self, args: &Value) -> InterfaceResult {
-		let text_vec = hex::decode(args["message"].as_str().unwrap()).unwrap();
-		let text = std::str::from_utf8(&text_vec).unwrap();
+		let text = decode_bytes_string_arg(args, "message")?;
 		println!("{}", text);
 		Ok((0, json!({})))
     }
@@ -1,5 +1,5 @@
 use super::dinterface::{decode_answer_id, decode_bool_arg, decode_prompt, decode_string_arg, Printer};
-use crate::debot::term_browser::terminal_input;
+use crate::debot::term_browser::{terminal_input, terminal_input_hidden};
 use serde_json::{Value, json};
 use ton_client::abi::Abi;
 use ton_client::debot::{DebotInterface, InterfaceResult};
@@ -81,8 +81,13 @@ impl Terminal {
         let answer_id = decode_answer_id(args)?;
         let prompt = decode_prompt(args)?;
         let multiline = decode_bool_arg(args, "multiline")?;
+        let masked = decode_bool_arg(args, "mask")
+            .or_else(|_| decode_bool_arg(args, "password"))
+            .unwrap_or(false);
         let mut value = String::new();
-        if multiline {
+        if masked {
+            value = terminal_input_hidden(&prompt)?;
+        } else if multiline {
             println!("{}", &prompt);
             if cfg!(windows) {
                 println!("(Ctrl+Z to exit)");
@@ -93,7 +98,7 @@ impl Terminal {
                 .map_err(|e| format!("input error: {}", e))?;
             println!();
         } else {
-            value = terminal_input(&prompt, |_val| Ok(()));
+            value = terminal_input(&prompt, |_val| Ok(()))?;
         }
         Ok((answer_id, json!({ "value": value })))
     }
@@ -103,7 +108,7 @@ impl Terminal {
         let value = terminal_input(&decode_prompt(args)?, |val| {
             let _ = decode_abi_bigint(val).map_err(|e| format!("{}", e))?;
             Ok(())
-        });
+        })?;
         Ok((answer_id, json!({ "value": value })))
     }
 
@@ -112,17 +117,17 @@ impl Terminal {
         let value = terminal_input(&decode_prompt(args)?, |val| {
             let _ = decode_abi_bigint(val).map_err(|e| format!("{}", e))?;
             Ok(())
-        });
+        })?;
         Ok((answer_id, json!({ "value": value })))
     }
 
     fn input_tokens(&self, args: &Value) -> InterfaceResult {
         let answer_id = decode_answer_id(args)?;
         let mut nanotokens = String::new();
-        let _ = terminal_input(&decode_prompt(args)?, |val| {
+        terminal_input(&decode_prompt(args)?, |val| {
             nanotokens = convert_token(val)?;
             Ok(())
-        });
+        })?;
         Ok((answer_id, json!({ "value": nanotokens })))
     }
 
@@ -130,14 +135,14 @@ impl Terminal {
         let answer_id = decode_answer_id(args)?;
         println!("{}", decode_prompt(args)?);
         let mut yes_no = false;
-        let _ = terminal_input("(y/n)", |val| {
+        terminal_input("(y/n)", |val| {
             yes_no = match val.as_str() {
                 "y" => true,
                 "n" => false,
                 _ => return Err("invalid enter".to_string()),
             };
             Ok(())
-        });
+        })?;
         Ok((answer_id, json!({ "value": yes_no })))
     }
 
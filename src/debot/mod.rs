@@ -10,6 +10,7 @@
 * See the License for the specific TON DEV software governing permissions and
 * limitations under the License.
 */
+mod answers;
 mod callbacks;
 mod interfaces;
 mod pipechain;
@@ -21,8 +22,10 @@ pub mod term_browser;
 use crate::config::Config;
 use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use simplelog::*;
-use term_browser::{run_debot_browser, terminal_input, input, action_input};
+use term_browser::{run_debot_browser, terminal_input, input, action_input, install_interrupt_handler};
+use crate::call::format_json_result;
 use crate::helpers::load_ton_address;
+use answers::{Answers, BROWSER_KEY};
 use callbacks::Callbacks;
 use processor::{ChainProcessor, ProcessorError};
 use pipechain::{ApproveKind, PipeChain, ChainLink};
@@ -66,6 +69,61 @@ pub fn create_debot_command<'a, 'b>() -> App<'a, 'b> {
                         .takes_value(true)
                         .help("Define keypair to auto sign transactions."),
                 )
+                .arg(
+                    Arg::with_name("ANSWERS")
+                        .long("answers")
+                        .takes_value(true)
+                        .help("Path to the json file with answers for DeBot interface calls."),
+                )
+                .arg(
+                    Arg::with_name("DEBOT_LOG")
+                        .long("debot-log")
+                        .takes_value(true)
+                        .help("Path to the file where all DeBot interface calls are logged in JSON Lines format."),
+                )
+                .arg(
+                    Arg::with_name("INPUT_TIMEOUT")
+                        .long("input-timeout")
+                        .takes_value(true)
+                        .help("Timeout in seconds for a single user input. DeBot is aborted if no answer is given in time."),
+                )
+                .arg(
+                    Arg::with_name("MAX_STEPS")
+                        .long("max-steps")
+                        .takes_value(true)
+                        .help("Maximum number of DeBot interface calls. DeBot is aborted when the limit is exceeded. Unlimited by default."),
+                )
+                .arg(
+                    Arg::with_name("SESSION_TIMEOUT")
+                        .long("session-timeout")
+                        .takes_value(true)
+                        .help("Timeout in seconds for the whole DeBot session. DeBot is aborted on the next interface call after the timeout. Unlimited by default."),
+                )
+                .arg(
+                    Arg::with_name("DISABLE_INTERFACE")
+                        .long("disable-interface")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Name or id of the DeBot interface which should not be available to DeBots (e.g. signingBox, terminal). Can be specified several times."),
+                )
+                .arg(
+                    Arg::with_name("ALLOW_NETWORK")
+                        .long("allow-network")
+                        .help("Makes the Network interface available to DeBots, so they can send HTTP requests to arbitrary URLs."),
+                )
+                .arg(
+                    Arg::with_name("CONFIRM_YES")
+                        .long("confirm-yes")
+                        .takes_value(true)
+                        .help("Token accepted as \"yes\" by the ConfirmInput interface (case-insensitive). \"yes\" and \"true\" are accepted too. Default is \"y\"."),
+                )
+                .arg(
+                    Arg::with_name("CONFIRM_NO")
+                        .long("confirm-no")
+                        .takes_value(true)
+                        .help("Token accepted as \"no\" by the ConfirmInput interface (case-insensitive). \"no\" and \"false\" are accepted too. Default is \"n\"."),
+                )
         )
         .subcommand(
             SubCommand::with_name("invoke")
@@ -122,7 +180,40 @@ pub async fn debot_command(m: &ArgMatches<'_>, config: Config) -> Result<(), Str
     Err("unknown debot command".to_owned())
 }
 
-async fn fetch_command(m: &ArgMatches<'_>, config: Config) -> Result<(), String> {
+fn check_confirm_tokens(config: &Config) -> Result<(), String> {
+    let (yes, no) = (config.debot_confirm_yes.trim(), config.debot_confirm_no.trim());
+    if yes.is_empty() || no.is_empty() || yes.eq_ignore_ascii_case(no) {
+        return Err("confirm tokens must be non-empty and differ from each other".to_owned());
+    }
+    Ok(())
+}
+
+async fn fetch_command(m: &ArgMatches<'_>, mut config: Config) -> Result<(), String> {
+    install_interrupt_handler();
+    config.debot_log = m.value_of("DEBOT_LOG").map(|x| x.to_owned());
+    config.debot_input_timeout = m.value_of("INPUT_TIMEOUT")
+        .map(|x| u64::from_str_radix(x, 10)
+            .map_err(|e| format!("failed to parse input timeout: {}", e)))
+        .transpose()?;
+    config.debot_max_steps = m.value_of("MAX_STEPS")
+        .map(|x| u64::from_str_radix(x, 10)
+            .map_err(|e| format!("failed to parse max steps: {}", e)))
+        .transpose()?;
+    config.debot_session_timeout = m.value_of("SESSION_TIMEOUT")
+        .map(|x| u64::from_str_radix(x, 10)
+            .map_err(|e| format!("failed to parse session timeout: {}", e)))
+        .transpose()?;
+    config.debot_allow_network = m.is_present("ALLOW_NETWORK");
+    if let Some(yes) = m.value_of("CONFIRM_YES") {
+        config.debot_confirm_yes = yes.to_owned();
+    }
+    if let Some(no) = m.value_of("CONFIRM_NO") {
+        config.debot_confirm_no = no.to_owned();
+    }
+    check_confirm_tokens(&config)?;
+    config.disabled_interfaces = m.values_of("DISABLE_INTERFACE")
+        .map(|values| values.map(|x| x.to_owned()).collect())
+        .unwrap_or_default();
     let addr = m.value_of("ADDRESS");
     let pipechain = m.value_of("PIPECHAIN");
     let signkey_path = m.value_of("SIGNKEY")
@@ -137,12 +228,16 @@ async fn fetch_command(m: &ArgMatches<'_>, config: Config) -> Result<(), String>
     } else {
         PipeChain::new()
     };
+    let answers = m.value_of("ANSWERS")
+        .map(Answers::from_file)
+        .transpose()?
+        .unwrap_or_default();
     let addr = load_ton_address(addr.unwrap(), &config)?;
-    let result = run_debot_browser(addr.as_str(), config, pipechain, signkey_path).await;
+    let result = run_debot_browser(addr.as_str(), config.clone(), pipechain, answers, signkey_path).await;
     match result {
         Ok(Some(arg)) => {
             if !is_json { println!("Returned value:"); }
-            println!("{:#}", arg);
+            println!("{}", format_json_result(&arg, &config));
             Ok(())
         }
         Err(err) if err.contains("NoMoreChainlinks") => Ok(()),
@@ -156,3 +251,20 @@ async fn invoke_command(m: &ArgMatches<'_>, config: Config) -> Result<(), String
     let _ = m.value_of("MESSAGE").unwrap().to_owned();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_tokens_from_config_file() {
+        let config: Config = serde_json::from_str(r#"{"url": "https://example.com", "retries": 3}"#).unwrap();
+        assert_eq!(config.debot_confirm_yes, "y");
+        assert_eq!(config.debot_confirm_no, "n");
+        assert_eq!(check_confirm_tokens(&config), Ok(()));
+
+        let mut config = config;
+        config.debot_confirm_yes = "N".to_owned();
+        assert!(check_confirm_tokens(&config).is_err());
+    }
+}
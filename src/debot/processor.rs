@@ -1,5 +1,5 @@
 use serde_json::Value;
-use super::{ApproveKind, PipeChain, ChainLink};
+use super::{Answers, ApproveKind, PipeChain, ChainLink};
 use std::vec::IntoIter;
 use ton_client::debot::DebotActivity;
 use ton_client::abi::{CallSet, Abi};
@@ -19,12 +19,17 @@ pub enum ProcessorError {
 pub struct ChainProcessor {
     pipechain: PipeChain,
     chain_iter: IntoIter<ChainLink>,
+    answers: Answers,
 }
 
 impl ChainProcessor {
-    pub fn new(mut pipechain: PipeChain ) -> Self {
+    pub fn new(mut pipechain: PipeChain, answers: Answers) -> Self {
         let chain_vec = std::mem::take(&mut pipechain.chain);
-        Self { pipechain, chain_iter: chain_vec.into_iter() }
+        Self { pipechain, chain_iter: chain_vec.into_iter(), answers }
+    }
+
+    pub fn next_answer(&mut self, interface: &str, method: &str) -> Option<Value> {
+        self.answers.next(interface, method)
     }
 
     pub fn abi(&self) -> Option<Abi> {
@@ -11,17 +11,21 @@
 * limitations under the License.
 */
 use super::term_signing_box::TerminalSigningBox;
+use super::interfaces::logging_interface::flush_debot_log;
 use crate::config::Config;
 use crate::helpers::{create_client, load_ton_address, load_abi, TonClient};
-use std::io::{self, BufRead, Write};
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 use serde_json::json;
 use ton_client::abi::{ Abi, CallSet, ParamsOfEncodeInternalMessage, ParamsOfDecodeMessage,
     encode_internal_message, decode_message};
 use ton_client::boc::{ParamsOfParse, parse_message};
 use ton_client::debot::{DebotInterfaceExecutor, DEngine, DebotInfo, DEBOT_WC};
 use std::collections::{HashMap, VecDeque};
-use super::{Callbacks, ChainLink, PipeChain, ChainProcessor, SupportedInterfaces};
+use super::{Answers, BROWSER_KEY, Callbacks, ChainLink, PipeChain, ChainProcessor, SupportedInterfaces};
 
 const BROWSER_ID: &str = "0000000000000000000000000000000000000000000000000000000000000000";
 /// Stores Debot info needed for DBrowser.
@@ -32,6 +36,42 @@ struct DebotEntry {
     info: DebotInfo,
 }
 
+/// Limits the number of interface calls and the duration of the DeBot session,
+/// so a looping DeBot is aborted instead of running forever.
+struct StepGuard {
+    steps: u64,
+    max_steps: Option<u64>,
+    started: Instant,
+    timeout: Option<Duration>,
+}
+
+impl StepGuard {
+    fn new(max_steps: Option<u64>, timeout: Option<u64>) -> Self {
+        Self {
+            steps: 0,
+            max_steps,
+            started: Instant::now(),
+            timeout: timeout.map(Duration::from_secs),
+        }
+    }
+
+    /// Registers one more step. Fails if any of the limits is exceeded.
+    fn step(&mut self) -> Result<(), String> {
+        self.steps += 1;
+        if let Some(max) = self.max_steps {
+            if self.steps > max {
+                return Err(format!("DeBot aborted: limit of {} interface calls is exceeded", max));
+            }
+        }
+        if let Some(timeout) = self.timeout {
+            if self.started.elapsed() > timeout {
+                return Err(format!("DeBot aborted: session timeout of {} seconds is exceeded", timeout.as_secs()));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Top level object. Created only once.
 struct TerminalBrowser {
     client: TonClient,
@@ -48,11 +88,12 @@ struct TerminalBrowser {
     interactive: bool,
     /// Browser exit argument. Initialized only if DeBot sends message to the DeBot Browser address.
     pub exit_arg: Option<serde_json::Value>,
+    guard: StepGuard,
 }
 
 impl TerminalBrowser {
-    async fn new(client: TonClient, addr: &str, config: Config, pipechain: PipeChain) -> Result<Self, String> {
-        let processor = ChainProcessor::new(pipechain);
+    async fn new(client: TonClient, addr: &str, config: Config, pipechain: PipeChain, answers: Answers) -> Result<Self, String> {
+        let processor = ChainProcessor::new(pipechain, answers);
         let start = processor.default_start();
         let interactive = processor.interactive();
         let call_set = processor.initial_call_set();
@@ -63,7 +104,8 @@ impl TerminalBrowser {
             client: client.clone(),
             msg_queue: Default::default(),
             bots: HashMap::new(),
-            interfaces: SupportedInterfaces::new(client.clone(), &config, processor.clone()),
+            interfaces: SupportedInterfaces::new(client.clone(), &config, processor.clone())?,
+            guard: StepGuard::new(config.debot_max_steps, config.debot_session_timeout),
             config,
             processor,
             interactive,
@@ -121,14 +163,22 @@ impl TerminalBrowser {
         }
         let mut run_debot = autorun;
         if !run_debot {
-            let _ = terminal_input("Run the DeBot (y/n)?", |val| {
+            if let Some(answer) = self.processor.write().await.next_answer(BROWSER_KEY, "run") {
+                run_debot = answer.as_bool().unwrap_or(false);
+                if !run_debot {
+                    return Err("DeBot rejected".to_string());
+                }
+            }
+        }
+        if !run_debot {
+            terminal_input("Run the DeBot (y/n)?", |val| {
                 run_debot = match val.as_str() {
                     "y" => true,
                     "n" => false,
                     _ => return Err("invalid enter".to_string()),
                 };
                 Ok(())
-            });
+            })?;
         }
         if !run_debot {
             return Err("DeBot rejected".to_string());
@@ -156,6 +206,7 @@ impl TerminalBrowser {
         interface_id: String,
         debot_addr: &str,
     ) -> Result<(), String> {
+        self.guard.step()?;
         let debot = self.bots.get_mut(debot_addr)
             .ok_or_else(|| "Internal browser error: debot not found".to_owned())?;
         if let Some(result) = self.interfaces.try_execute(&msg, &interface_id, &debot.info.dabi_version).await {
@@ -245,9 +296,13 @@ where
             println!("failed to flush: {}", e);
             return input_str;
         }
-        if let Err(e) = reader.read_line(&mut input_str) {
-            println!("failed to read line: {}", e);
-            return input_str;
+        match reader.read_line(&mut input_str) {
+            Ok(0) => return input_str,
+            Ok(_) => {},
+            Err(e) => {
+                println!("failed to read line: {}", e);
+                return input_str;
+            }
         }
         argc = input_str
             .split_whitespace()
@@ -256,46 +311,164 @@ where
     input_str.trim().to_owned()
 }
 
-pub(crate) fn terminal_input<F>(prompt: &str, mut validator: F) -> String
+/// Timeout in seconds for a single stdin read in DeBot interfaces. 0 means no timeout.
+static INPUT_TIMEOUT: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn set_input_timeout(timeout: Option<u64>) {
+    INPUT_TIMEOUT.store(timeout.unwrap_or(0), Ordering::Relaxed);
+}
+
+fn read_line_blocking() -> io::Result<Option<String>> {
+    let mut line = String::new();
+    let n = io::stdin().read_line(&mut line)?;
+    Ok(if n == 0 { None } else { Some(line) })
+}
+
+/// Reads a line from stdin respecting the input timeout. Returns `None` on EOF.
+fn read_stdin_line() -> Result<Option<String>, String> {
+    let timeout = INPUT_TIMEOUT.load(Ordering::Relaxed);
+    let result = if timeout == 0 {
+        read_line_blocking()
+    } else {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(read_line_blocking());
+        });
+        rx.recv_timeout(Duration::from_secs(timeout))
+            .map_err(|_| format!("input timeout: no answer was given in {} seconds", timeout))?
+    };
+    result.map_err(|e| format!("failed to read line: {}", e))
+}
+
+/// Prints prompt and reads the first non-empty line from stdin.
+fn read_input(prompt: &str) -> Result<String, String> {
+    loop {
+        println!("{}", prompt);
+        let _ = io::stdout().flush();
+        match read_stdin_line()? {
+            Some(line) if !line.trim().is_empty() => return Ok(line.trim().to_owned()),
+            Some(_) => continue,
+            None => return Err("input is closed: no answer was given".to_owned()),
+        }
+    }
+}
+
+pub(crate) fn terminal_input<F>(prompt: &str, mut validator: F) -> Result<String, String>
 where
     F: FnMut(&String) -> Result<(), String>
 {
-    let stdio = io::stdin();
-    let mut reader = stdio.lock();
-    let mut writer = io::stdout();
-    let mut value = input(prompt, &mut reader, &mut writer);
+    let mut value = read_input(prompt)?;
     while let Err(e) = validator(&value) {
         println!("{}. Try again.", e);
-        value = input(prompt, &mut reader, &mut writer);
+        value = read_input(prompt)?;
     }
-    value
+    Ok(value)
 }
+
+/// Reads a line from the terminal without echoing typed characters.
+///
+/// Falls back to the ordinary `terminal_input` if stdin is not a TTY (e.g. piped input).
+pub(crate) fn terminal_input_hidden(prompt: &str) -> Result<String, String> {
+    if !io::stdin().is_terminal() {
+        return terminal_input(prompt, |_val| Ok(()));
+    }
+    loop {
+        println!("{}", prompt);
+        let _ = io::stdout().flush();
+        match rpassword::read_password() {
+            Ok(value) if !value.trim().is_empty() => return Ok(value.trim().to_owned()),
+            Ok(_) => continue,
+            Err(e) => return Err(format!("failed to read line: {}", e)),
+        }
+    }
+}
+
+/// Reads number of the action from stdin. Invalid input is reported and requested again,
+/// errors are returned only if stdin is closed or input timeout has expired.
 pub fn action_input(max: usize) -> Result<(usize, usize, Vec<String>), String> {
-    let mut a_str = String::new();
-    let mut argc = 0;
-    let mut argv = vec![];
     println!();
-    while argc == 0 {
+    loop {
         print!("debash$ ");
         let _ = io::stdout().flush();
-        io::stdin()
-            .read_line(&mut a_str)
-            .map_err(|e| format!("failed to read line: {}", e))?;
-        argv = a_str
+        let a_str = read_stdin_line()?
+            .ok_or("input is closed: no answer was given".to_owned())?;
+        let argv = a_str
             .split_whitespace()
-            .map(|x| x.parse::<String>().expect("parse error"))
+            .map(|x| x.to_owned())
             .collect::<Vec<String>>();
-        argc = argv.len();
+        if argv.is_empty() {
+            continue;
+        }
+        let n = match usize::from_str_radix(&argv[0], 10) {
+            Ok(n) => n,
+            Err(_) => {
+                println!("Oops! Invalid action. Try again, please.");
+                continue;
+            }
+        };
+        if n > max {
+            println!("Auch! Invalid action. Try again, please.");
+            continue;
+        }
+        return Ok((n, argv.len(), argv));
+    }
+}
+
+/// Installs SIGINT handler for the interactive DeBot session. The handler waits for the signal
+/// in a separate thread, so it works even while the browser is blocked on reading stdin.
+pub(crate) fn install_interrupt_handler() {
+    let terminal_mode = save_terminal_mode();
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(_) => return,
+        };
+        if runtime.block_on(tokio::signal::ctrl_c()).is_err() {
+            return;
+        }
+        if let Some(mode) = &terminal_mode {
+            restore_terminal_mode(mode);
+        }
+        println!();
+        println!("interrupted");
+        flush_debot_log();
+        let _ = io::stdout().flush();
+        std::process::exit(130);
+    });
+}
+
+#[cfg(unix)]
+fn save_terminal_mode() -> Option<String> {
+    if !io::stdin().is_terminal() {
+        return None;
     }
-    let n = usize::from_str_radix(&argv[0], 10)
-        .map_err(|_| "Oops! Invalid action. Try again, please.".to_string())?;
-    if n > max {
-        return Err("Auch! Invalid action. Try again, please.".to_string());
+    let out = std::process::Command::new("stty")
+        .arg("-g")
+        .stdin(std::process::Stdio::inherit())
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
     }
+    String::from_utf8(out.stdout).ok().map(|mode| mode.trim().to_owned())
+}
+
+#[cfg(unix)]
+fn restore_terminal_mode(mode: &str) {
+    let _ = std::process::Command::new("stty")
+        .arg(mode)
+        .stdin(std::process::Stdio::inherit())
+        .status();
+}
 
-    Ok((n, argc, argv))
+#[cfg(not(unix))]
+fn save_terminal_mode() -> Option<String> {
+    None
 }
 
+#[cfg(not(unix))]
+fn restore_terminal_mode(_mode: &str) {}
+
 /// Starts Terminal DeBot Browser with main DeBot.
 ///
 /// Fetches DeBot by address from blockchain and runs it according to pipechain.
@@ -303,11 +476,13 @@ pub async fn run_debot_browser(
     addr: &str,
     config: Config,
     mut pipechain: PipeChain,
+    answers: Answers,
     signkey_path: Option<String>,
 ) -> Result<Option<serde_json::Value>, String> {
     if !config.is_json {
         println!("Network: {}", config.url);
     }
+    set_input_timeout(config.debot_input_timeout);
     let ton = create_client(&config)?;
 
     if let Some(path) = signkey_path {
@@ -320,7 +495,7 @@ pub async fn run_debot_browser(
             }
         }
     }
-    let mut browser = TerminalBrowser::new(ton.clone(), addr, config, pipechain).await?;
+    let mut browser = TerminalBrowser::new(ton.clone(), addr, config, pipechain, answers).await?;
     loop {
         let mut next_msg = browser.msg_queue.pop_front();
         while let Some(msg) = next_msg {
@@ -376,4 +551,30 @@ pub async fn run_debot_browser(
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_guard() {
+        // DeBot which calls an interface again from every answer never stops by itself
+        let mut guard = StepGuard::new(Some(5), None);
+        let mut calls = 0;
+        let err = loop {
+            if let Err(e) = guard.step() {
+                break e;
+            }
+            calls += 1;
+        };
+        assert_eq!(calls, 5);
+        assert!(err.contains("limit of 5 interface calls"));
+
+        let mut guard = StepGuard::new(None, Some(0));
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(guard.step().unwrap_err().contains("session timeout"));
+
+        let mut guard = StepGuard::new(None, None);
+        for _ in 0..1000 {
+            guard.step().unwrap();
+        }
+    }
+}
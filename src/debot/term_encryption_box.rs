@@ -3,7 +3,7 @@ use crate::crypto::load_keypair;
 use crate::helpers::{TonClient, HD_PATH};
 use std::io::{self};
 use ton_client::crypto::{
-    register_encryption_box, remove_encryption_box,
+    generate_random_sign_keys, register_encryption_box, remove_encryption_box,
     EncryptionBoxHandle, RegisteredEncryptionBox, ChaCha20ParamsEB, ChaCha20EncryptionBox,
     NaclBoxParamsEB, NaclEncryptionBox, NaclSecretBoxParamsEB, NaclSecretEncryptionBox
 };
@@ -20,6 +20,9 @@ pub(crate) struct ParamsOfTerminalEncryptionBox {
     pub their_pubkey: String,
     pub nonce: String,
     pub context: TonClient,
+    /// Keypair (path, seed phrase or inline keys) used instead of asking the user.
+    pub keys: Option<String>,
+    pub is_json: bool,
 }
 
 pub(super) struct TerminalEncryptionBox {
@@ -42,17 +45,26 @@ impl Drop for TerminalEncryptionBox {
 
 impl TerminalEncryptionBox {
     pub async fn new(params: ParamsOfTerminalEncryptionBox) -> Result<Self, String> {
-        let key: String;
-
-        {
-            let stdio = io::stdin();
-            let mut reader = stdio.lock();
-            let mut writer = io::stdout();
-            let enter_str = "enter seed phrase or path to keypair file";
-            let value = input(enter_str, &mut reader, &mut writer);
-            let pair = load_keypair(&value).map_err(|e| e)?;
-            key = format!("{:064}", pair.secret);
+        let pair = match &params.keys {
+            Some(keys) => load_keypair(keys)?,
+            None => {
+                let stdio = io::stdin();
+                let mut reader = stdio.lock();
+                let mut writer = io::stdout();
+                let enter_str = "enter seed phrase or path to keypair file (or \"new\" to generate random keys)";
+                let value = input(enter_str, &mut reader, &mut writer);
+                if value == "new" {
+                    generate_random_sign_keys(params.context.clone())
+                        .map_err(|e| format!("failed to generate keys: {}", e))?
+                } else {
+                    load_keypair(&value)?
+                }
+            },
+        };
+        if !params.is_json {
+            println!("Encryption box public key: {}", pair.public);
         }
+        let key = format!("{:064}", pair.secret);
 
         let registered_box = match params.box_type {
             EncryptionBoxType::SecretNaCl => {
@@ -102,3 +114,39 @@ impl TerminalEncryptionBox {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::create_client_local;
+    use ton_client::crypto::{
+        encryption_box_decrypt, encryption_box_encrypt,
+        ParamsOfEncryptionBoxDecrypt, ParamsOfEncryptionBoxEncrypt,
+    };
+
+    #[tokio::test]
+    async fn test_encryption_box_roundtrip() {
+        let client = create_client_local().unwrap();
+        let enc_box = TerminalEncryptionBox::new(ParamsOfTerminalEncryptionBox {
+            box_type: EncryptionBoxType::SecretNaCl,
+            their_pubkey: String::new(),
+            nonce: "cd7f99924bf422544046e83595dd5803f17536f5c9a11746".to_owned(),
+            context: client.clone(),
+            keys: Some("tests/samples/test.keys.json".to_owned()),
+            is_json: true,
+        }).await.unwrap();
+        assert_ne!(enc_box.handle().0, 0);
+
+        let data = base64::encode("debot payload");
+        let encrypted = encryption_box_encrypt(client.clone(), ParamsOfEncryptionBoxEncrypt {
+            encryption_box: enc_box.handle(),
+            data: data.clone(),
+        }).await.unwrap().data;
+        assert_ne!(encrypted, data);
+        let decrypted = encryption_box_decrypt(client.clone(), ParamsOfEncryptionBoxDecrypt {
+            encryption_box: enc_box.handle(),
+            data: encrypted,
+        }).await.unwrap().data;
+        assert_eq!(decrypted, data);
+    }
+}
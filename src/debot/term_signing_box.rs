@@ -2,9 +2,18 @@ use super::term_browser::input;
 use crate::crypto::load_keypair;
 use crate::helpers::{read_keys, TonClient};
 use std::io::{self, BufRead, Write, Read, BufReader};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use ton_client::ClientContext;
 use ton_client::crypto::{
-    get_signing_box, remove_signing_box, KeyPair, RegisteredSigningBox, SigningBoxHandle,
+    get_signing_box, register_signing_box, remove_signing_box, signing_box_get_public_key,
+    KeyPair, RegisteredSigningBox, SigningBox, SigningBoxHandle,
 };
+use ton_client::error::{ClientError, ClientResult};
+
+const SIGNING_COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
 
 pub(super) struct TerminalSigningBox {
     handle: SigningBoxHandle,
@@ -43,6 +52,28 @@ impl TerminalSigningBox {
         Ok(Self { handle, client })
     }
 
+    /// Creates signing box which delegates signing to the external program.
+    pub async fn new_with_command(client: TonClient, command: String, possible_keys: Vec<String>) -> Result<Self, String> {
+        let external = ExternalSigningBox { command, timeout: SIGNING_COMMAND_TIMEOUT };
+        let handle = register_signing_box(client.clone(), external)
+            .await
+            .map(|r| r.handle)
+            .map_err(|e| e.to_string())?;
+        let signing_box = Self { handle, client };
+
+        let public = signing_box_get_public_key(
+            signing_box.client.clone(),
+            RegisteredSigningBox { handle: signing_box.handle() },
+        )
+            .await
+            .map_err(|e| e.to_string())?
+            .pubkey;
+        if !possible_keys.is_empty() && !possible_keys.iter().any(|x| x.get(2..) == Some(public.as_str())) {
+            return Err(format!("Unexpected public key {} returned by the signing command.", public));
+        }
+        Ok(signing_box)
+    }
+
     pub fn handle(&self) -> SigningBoxHandle {
         self.handle.clone()
     }
@@ -67,6 +98,71 @@ impl Drop for TerminalSigningBox {
     }
 }
 
+/// Signing box which delegates signing to the external program (e.g. HSM or remote custody client).
+///
+/// The program is called as `<command> public` to get the public key and as `<command> sign`
+/// with hex encoded data on stdin to sign it. Both results are expected on stdout in hex.
+struct ExternalSigningBox {
+    command: String,
+    timeout: Duration,
+}
+
+impl ExternalSigningBox {
+    async fn run(&self, action: &str, input: &[u8]) -> Result<Vec<u8>, String> {
+        let mut parts = self.command.split_whitespace();
+        let program = parts.next().ok_or("signing command is empty".to_string())?;
+        let mut child = tokio::process::Command::new(program)
+            .args(parts)
+            .arg(action)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("failed to start signing command: {}", e))?;
+        let mut stdin = child.stdin.take()
+            .ok_or("failed to open stdin of signing command".to_string())?;
+        stdin.write_all(hex::encode(input).as_bytes()).await
+            .map_err(|e| format!("failed to write to signing command: {}", e))?;
+        drop(stdin);
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output()).await
+            .map_err(|_| format!("signing command timed out after {} seconds", self.timeout.as_secs()))?
+            .map_err(|e| format!("signing command failed: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("signing command exited with {}", output.status));
+        }
+        let result = String::from_utf8_lossy(&output.stdout);
+        hex::decode(result.trim())
+            .map_err(|e| format!("signing command returned malformed output: {}", e))
+    }
+}
+
+fn signing_error(message: String) -> ClientError {
+    ClientError::with_code_message(0, message)
+}
+
+#[async_trait::async_trait]
+impl SigningBox for ExternalSigningBox {
+    async fn get_public_key(&self, _context: Arc<ClientContext>) -> ClientResult<Vec<u8>> {
+        let key = self.run("public", &[]).await.map_err(signing_error)?;
+        if key.len() != 32 {
+            return Err(signing_error(format!(
+                "signing command returned malformed public key: expected 32 bytes, got {}", key.len()
+            )));
+        }
+        Ok(key)
+    }
+
+    async fn sign(&self, _context: Arc<ClientContext>, unsigned: &[u8]) -> ClientResult<Vec<u8>> {
+        let signature = self.run("sign", unsigned).await.map_err(signing_error)?;
+        if signature.len() != 64 {
+            return Err(signing_error(format!(
+                "signing command returned malformed signature: expected 64 bytes, got {}", signature.len()
+            )));
+        }
+        Ok(signature)
+    }
+}
+
 pub(super) fn input_keys<R, W>(
     prompt: Option<&str>,
     possible_keys: Vec<String>,
@@ -150,6 +246,58 @@ mod tests {
         assert_eq!(keys.secret, PRIVATE);
     }
 
+    /// Creates the shell script which acts as a signing command in the temp directory.
+    #[cfg(unix)]
+    fn create_signer_script(name: &str, signature: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("{}_{}", std::process::id(), name));
+        let mut file = File::create(&path).unwrap();
+        file.write_all(
+            format!(
+                "#!/bin/sh\ncase \"$1\" in\n  public) echo {} ;;\n  sign) cat > /dev/null; echo {} ;;\nesac\n",
+                PUBLIC, signature
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn external_signing_command() {
+        let signature = "ab".repeat(64);
+        let script = create_signer_script("fake_signer.sh", &signature);
+        let client = crate::helpers::create_client_local().unwrap();
+        let signing_box = TerminalSigningBox::new_with_command(
+            client.clone(), script.clone(), vec![format!("0x{}", PUBLIC)]
+        ).await.unwrap();
+        let result = ton_client::crypto::signing_box_sign(
+            client.clone(),
+            ton_client::crypto::ParamsOfSigningBoxSign {
+                signing_box: signing_box.handle(),
+                unsigned: base64::encode("message"),
+            },
+        ).await.unwrap();
+        assert_eq!(result.signature, signature);
+
+        let malformed = create_signer_script("fake_signer_malformed.sh", "zz");
+        let signing_box = TerminalSigningBox::new_with_command(
+            client.clone(), malformed.clone(), vec![]
+        ).await.unwrap();
+        let result = ton_client::crypto::signing_box_sign(
+            client,
+            ton_client::crypto::ParamsOfSigningBoxSign {
+                signing_box: signing_box.handle(),
+                unsigned: base64::encode("message"),
+            },
+        ).await;
+        assert!(result.is_err());
+        std::fs::remove_file(&script).unwrap();
+        std::fs::remove_file(&malformed).unwrap();
+    }
+
     #[test]
     fn load_key_from_seed() {
         let mut in_data = SEED.as_bytes();
@@ -13,6 +13,7 @@
 use crate::{contract_data_from_matches_or_config_alias, FullConfig, print_args,
             unpack_alternative_params};
 use crate::message::prepare_message;
+use crate::call::format_json_result;
 use clap::{ArgMatches, SubCommand, Arg, App};
 use ton_client::boc::internal::deserialize_cell_from_base64;
 use ton_client::error::ClientError;
@@ -20,7 +21,8 @@ use crate::config::Config;
 use crate::helpers::{load_ton_address, create_client, load_abi, now_ms, construct_account_from_tvc,
                      query_account_field, query_with_limit, create_client_verbose,
                      abi_from_matches_or_config, load_debug_info, wc_from_matches_or_config,
-                     get_blockchain_config, load_params, create_client_local};
+                     get_blockchain_config, load_params, create_client_local, load_state_libs, state_libs_dict,
+                     file_logger_installed};
 use crate::replay::{
     fetch, CONFIG_ADDR, replay, DUMP_NONE, DUMP_CONFIG, DUMP_ACCOUNT
 };
@@ -28,7 +30,7 @@ use std::io::{Write, BufRead};
 use std::collections::{HashSet, HashMap};
 use ton_block::{Message, Account, Serializable, Deserializable, Transaction,
                 MsgAddressInt, CurrencyCollection, GasLimitsPrices, ConfigParamEnum, TransactionTickTock, InRefValue, TrComputePhase};
-use ton_types::{UInt256, Cell, AccountId};
+use ton_types::{UInt256, Cell, AccountId, HashmapE};
 use ton_client::abi::{CallSet, Signer, FunctionHeader, encode_message, ParamsOfEncodeMessage};
 use ton_executor::{
     BlockchainConfig, ExecuteParams, OrdinaryTransactionExecutor, TransactionExecutor, TickTockTransactionExecutor
@@ -127,7 +129,8 @@ impl log::Log for DebugLogger {
 }
 
 pub fn init_debug_logger(trace_path: &str) -> Result<(), String> {
-    if trace_path == "nul" {
+    // explicit --log-file takes precedence, the trace is written there
+    if trace_path == "nul" || file_logger_installed() {
         return Ok(())
     }
     let logger = Box::new(DebugLogger::new(trace_path.to_string()));
@@ -237,6 +240,11 @@ pub fn create_debug_command<'a, 'b>() -> App<'a, 'b> {
         .long("--now")
         .help("Now timestamp (in milliseconds) for execution. If not set it is equal to the current timestamp.");
 
+    let libs_arg = Arg::with_name("LIBS")
+        .help("Path to the file with library cells used by the contract: boc with a single library cell or json array of base64 encoded cells.")
+        .long("--libs")
+        .takes_value(true);
+
     let msg_cmd = SubCommand::with_name("message")
         .about("Play message locally with trace")
         .arg(output_arg.clone())
@@ -248,6 +256,7 @@ pub fn create_debug_command<'a, 'b>() -> App<'a, 'b> {
         .arg(config_path_arg.clone())
         .arg(update_arg.clone().requires("BOC"))
         .arg(now_arg.clone())
+        .arg(libs_arg.clone())
         .arg(Arg::with_name("MESSAGE")
             .takes_value(true)
             .required(true)
@@ -275,7 +284,8 @@ pub fn create_debug_command<'a, 'b>() -> App<'a, 'b> {
             .help("Account address for account constructed from TVC.")
             .requires("TVC"))
         .arg(now_arg.clone())
-        .arg(config_path_arg.clone());
+        .arg(config_path_arg.clone())
+        .arg(libs_arg.clone());
 
     let deploy_cmd = SubCommand::with_name("deploy")
         .about("Play deploy locally with trace")
@@ -287,6 +297,7 @@ pub fn create_debug_command<'a, 'b>() -> App<'a, 'b> {
         .arg(sign_arg.clone())
         .arg(now_arg.clone())
         .arg(config_path_arg.clone())
+        .arg(libs_arg.clone())
         .arg(Arg::with_name("TVC")
             .required(true)
             .takes_value(true)
@@ -767,7 +778,7 @@ async fn debug_call_command(matches: &ArgMatches<'_>, full_config: &FullConfig,
     if is_getter && !out_res.is_empty() && !full_config.config.is_json {
         print!("Output: ");
         for msg in out_res {
-            println!("{:#}", msg)
+            println!("{}", format_json_result(&msg, &full_config.config))
         }
     }
 
@@ -992,12 +1003,14 @@ pub async fn decode_messages(tr: &Transaction, abi: Option<String>, config: &Con
     if config.is_json {
         let description = tr.read_description()
             .map_err(|e| format!("Failed to read transaction description: {e}"))?;
-        let (exit_code, gas_usage) = match description.compute_phase_ref() {
+        let (exit_code, exit_arg, gas_usage, vm_steps) = match description.compute_phase_ref() {
             Some(TrComputePhase::Vm(compute)) => (
                 compute.exit_code,
-                compute.gas_used.as_u64()
+                compute.exit_arg,
+                compute.gas_used.as_u64(),
+                compute.vm_steps,
             ),
-            _ => (0, 0)
+            _ => (0, None, 0, 0)
         };
         // let _tr = match ton_block_json::debug_transaction(tr.clone()) {
         //     Ok(tr) => serde_json::from_str::<Value>(&tr).unwrap(),
@@ -1010,14 +1023,16 @@ pub async fn decode_messages(tr: &Transaction, abi: Option<String>, config: &Con
         let result = json!({
             "description": {
                 "exit_code": exit_code,
+                "exit_arg": exit_arg,
                 "gas_usage": gas_usage,
+                "vm_steps": vm_steps,
                 "total_fees": tr.total_fees().grams.as_u128(),
                 // "in_msg": _in_msg,
             },
             "messages": output,
             // "transaction": _tr
         });
-        println!("{:#}", result);
+        println!("{}", format_json_result(&result, config));
     }
     Ok(res)
 }
@@ -1176,7 +1191,14 @@ pub async fn execute_debug(
         let tt = if is_tock { TransactionTickTock::Tock } else { TransactionTickTock::Tick };
         Box::new(TickTockTransactionExecutor::new(bc_config, tt))
     };
+    let libs = matches.and_then(|m| m.value_of("LIBS"))
+        .or(tonos_config.libs.as_deref());
+    let state_libs = match libs {
+        Some(path) => state_libs_dict(&load_state_libs(path)?)?,
+        None => HashmapE::with_bit_len(256),
+    };
     let params = ExecuteParams {
+        state_libs,
         block_unixtime: (time_in_ms / 1000) as u32,
         block_lt,
         last_tr_lt: Arc::new(AtomicU64::new(last_tr_lt)),
@@ -1602,7 +1624,7 @@ pub async fn debug_error(e: &ClientError, debug_params: DebugParams<'_>) -> Resu
         return Err(result)
     }
     if debug_params.config.is_json {
-        println!("{:#}", json!({"Error": e}));
+        println!("{}", format_json_result(&json!({"Error": e}), debug_params.config));
     } else {
         println!("Error: {}", result);
         println!("Execution failed. Starting debug...");
@@ -12,14 +12,16 @@
  */
 use crate::{load_abi, print_args};
 use crate::config::Config;
+use crate::call::format_json_result;
 use crate::decode::msg_printer::tree_of_cells_into_base64;
 use crate::helpers::{decode_msg_body, print_account, create_client_local, create_client_verbose, query_account_field, abi_from_matches_or_config, load_ton_address, load_ton_abi, create_client, query_message};
 use clap::{ArgMatches, SubCommand, Arg, App, AppSettings};
 use ton_types::{Cell, SliceData, write_boc, read_single_root_boc};
 use ton_block::{Account, Deserializable, Serializable, AccountStatus, StateInit};
-use ton_client::abi::{decode_account_data, ParamsOfDecodeAccountData};
+use ton_client::abi::{decode_account_data, MessageBodyType, ParamsOfDecodeAccountData};
 use serde::Serialize;
-use serde_json::json;
+use serde_json::{json, Value};
+use ton_abi::ParamType;
 
 pub fn create_decode_command<'a, 'b>() -> App<'a, 'b> {
     let tvc_cmd = SubCommand::with_name("stateinit")
@@ -45,11 +47,18 @@ pub fn create_decode_command<'a, 'b>() -> App<'a, 'b> {
             .about("Decodes body base64 string.")
             .arg(Arg::with_name("BODY")
                 .required(true)
-                .help("Message body encoded as base64."))
+                .help("Message body encoded as base64 or hex or path to the file with the body boc. Function call, function output and event bodies are supported."))
             .arg(Arg::with_name("ABI")
                 .long("--abi")
                 .takes_value(true)
                 .help("Path or link to the contract ABI file or pure json ABI data. Can be specified in the config file.")))
+        .subcommand(SubCommand::with_name("abi")
+            .about("Prints JSON schema of the contract ABI functions: inputs, outputs and required header fields.")
+            .arg(Arg::with_name("SCHEMA")
+                .long("--schema")
+                .takes_value(true)
+                .required(true)
+                .help("Path or link to the contract ABI file or pure json ABI data.")))
         .subcommand(SubCommand::with_name("msg")
             .about("Decodes message file.")
             .arg(Arg::with_name("MSG")
@@ -100,6 +109,9 @@ pub async fn decode_command(m: &ArgMatches<'_>, config: &Config) -> Result<(), S
     if let Some(m) = m.subcommand_matches("body") {
         return decode_body_command(m, config).await;
     }
+    if let Some(m) = m.subcommand_matches("abi") {
+        return decode_abi_schema_command(m, config).await;
+    }
     if let Some(m) = m.subcommand_matches("msg") {
         return decode_message_command(m, config).await;
     }
@@ -137,6 +149,74 @@ async fn decode_body_command(m: &ArgMatches<'_>, config: &Config) -> Result<(),
     Ok(())
 }
 
+async fn decode_abi_schema_command(m: &ArgMatches<'_>, config: &Config) -> Result<(), String> {
+    let abi = m.value_of("SCHEMA");
+    if !config.is_json {
+        print_args!(abi);
+    }
+    let contract = load_ton_abi(abi.unwrap(), config).await?;
+    println!("{}", format_json_result(&abi_schema(&contract), config));
+    Ok(())
+}
+
+fn abi_schema(contract: &ton_abi::Contract) -> Value {
+    let mut functions: Vec<&ton_abi::Function> = contract.functions().values().collect();
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+    let functions: Vec<Value> = functions.into_iter().map(|func| json!({
+        "name": func.name,
+        "id": format!("0x{:08x}", func.get_input_id()),
+        "header": func.header.iter().map(|p| param_schema(&p.name, &p.kind)).collect::<Vec<_>>(),
+        "inputs": func.input_params().iter().map(|p| param_schema(&p.name, &p.kind)).collect::<Vec<_>>(),
+        "outputs": func.output_params().iter().map(|p| param_schema(&p.name, &p.kind)).collect::<Vec<_>>(),
+    })).collect();
+    json!({
+        "version": contract.version().to_string(),
+        "functions": functions,
+    })
+}
+
+fn param_schema(name: &str, kind: &ParamType) -> Value {
+    let mut schema = json!({ "name": name, "type": type_name(kind) });
+    match kind {
+        ParamType::Tuple(params) => {
+            schema["components"] = params.iter()
+                .map(|p| param_schema(&p.name, &p.kind))
+                .collect::<Vec<_>>()
+                .into();
+        },
+        ParamType::Array(item) => {
+            schema["items"] = param_schema("", item);
+        },
+        ParamType::FixedArray(item, size) => {
+            schema["items"] = param_schema("", item);
+            schema["size"] = json!(size);
+        },
+        ParamType::Map(key, value) => {
+            schema["key"] = param_schema("", key);
+            schema["value"] = param_schema("", value);
+        },
+        ParamType::Optional(inner) | ParamType::Ref(inner) => {
+            schema["inner"] = param_schema("", inner);
+        },
+        _ => {},
+    }
+    schema
+}
+
+/// ABI type name in the same notation as in the ABI file, e.g. `tuple[]` instead of the
+/// expanded tuple signature.
+fn type_name(kind: &ParamType) -> String {
+    match kind {
+        ParamType::Tuple(_) => "tuple".to_owned(),
+        ParamType::Array(item) => format!("{}[]", type_name(item)),
+        ParamType::FixedArray(item, size) => format!("{}[{}]", type_name(item), size),
+        ParamType::Map(key, value) => format!("map({},{})", type_name(key), type_name(value)),
+        ParamType::Optional(inner) => format!("optional({})", type_name(inner)),
+        ParamType::Ref(inner) => format!("ref({})", type_name(inner)),
+        _ => kind.type_signature(),
+    }
+}
+
 async fn decode_account_from_boc(m: &ArgMatches<'_>, config: &Config) -> Result<(), String> {
     let boc = m.value_of("BOCFILE");
     let tvc_path = m.value_of("DUMPTVC");
@@ -268,7 +348,7 @@ async fn decode_message_command(m: &ArgMatches<'_>, config: &Config) -> Result<(
             }
         }
     };
-    println!("{decoded_message}");
+    println!("{}", format_json_result(&decoded_message, config));
     Ok(())
 }
 
@@ -294,7 +374,7 @@ async fn decode_tvc_fields(m: &ArgMatches<'_>, config: &Config) -> Result<(), St
     if !config.is_json {
         println!("TVC fields:");
     }
-    println!("{:#}", res.data);
+    println!("{}", format_json_result(&res.data, config));
     Ok(())
 }
 
@@ -321,7 +401,7 @@ async fn decode_account_fields(m: &ArgMatches<'_>, config: &Config) -> Result<()
     if !config.is_json {
         println!("Account fields:");
     }
-    println!("{:#}", res.data);
+    println!("{}", format_json_result(&res.data, config));
     Ok(())
 }
 
@@ -332,7 +412,25 @@ struct SortedFunctionHeader {
     expire: Option<u32>
 }
 
-async fn decode_body(body_base64: &str, abi_path: &str, is_json: bool, config: &Config) -> Result<(), String> {
+/// Loads message body specified as base64 or hex string or as a path to the file
+/// with the body boc (binary or base64) and returns it in base64.
+pub fn load_body_input(input: &str) -> Result<String, String> {
+    if std::path::Path::new(input).exists() {
+        let bytes = std::fs::read(input)
+            .map_err(|e| format!("failed to read body from file {}: {}", input, e))?;
+        return match std::str::from_utf8(&bytes).map(|s| s.trim()) {
+            Ok(text) if base64::decode(text).is_ok() => Ok(text.to_owned()),
+            _ => Ok(base64::encode(&bytes)),
+        };
+    }
+    match hex::decode(input) {
+        Ok(bytes) => Ok(base64::encode(&bytes)),
+        Err(_) => Ok(input.to_owned()),
+    }
+}
+
+async fn decode_body(body: &str, abi_path: &str, is_json: bool, config: &Config) -> Result<(), String> {
+    let body_base64 = &load_body_input(body)?;
     let body_vec  = base64::decode(body_base64)
         .map_err(|e| format!("body is not a valid base64 string: {}", e))?;
 
@@ -378,29 +476,36 @@ async fn decode_body(body_base64: &str, abi_path: &str, is_json: bool, config: &
             expire: hdr.expire
         }
     });
+    let (body_key, body_type) = match res.body_type {
+        MessageBodyType::Input => ("BodyCall", "Input"),
+        MessageBodyType::Output => ("BodyOutput", "Output"),
+        MessageBodyType::InternalOutput => ("BodyOutput", "InternalOutput"),
+        MessageBodyType::Event => ("BodyEvent", "Event"),
+    };
+    let id_key = if body_type == "Event" { "EventId" } else { "FunctionId" };
     if is_json {
         let mut result = json!({});
-        result["BodyCall"] = json!({res.name: output});
+        result[body_key] = json!({res.name: output});
+        result["BodyType"] = json!(body_type);
         result["Signature"] = json!(signature.unwrap_or("None".to_string()));
         result["Header"] = json!(header);
-        result["FunctionId"] = json!(format!("{:08X}", func_id));
-        println!("{:#}", result);
+        result[id_key] = json!(format!("{:08X}", func_id));
+        println!("{}", format_json_result(&result, config));
     } else {
-        println!("\n\n{}: {:#}", res.name, output);
+        println!("\n\n{}: {}", res.name, format_json_result(&output, config));
+        println!("BodyType: {}", body_type);
         println!("Signature: {}", signature.unwrap_or("None".to_string()));
-        println!("Header: {:#}", json!(header));
-        println!("FunctionId: {:08X}", func_id);
+        println!("Header: {}", format_json_result(&json!(header), config));
+        println!("{}: {:08X}", id_key, func_id);
     }
     Ok(())
 }
 
-async fn decode_message(msg_boc: Vec<u8>, abi_path: Option<String>) -> Result<String, String> {
+async fn decode_message(msg_boc: Vec<u8>, abi_path: Option<String>) -> Result<Value, String> {
     let tvm_msg = ton_sdk::Contract::deserialize_message(&msg_boc[..])
         .map_err(|e| format!("failed to deserialize message boc: {}", e))?;
     let config = Config::default();
-    let result = msg_printer::serialize_msg(&tvm_msg, abi_path, &config).await?;
-    Ok(serde_json::to_string_pretty(&result)
-        .map_err(|e| format!("Failed to serialize the result: {}", e))?)
+    msg_printer::serialize_msg(&tvm_msg, abi_path, &config).await
 }
 
 fn load_state_init(m: &ArgMatches<'_>) -> Result<StateInit, String> {
@@ -447,8 +552,7 @@ async fn decode_tvc_command(m: &ArgMatches<'_>, config: &Config) -> Result<(), S
         println!("Decoded data:");
     }
     let result = msg_printer::serialize_state_init(&state, ton.clone()).await?;
-    println!("{}", serde_json::to_string_pretty(&result)
-        .map_err(|e| format!("Failed to serialize json: {}", e))?);
+    println!("{}", format_json_result(&result, config));
 
     Ok(())
 }
@@ -633,7 +737,7 @@ mod tests {
     async fn test_decode_msg_json() {
         let msg_boc = std::fs::read("tests/samples/wallet.boc").unwrap();
         let out = decode_message(msg_boc, Some("tests/samples/wallet.abi.json".to_owned())).await.unwrap();
-        let _ : serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert!(out.is_object());
     }
 
     #[tokio::test]
@@ -642,4 +746,59 @@ mod tests {
         let config = Config::default();
         let _out = decode_body(body, "tests/samples/wallet.abi.json", true, &config).await.unwrap();
     }
+
+    #[test]
+    fn test_load_body_input() {
+        let body = "te6ccgEBAQEARAAAgwAAALqUCTqWL8OX7JivfJrAAzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMQAAAAAAAAAAAAAAAEeGjADA==";
+        assert_eq!(load_body_input(body).unwrap(), body);
+
+        let body_hex = hex::encode(base64::decode(body).unwrap());
+        assert_eq!(load_body_input(&body_hex).unwrap(), body);
+
+        let path = "test_load_body_input.boc";
+        std::fs::write(path, base64::decode(body).unwrap()).unwrap();
+        assert_eq!(load_body_input(path).unwrap(), body);
+        std::fs::write(path, body).unwrap();
+        assert_eq!(load_body_input(path).unwrap(), body);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_abi_schema() {
+        let abi = std::fs::read_to_string("tests/samples/SafeMultisigWallet.abi.json").unwrap();
+        let contract = ton_abi::Contract::load(abi.as_bytes()).unwrap();
+        let schema = abi_schema(&contract);
+        let functions = schema["functions"].as_array().unwrap();
+        let find = |name: &str| functions.iter().find(|f| f["name"] == name).unwrap().clone();
+
+        let submit = find("submitTransaction");
+        let inputs: Vec<_> = submit["inputs"].as_array().unwrap().iter()
+            .map(|p| (p["name"].as_str().unwrap(), p["type"].as_str().unwrap()))
+            .collect();
+        assert_eq!(inputs, vec![
+            ("dest", "address"), ("value", "uint128"), ("bounce", "bool"),
+            ("allBalance", "bool"), ("payload", "cell"),
+        ]);
+        assert_eq!(submit["outputs"][0], json!({ "name": "transId", "type": "uint64" }));
+        let header: Vec<_> = submit["header"].as_array().unwrap().iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(header, vec!["pubkey", "time", "expire"]);
+
+        let constructor = find("constructor");
+        assert_eq!(constructor["inputs"][0]["type"], "uint256[]");
+        assert_eq!(constructor["inputs"][0]["items"]["type"], "uint256");
+
+        let custodians = &find("getCustodians")["outputs"][0];
+        assert_eq!(custodians["type"], "tuple[]");
+        assert_eq!(custodians["items"]["components"][1], json!({ "name": "pubkey", "type": "uint256" }));
+    }
+
+    #[tokio::test]
+    async fn test_decode_body_hex() {
+        let body = "te6ccgEBAQEARAAAgwAAALqUCTqWL8OX7JivfJrAAzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMQAAAAAAAAAAAAAAAEeGjADA==";
+        let body_hex = hex::encode(base64::decode(body).unwrap());
+        let config = Config::default();
+        decode_body(&body_hex, "tests/samples/wallet.abi.json", true, &config).await.unwrap();
+    }
 }
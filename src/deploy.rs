@@ -10,11 +10,16 @@
  * See the License for the specific TON DEV software governing permissions and
  * limitations under the License.
  */
-use crate::helpers::{create_client_verbose, create_client_with_signature_id, load_abi, now_ms};
+use crate::helpers::{check_abi_function, create_client_verbose, create_client_with_signature_id, load_abi,
+    load_ton_abi, now_ms, query_with_limit, TonClient};
 use crate::config::FullConfig;
 use crate::crypto::load_keypair;
 use crate::call::{
+    call_contract_with_client,
+    dry_run_message,
     emulate_locally,
+    format_json_result,
+    parse_integer_param,
     process_message,
     send_message_and_wait,
 };
@@ -24,6 +29,14 @@ use ton_client::abi::{
 use ton_client::crypto::KeyPair;
 use crate::{Config, SignatureIDType};
 use crate::message::{display_generated_message, EncodedMessage};
+use serde_json::json;
+use num_bigint::BigUint;
+use num_traits::Num;
+
+/// Returns the name of the function used as constructor on deploy.
+pub fn constructor_name(config: &Config) -> &str {
+    config.constructor.as_deref().unwrap_or("constructor")
+}
 
 pub async fn deploy_contract(
     full_config: &mut FullConfig,
@@ -47,23 +60,34 @@ pub async fn deploy_contract(
     let enc_msg = encode_message(ton.clone(), msg.clone()).await
         .map_err(|e| format!("failed to create inbound message: {}", e))?;
 
-    if config.local_run || is_fee {
-        emulate_locally(ton.clone(), addr.as_str(), enc_msg.message.clone(), is_fee).await?;
-        if is_fee {
-            return Ok(());
-        }
+    if config.dry_run && !is_fee {
+        let abi = load_abi(abi, config).await?;
+        return dry_run_message(ton, &addr, enc_msg.message, Some(abi), true, config).await;
+    }
+    if is_fee {
+        emulate_locally(ton.clone(), addr.as_str(), enc_msg.message, None, true, config).await?;
+        return Ok(());
+    }
+    // the giver is called only when the deploy message is actually sent
+    if let Some(value) = &config.prepay {
+        prepay_address(ton.clone(), config, &addr, value).await?;
+    }
+    if config.local_run {
+        emulate_locally(ton.clone(), addr.as_str(), enc_msg.message.clone(), None, false, config).await?;
     }
 
-    if config.async_call {
+    let result = if config.async_call {
         let abi = load_abi(&abi, config).await?;
         send_message_and_wait(ton,
                               Some(abi),
                               enc_msg.message,
-                              config).await?;
+                              config).await?
+            .output
     } else {
         process_message(ton.clone(), msg, config).await
             .map_err(|e| format!("{:#}", e))?;
-    }
+        json!({})
+    };
 
     if !config.is_json {
         if !config.async_call {
@@ -71,7 +95,7 @@ pub async fn deploy_contract(
         }
         println!("Contract deployed at address: {}", addr);
     } else {
-        println!("{{}}");
+        println!("{}", format_json_result(&result, config));
     }
     if let Some(alias) = alias {
         full_config.add_alias(alias, Some(addr), Some(abi.to_string()), keys_file)?;
@@ -79,6 +103,68 @@ pub async fn deploy_contract(
     Ok(())
 }
 
+/// Sends `value` nanotokens from the giver specified in the config to the address
+/// and waits until the account balance is updated.
+pub async fn prepay_address(ton: TonClient, config: &Config, addr: &str, value: &str) -> Result<(), String> {
+    let value = parse_integer_param(value)
+        .and_then(|value| BigUint::from_str_radix(&value, 10)
+            .map_err(|e| format!("failed to parse prepay value: {}", e)))?;
+    let giver = config.giver_address.as_deref()
+        .ok_or("giver address is not specified in the config".to_string())?;
+    let giver_abi = config.giver_abi.as_deref()
+        .ok_or("giver ABI is not specified in the config".to_string())?;
+    let target = account_balance(ton.clone(), addr).await? + &value;
+    if !config.is_json {
+        println!("Prepaying {} nanotokens from the giver {}...", value, giver);
+    }
+    let params = json!({ "dest": addr, "value": value.to_string(), "bounce": false }).to_string();
+    // options of the deploy are not applied to the giver call
+    let mut giver_config = config.clone();
+    giver_config.confirm = Some(false);
+    giver_config.dry_run = false;
+    giver_config.async_call = false;
+    giver_config.local_run = false;
+    giver_config.once = None;
+    call_contract_with_client(
+        ton.clone(),
+        &giver_config,
+        giver,
+        giver_abi,
+        "sendTransaction",
+        &params,
+        config.giver_keys.clone(),
+        false,
+        None,
+    ).await?;
+
+    let attempts = std::cmp::max(config.timeout / 1000, 1);
+    for _ in 0..attempts {
+        if account_balance(ton.clone(), addr).await? >= target {
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    Err(format!("balance of {} was not updated after prepay", addr))
+}
+
+/// Returns the balance of the account or 0 if the account does not exist yet.
+async fn account_balance(ton: TonClient, addr: &str) -> Result<BigUint, String> {
+    let accounts = query_with_limit(ton, "accounts", json!({ "id": { "eq": addr } }), "balance", None, Some(1)).await
+        .map_err(|e| format!("failed to query the balance of {}: {}", addr, e))?;
+    match accounts.first().and_then(|account| account["balance"].as_str()) {
+        Some(balance) => parse_balance(balance)
+            .ok_or(format!("failed to parse the balance of {}: {}", addr, balance)),
+        None => Ok(BigUint::default()),
+    }
+}
+
+fn parse_balance(balance: &str) -> Option<BigUint> {
+    match balance.strip_prefix("0x") {
+        Some(hex) => BigUint::from_str_radix(hex, 16).ok(),
+        None => BigUint::from_str_radix(balance, 10).ok(),
+    }
+}
+
 pub async fn generate_deploy_message(
     tvc: &str,
     abi: &str,
@@ -103,7 +189,7 @@ pub async fn generate_deploy_message(
         expire: None,
         address: addr.to_owned(),
     };
-    display_generated_message(&msg, "constructor", is_raw, output, config.is_json)?;
+    display_generated_message(&msg, constructor_name(config), is_raw, output, config.is_json)?;
     if !config.is_json {
         println!("Contract's address: {}", addr);
         println!("Succeeded.");
@@ -120,6 +206,8 @@ pub async fn prepare_deploy_message(
     config: &Config,
     signature_id: Option<i32>,
 ) -> Result<(ParamsOfEncodeMessage, String), String> {
+    let function_name = constructor_name(config).to_string();
+    check_abi_function(&load_ton_abi(abi, config).await?, &function_name)?;
     let abi = load_abi(abi, config).await?;
 
     let keys = keys_file.map(|k| load_keypair(&k)).transpose()?;
@@ -130,7 +218,7 @@ pub async fn prepare_deploy_message(
     prepare_deploy_message_params(
         &tvc_bytes,
         abi,
-        "constructor".to_string(),
+        function_name,
         now_ms(),
         params,
         keys,
@@ -198,3 +286,38 @@ pub async fn prepare_deploy_message_params(
         ..Default::default()
     }, address))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::create_client_local;
+    use ton_client::abi::{decode_message, ParamsOfDecodeMessage};
+
+    #[tokio::test]
+    async fn test_custom_constructor() {
+        let abi = std::fs::read_to_string("tests/samples/wallet.abi.json").unwrap();
+        let abi = abi.replacen("\"constructor\"", "\"init\"", 1);
+        let mut config = Config::default();
+
+        let err = prepare_deploy_message("tests/samples/wallet.tvc", &abi, "{}", None, 0, &config, None)
+            .await.unwrap_err();
+        assert!(err.contains("\"constructor\" is not found in the ABI"));
+
+        config.constructor = Some("init".to_owned());
+        let (params, _) = prepare_deploy_message("tests/samples/wallet.tvc", &abi, "{}", None, 0, &config, None)
+            .await.unwrap();
+        assert_eq!(params.call_set.as_ref().unwrap().function_name, "init");
+        let client = create_client_local().unwrap();
+        let msg = encode_message(client.clone(), params).await.unwrap();
+        let decoded = decode_message(client, ParamsOfDecodeMessage {
+            abi: load_abi(&abi, &config).await.unwrap(),
+            message: msg.message,
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(decoded.name, "init");
+
+        config.constructor = Some("unknown".to_owned());
+        assert!(prepare_deploy_message("tests/samples/wallet.tvc", &abi, "{}", None, 0, &config, None)
+            .await.is_err());
+    }
+}
@@ -11,6 +11,7 @@
  * limitations under the License.
  */
 use crate::config::Config;
+use crate::call::format_json_result;
 use crate::helpers::{create_client_local, read_keys, load_abi, calc_acc_address, load_abi_str};
 use serde_json::json;
 use std::fs::OpenOptions;
@@ -117,7 +118,7 @@ pub async fn generate_address(
             "non-bounceable": calc_userfriendly_address(&addr, false, false)?,
             "bounceable": calc_userfriendly_address(&addr, true, false)?
         });
-        println!("{:#}", res);
+        println!("{}", format_json_result(&res, config));
     }
     Ok(())
 }
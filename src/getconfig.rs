@@ -13,6 +13,7 @@
 
 use num_bigint::BigUint;
 use crate::config::Config;
+use crate::call::format_json_result;
 use crate::helpers::{create_client_verbose, query_with_limit, now, now_ms, TonClient};
 use serde_json::{json, Value};
 use ton_abi::{Contract, Token, TokenValue, Uint};
@@ -308,7 +309,7 @@ pub async fn query_global_config(config: &Config, index: Option<&str>) -> Result
             if !config.is_json {
                 print!("Config: ");
             }
-            println!("{:#}", Value::from(config_value));
+            println!("{}", format_json_result(&Value::from(config_value), config));
         },
         Some(index) => {
             index.parse::<i32>()
@@ -325,7 +326,7 @@ pub async fn query_global_config(config: &Config, index: Option<&str>) -> Result
             if config_value.is_null() {
                 println!("{{}}");
             } else {
-                println!("{:#}", config_value);
+                println!("{}", format_json_result(config_value, config));
             }
         }
     }
@@ -36,25 +36,27 @@ mod debug;
 mod run;
 mod test;
 mod message;
+mod output;
 #[cfg(feature = "sold")]
 mod compile;
 
 use account::{get_account, calc_storage, wait_for_change};
-use call::{call_contract, call_contract_with_msg};
+use call::{call_batch, call_contract, call_contract_with_msg, call_raw_body, abi_from_contract,
+           call_repeated, format_json_result, parse_integer_param};
 use clap::{ArgMatches, SubCommand, Arg, AppSettings, App};
 use config::{Config, set_config, clear_config};
-use crypto::{generate_mnemonic, extract_pubkey, generate_keypair};
+use crypto::{generate_mnemonic, extract_pubkey, generate_keypair, hide_inline_keypair};
 use debot::{create_debot_command, debot_command};
 use decode::{create_decode_command, decode_command};
 use debug::{create_debug_command, debug_command};
-use deploy::{deploy_contract, generate_deploy_message};
+use deploy::{constructor_name, deploy_contract, generate_deploy_message};
 use depool::{create_depool_command, depool_command};
 use genaddr::generate_address;
 use getconfig::{query_global_config, dump_blockchain_config};
 use helpers::{load_ton_address, load_abi, create_client_local, query_raw,
-              contract_data_from_matches_or_config_alias};
+              contract_data_from_matches_or_config_alias, init_file_logger, keypair_from_phrase, keypair_from_phrase_file};
 use multisig::{create_multisig_command, multisig_command};
-use replay::{fetch_block_command, fetch_command, replay_command};
+use replay::{fetch_block_command, fetch_command, replay_command, replay_message_command};
 use serde_json::{json, Value};
 use std::collections::BTreeMap;
 use std::env;
@@ -68,7 +70,7 @@ use crate::compile::{compile_command, create_compile_command};
 
 use crate::config::{FullConfig, resolve_net_name};
 use crate::getconfig::gen_update_config_message;
-use crate::helpers::{abi_from_matches_or_config, AccountSource, default_config_name,
+use crate::helpers::{abi_from_matches_or_config, AccountSource, default_config_name, once_ledger_path,
     global_config_path, load_abi_from_tvc, load_params, parse_lifetime,
     unpack_alternative_params, wc_from_matches_or_config
 };
@@ -76,6 +78,7 @@ use crate::message::generate_message;
 use crate::run::{run_command, run_get_method};
 
 const DEF_MSG_LIFETIME: u32 = 30;
+const MAX_CALL_LIFETIME: u32 = 3600;
 const DEF_STORAGE_PERIOD: u32 = 60 * 60 * 24 * 365;
 
 enum CallType {
@@ -103,8 +106,13 @@ fn main() {
         .expect("Can't create Engine tokio runtime");
     let result = runtime.block_on(async move { main_internal().await });
     if let Err(err_str) = result {
-        if !err_str.is_empty() { println!("{}", err_str); }
-        exit(1)
+        log::error!("{}", err_str);
+        if err_str.starts_with("Error: ") {
+            println!("{}", output::error(&err_str));
+        } else if !err_str.is_empty() {
+            println!("{}", err_str);
+        }
+        exit(error_exit_code(&err_str))
     }
 }
 
@@ -119,12 +127,12 @@ async fn main_internal() -> Result <(), String> {
     let keys_arg = Arg::with_name("KEYS")
         .long("--keys")
         .takes_value(true)
-        .help("Seed phrase or path to the file with keypair used to sign the message. Can be specified in the config file.");
+        .help("Seed phrase, path to the file with keypair or inline \"secret:public\" hex pair used to sign the message. Can be specified in the config file.");
 
     let sign_arg = Arg::with_name("SIGN")
         .long("--sign")
         .takes_value(true)
-        .help("Seed phrase or path to the file with keypair used to sign the message. Can be specified in the config.");
+        .help("Seed phrase, path to the file with keypair or inline \"secret:public\" hex pair used to sign the message. Can be specified in the config.");
 
     let method_opt_arg = Arg::with_name("METHOD")
         .takes_value(true)
@@ -143,6 +151,11 @@ async fn main_internal() -> Result <(), String> {
 
     let author = "EverX";
 
+    let no_prompt_arg = Arg::with_name("YES")
+        .short("-y")
+        .long("--yes")
+        .help("Do not prompt for the missing function arguments even with --interactive, fail instead.");
+
     let callx_cmd = SubCommand::with_name("callx")
         .about("Sends an external message with encoded function call to the contract (alternative syntax).")
         .version(version_string)
@@ -154,6 +167,7 @@ async fn main_internal() -> Result <(), String> {
         .arg(abi_arg.clone())
         .arg(keys_arg.clone())
         .arg(method_opt_arg.clone())
+        .arg(no_prompt_arg.clone())
         .arg(multi_params_arg.clone());
 
     let tvc_arg = Arg::with_name("TVC")
@@ -172,6 +186,12 @@ async fn main_internal() -> Result <(), String> {
         .takes_value(true)
         .help("Saves contract address and abi to the aliases list to be able to call this contract with alias instaed of address.");
 
+    let constructor_arg = Arg::with_name("CONSTRUCTOR")
+        .long("--method")
+        .takes_value(true)
+        .value_name("NAME")
+        .help("Name of the function used as constructor. Default is \"constructor\".");
+
     let deployx_cmd = SubCommand::with_name("deployx")
         .about("Deploys a smart contract to the blockchain (alternative syntax).")
         .version(version_string)
@@ -184,6 +204,8 @@ async fn main_internal() -> Result <(), String> {
         .arg(wc_arg.clone())
         .arg(tvc_arg.clone())
         .arg(alias_arg_long.clone())
+        .arg(constructor_arg.clone())
+        .arg(no_prompt_arg.clone())
         .arg(multi_params_arg.clone());
 
     let address_boc_tvc_arg = Arg::with_name("ADDRESS")
@@ -222,6 +244,7 @@ async fn main_internal() -> Result <(), String> {
             .long("--addr"))
         .arg(abi_arg.clone())
         .arg(method_opt_arg.clone())
+        .arg(no_prompt_arg.clone())
         .arg(multi_params_arg.clone())
         .arg(boc_flag.clone())
         .arg(tvc_flag.clone())
@@ -307,6 +330,13 @@ async fn main_internal() -> Result <(), String> {
             .long("--save")
             .help("If this flag is specified, modifies the tvc file with the keypair and initial data"));
 
+    let keychain_arg = Arg::with_name("KEYS_FROM_KEYCHAIN")
+        .long("--keys-from-keychain")
+        .takes_value(true)
+        .value_name("NAME")
+        .conflicts_with_all(&["KEYS", "SIGN"])
+        .help("Name of the OS keychain entry (service \"tonos-cli\") with the keys used to sign the message: keypair json, inline \"secret:public\" pair or seed phrase. Requires the \"keychain\" build feature.");
+
     let deploy_cmd = SubCommand::with_name("deploy")
         .setting(AppSettings::AllowNegativeNumbers)
         .setting(AppSettings::AllowLeadingHyphen)
@@ -321,7 +351,15 @@ async fn main_internal() -> Result <(), String> {
         .arg(abi_arg.clone())
         .arg(sign_arg.clone())
         .arg(keys_arg.clone())
-        .arg(wc_arg.clone());
+        .arg(wc_arg.clone())
+        .arg(constructor_arg.clone())
+        .arg(keychain_arg.clone());
+
+    let prepay_arg = Arg::with_name("PREPAY")
+        .long("--prepay")
+        .takes_value(true)
+        .value_name("VALUE")
+        .help("Value sent from the giver specified in the config to the contract address before deploy, in nanotokens (or with a unit suffix, e.g. 10T).");
 
     let output_arg = Arg::with_name("OUTPUT")
         .short("-o")
@@ -359,12 +397,181 @@ async fn main_internal() -> Result <(), String> {
         .about("Sends an external message with encoded function call to the contract.")
         .version(version_string)
         .author(author)
-        .arg(address_arg.clone())
-        .arg(method_arg.clone())
-        .arg(params_arg.clone())
+        .arg(address_arg.clone().required_unless("BATCH"))
+        .arg(method_arg.clone().required_unless_one(&["BATCH", "RAW_BODY"]))
+        .arg(params_arg.clone().required_unless_one(&["BATCH", "RAW_BODY"]))
         .arg(abi_arg.clone())
+        .arg(Arg::with_name("ABI_FROM_CONTRACT")
+            .long("--abi-from-contract")
+            .takes_value(true)
+            .value_name("GETTER")
+            .conflicts_with_all(&["ABI", "BATCH", "RAW_BODY"])
+            .help("Fetch the contract ABI with its getter instead of the ABI file. The getter must return the ABI json (or hex encoded json) as its first string or bytes output. Can't be used with --batch and --raw-body."))
         .arg(keys_arg.clone())
-        .arg(sign_arg.clone());
+        .arg(sign_arg.clone()
+            .multiple(true)
+            .number_of_values(1)
+            .help("Seed phrase, path to the file with keypair or inline \"secret:public\" hex pair used to sign the message. Can be specified in the config. Can be repeated to sign the call by several keys: their signatures are passed in the \"signatures\" (bytes[]) parameter of the function, and the message is signed by the first one."))
+        .arg(Arg::with_name("PHRASE")
+            .long("--phrase")
+            .takes_value(true)
+            .value_name("SEED_PHRASE")
+            .conflicts_with_all(&["KEYS", "SIGN", "PHRASE_FILE"])
+            .help("Seed phrase to derive the keys used to sign the message. The phrase itself is never printed."))
+        .arg(Arg::with_name("PHRASE_FILE")
+            .long("--phrase-file")
+            .takes_value(true)
+            .value_name("PATH")
+            .conflicts_with_all(&["KEYS", "SIGN"])
+            .help("Path to the file with the seed phrase to derive the keys used to sign the message."))
+        .arg(keychain_arg.clone()
+            .conflicts_with_all(&["KEYS", "SIGN", "PHRASE", "PHRASE_FILE"]))
+        .arg(Arg::with_name("RAW_BODY")
+            .long("--raw-body")
+            .takes_value(true)
+            .value_name("BODY")
+            .conflicts_with_all(&["METHOD", "PARAMS", "ABI", "BATCH"])
+            .help("Send an external message with this body cell (base64 or hex boc or path to the boc file) without ABI encoding. If keys are specified, the body is prefixed with the signature of its hash. The output is not decoded, only the transaction id is printed."))
+        .arg(Arg::with_name("LIFETIME")
+            .long("--lifetime")
+            .takes_value(true)
+            .help("Period of time in seconds while message is valid. Overrides \"lifetime\" from the config for this call."))
+        .arg(Arg::with_name("BATCH")
+            .long("--batch")
+            .takes_value(true)
+            .conflicts_with_all(&["ADDRESS", "METHOD", "PARAMS"])
+            .help("Path to the json file with an array of calls: [{\"address\", \"method\", \"params\", \"keys\", \"abi\"}]. \"keys\" and \"abi\" are optional and default to the command options. Calls are executed sequentially."))
+        .arg(Arg::with_name("STOP_ON_ERROR")
+            .long("--stop-on-error")
+            .requires("BATCH")
+            .help("Stop the batch on the first failed call. By default all calls are executed."))
+        .arg(Arg::with_name("TIME")
+            .long("--time")
+            .takes_value(true)
+            .help("Message creation time in milliseconds. Makes the message deterministic for the same inputs (expiration time is counted from this value)."))
+        .arg(Arg::with_name("OUTPUT_MESSAGE")
+            .long("--output-message")
+            .takes_value(true)
+            .help("Path to the file where the signed message (base64 BOC) is saved. The message is neither emulated nor sent."))
+        .arg(Arg::with_name("MESSAGE_TTL")
+            .long("--ttl")
+            .takes_value(true)
+            .value_name("SECS")
+            .requires("OUTPUT_MESSAGE")
+            .help("Validity period in seconds of the message saved with --output-message for later submission. Overrides the lifetime of the message."))
+        .arg(Arg::with_name("RAW")
+            .long("--raw")
+            .help("Print the undecoded transaction instead of the decoded output."))
+        .arg(Arg::with_name("VERIFY_KEY")
+            .long("--verify-key")
+            .takes_value(true)
+            .value_name("GETTER")
+            .help("Name of the contract getter which returns the owner public key(s). The call is aborted if the signing key is not among them."))
+        .arg(Arg::with_name("WAIT_TIMEOUT")
+            .long("--wait-timeout")
+            .takes_value(true)
+            .value_name("SECS")
+            .help("Maximum time in seconds to wait for the transaction. If it elapses the call fails, the error contains the message id to check it later. By default the SDK waiting timeout is used."))
+        .arg(Arg::with_name("FAIL_ON_EMPTY_OUTPUT")
+            .long("--fail-on-empty-output")
+            .help("Exit with an error if the decoded output of the call is empty, e.g. to assert in scripts that the function returned data."))
+        .arg(Arg::with_name("OUT_ABI")
+            .long("--out-abi")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .value_name("ABI")
+            .help("ABI used to decode internal out messages of the transaction. Can be specified several times, each message is decoded with the first matching ABI. Messages not matched by any ABI are printed as BOC."))
+        .arg(Arg::with_name("TONCONNECT")
+            .long("--tonconnect")
+            .requires("AMOUNT")
+            .conflicts_with_all(&["OUTPUT_MESSAGE", "BATCH", "SRC"])
+            .help("Print the call as a TON Connect SendTransaction request (address, amount and base64 payload with the internal call of the function) for a wallet app instead of sending it."))
+        .arg(Arg::with_name("AMOUNT")
+            .long("--amount")
+            .takes_value(true)
+            .requires("TONCONNECT")
+            .help("Value attached to the message of the TON Connect request, in nanotokens or with a unit suffix (e.g. 1.5ever)."))
+        .arg(Arg::with_name("MAX_FEE")
+            .long("--max-fee")
+            .takes_value(true)
+            .value_name("AMOUNT")
+            .help("Maximum fee of the call in nanotokens (or with a unit suffix, e.g. 0.5T). The call is emulated before sending and aborted if the estimated fee exceeds the amount."))
+        .arg(Arg::with_name("COUNT")
+            .long("--count")
+            .takes_value(true)
+            .value_name("N")
+            .conflicts_with_all(&["ONCE", "BATCH", "OUTPUT_MESSAGE", "SRC", "TONCONNECT"])
+            .help("Send the call N times and print latency statistics (min, max and average time of the call). \"{i}\" in the parameters is replaced with the number of the call, starting from 0."))
+        .arg(Arg::with_name("CONCURRENCY")
+            .long("--concurrency")
+            .takes_value(true)
+            .requires("COUNT")
+            .help("Maximum number of calls sent at once with --count (default 1)."))
+        .arg(Arg::with_name("ACCOUNT_SNAPSHOT")
+            .long("--account-snapshot")
+            .takes_value(true)
+            .value_name("DIR")
+            .help("Directory where the account state is saved before sending the message (before.boc) and after the transaction (after.boc) to inspect the state delta offline."))
+        .arg(Arg::with_name("WARN_DRIFT")
+            .long("--warn-drift")
+            .help("With local_run enabled in the config, compares the output of the local run with the onchain result and prints the difference if they differ."))
+        .arg(Arg::with_name("AWAIT")
+            .long("--await")
+            .help("Wait for the transaction and decode the output even if async_call is enabled in the config."))
+        .arg(Arg::with_name("SINCE_LT")
+            .long("--since-lt")
+            .takes_value(true)
+            .value_name("LT")
+            .help("Logical time after which the transaction of the call is expected, or \"current\" for the account's last transaction lt captured before sending. Transactions with lower logical time are not matched."))
+        .arg(Arg::with_name("NONCE")
+            .long("--nonce")
+            .takes_value(true)
+            .value_name("N")
+            .conflicts_with("BATCH")
+            .help("Nonce put into the \"nonce\" parameter of the called function."))
+        .arg(Arg::with_name("NONCE_GETTER")
+            .long("--nonce-getter")
+            .takes_value(true)
+            .value_name("GETTER")
+            .requires("NONCE")
+            .help("Name of the contract getter which returns the current nonce. The call is aborted if the nonce passed with --nonce is less than the current one."))
+        .arg(Arg::with_name("ONCE")
+            .long("--once")
+            .takes_value(true)
+            .value_name("KEY")
+            .conflicts_with("BATCH")
+            .help("Idempotency key of the call. If a call with the same key was already sent, it is not sent again and the previous transaction id is printed. The message id is recorded before sending, so the message whose transaction is not found yet is not sent again until it expires."))
+        .arg(Arg::with_name("SRC")
+            .long("--src")
+            .takes_value(true)
+            .conflicts_with_all(&["OUTPUT_MESSAGE", "BATCH"])
+            .help("Emulate the call as an internal message from this address instead of an external one. The message is only executed locally and is not sent."))
+        .arg(Arg::with_name("VALUE")
+            .long("--value")
+            .takes_value(true)
+            .requires("SRC")
+            .help("Value attached to the emulated internal message, in nanotokens or with a unit suffix (e.g. 1.5ever). Default is 1 ever."))
+        .arg(Arg::with_name("CONFIG_BOC")
+            .long("--config-boc")
+            .takes_value(true)
+            .help("Path to the file with the config contract account boc (e.g. saved with `dump config`) used to debug the failed call instead of the network config."))
+        .arg(Arg::with_name("LIBS")
+            .long("--libs")
+            .takes_value(true)
+            .help("Path to the file with library cells used by the contract in local emulation (local run, dry run, fee calculation) and in debug on fail: boc with a single library cell or json array of base64 encoded cells."))
+        .arg(Arg::with_name("ERRORS")
+            .long("--errors")
+            .takes_value(true)
+            .help("Path to the json file which maps contract exit codes to error messages, e.g. {\"101\": \"Not enough balance\"}. The mapped message is printed when the call fails."))
+        .arg(Arg::with_name("YES")
+            .short("-y")
+            .long("--yes")
+            .help("Send the message without confirmation.")
+            .conflicts_with("CONFIRM"))
+        .arg(Arg::with_name("CONFIRM")
+            .long("--confirm")
+            .help("Ask for confirmation before sending the message even if stdin is not a terminal."));
 
     let send_cmd = SubCommand::with_name("send")
         .about("Sends a prepared message to the contract.")
@@ -373,8 +580,14 @@ async fn main_internal() -> Result <(), String> {
         .arg(Arg::with_name("MESSAGE")
             .required(true)
             .takes_value(true)
-            .help("Message to send. Message data should be specified in quotes."))
-        .arg(abi_arg.clone());
+            .help("Message to send: output of the message command, message BOC in hex or base64 or path to the file with the message. Message data should be specified in quotes."))
+        .arg(abi_arg.clone()
+            .multiple(true)
+            .number_of_values(1)
+            .help("Path or link to the contract ABI file or pure json ABI data. Can be specified several times to try each ABI for decoding. Can be specified in the config file."))
+        .arg(Arg::with_name("OFFLINE")
+            .long("--offline")
+            .help("Only decode and print the message without sending it to the network."));
 
     let message_cmd = SubCommand::with_name("message")
         .setting(AppSettings::AllowLeadingHyphen)
@@ -391,6 +604,12 @@ async fn main_internal() -> Result <(), String> {
             .long("--lifetime")
             .takes_value(true)
             .help("Period of time in seconds while message is valid."))
+        .arg(Arg::with_name("MESSAGE_TTL")
+            .long("--ttl")
+            .takes_value(true)
+            .value_name("SECS")
+            .conflicts_with("LIFETIME")
+            .help("Validity period in seconds of the message which is submitted later. Can be much longer than the lifetime of interactive calls."))
         .arg(Arg::with_name("TIMESTAMP")
             .long("--time")
             .takes_value(true)
@@ -426,9 +645,47 @@ async fn main_internal() -> Result <(), String> {
         .arg(method_arg.clone())
         .arg(params_arg.clone())
         .arg(abi_arg.clone())
+        .arg(Arg::with_name("ABI_FROM_CONTRACT")
+            .long("--abi-from-contract")
+            .takes_value(true)
+            .value_name("GETTER")
+            .conflicts_with_all(&["ABI", "BOC", "TVC"])
+            .help("Fetch the contract ABI with its getter instead of the ABI file. The getter must return the ABI json (or hex encoded json) as its first string or bytes output."))
         .arg(boc_flag.clone())
         .arg(tvc_flag.clone())
-        .arg(bc_config_arg.clone());
+        .arg(bc_config_arg.clone())
+        .arg(Arg::with_name("EXECUTOR")
+            .long("--executor")
+            .conflicts_with("BCCONFIG")
+            .help("Execute the message as a full transaction (like `call --local-run`) instead of running the getter on TVM only. Nothing is sent onchain. The function must accept the external message."))
+        .arg(sign_arg.clone()
+            .requires("EXECUTOR")
+            .help("Seed phrase, path to the file with keypair or inline \"secret:public\" hex pair used to sign the message executed with --executor."))
+        .arg(Arg::with_name("UNTIL")
+            .long("--until")
+            .takes_value(true)
+            .value_name("PREDICATE")
+            .conflicts_with_all(&["BOC", "TVC"])
+            .help("Repeat the get-method until its decoded output satisfies the predicate in form \"<path>==<value>\" or \"<path>!=<value>\", e.g. \".value0==true\" or \".info.owners[0]==0x12\". The final result is printed."))
+        .arg(Arg::with_name("POLL_INTERVAL")
+            .long("--poll-interval")
+            .takes_value(true)
+            .value_name("SECS")
+            .requires("UNTIL")
+            .help("Interval in seconds between the get-method runs with --until. Default is 1."))
+        .arg(Arg::with_name("POLL_TIMEOUT")
+            .long("--poll-timeout")
+            .takes_value(true)
+            .value_name("SECS")
+            .requires("UNTIL")
+            .help("Maximum time in seconds to wait for the --until predicate. Default is 60."))
+        .arg(Arg::with_name("OUT_ABI")
+            .long("--out-abi")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .value_name("ABI")
+            .help("ABI used to decode internal out messages of the transaction. Can be specified several times, each message is decoded with the first matching ABI. Messages not matched by any ABI are printed as BOC."));
 
     let config_clear_cmd = SubCommand::with_name("clear")
         .setting(AppSettings::AllowLeadingHyphen)
@@ -499,7 +756,22 @@ async fn main_internal() -> Result <(), String> {
             .help("Project Id in Evercloud (dashboard.evercloud.dev)."))
         .arg(Arg::with_name("ACCESS_KEY")
             .long("--access_key")
-            .help("Project secret or JWT in Evercloud (dashboard.evercloud.dev)."));
+            .help("Project secret or JWT in Evercloud (dashboard.evercloud.dev)."))
+        .arg(Arg::with_name("SIGNING_COMMAND")
+            .long("--signing_command")
+            .help("External program used by DeBot signing boxes instead of local keys."))
+        .arg(Arg::with_name("GIVER_ADDRESS")
+            .long("--giver_address")
+            .help("Address of the giver used to prepay accounts before deploy."))
+        .arg(Arg::with_name("GIVER_ABI")
+            .long("--giver_abi")
+            .help("Path or link to the giver ABI."))
+        .arg(Arg::with_name("GIVER_KEYS")
+            .long("--giver_keys")
+            .help("Keys used to sign giver calls."))
+        .arg(Arg::with_name("ADDRESS_RESOLVER")
+            .long("--address_resolver")
+            .help("Address of the name resolver contract."));
 
     let alias_arg = Arg::with_name("ALIAS")
         .required(true)
@@ -650,6 +922,26 @@ async fn main_internal() -> Result <(), String> {
             .long("--access_key")
             .takes_value(true)
             .help("Project secret or JWT in Evercloud (dashboard.evercloud.dev)."))
+        .arg(Arg::with_name("SIGNING_COMMAND")
+            .long("--signing_command")
+            .takes_value(true)
+            .help("External program used by DeBot signing boxes instead of local keys. It is called with \"public\" argument to get the public key and with \"sign\" argument and hex data on stdin to sign it. Results are expected on stdout in hex."))
+        .arg(Arg::with_name("GIVER_ADDRESS")
+            .long("--giver_address")
+            .takes_value(true)
+            .help("Address of the giver used to prepay accounts before deploy (deploy --prepay). Giver must have sendTransaction(dest, value, bounce) function."))
+        .arg(Arg::with_name("GIVER_ABI")
+            .long("--giver_abi")
+            .takes_value(true)
+            .help("Path or link to the giver ABI."))
+        .arg(Arg::with_name("GIVER_KEYS")
+            .long("--giver_keys")
+            .takes_value(true)
+            .help("Seed phrase or path to the file with keypair used to sign giver calls."))
+        .arg(Arg::with_name("ADDRESS_RESOLVER")
+            .long("--address_resolver")
+            .takes_value(true)
+            .help("Address of the name resolver contract used by DeBot address input. Resolver must have resolve(name) getter returning address."))
         .subcommand(config_clear_cmd)
         .subcommand(config_endpoint_cmd)
         .subcommand(alias_cmd);
@@ -730,7 +1022,10 @@ async fn main_internal() -> Result <(), String> {
         .subcommand(deploy_cmd.clone()
             .about("Executes deploy locally, calculates fees and prints table of fees in nanotons."))
         .subcommand(call_cmd.clone()
-            .about("Executes call locally, calculates fees and prints table of all fees in nanotons."));
+            .about("Executes call locally, calculates fees and prints table of all fees in nanotons.")
+            .arg(Arg::with_name("REAL_BALANCE")
+                .long("--real-balance")
+                .help("Additionally execute the call with the real account balance and report whether the account can afford it.")));
 
     let proposal_cmd = SubCommand::with_name("proposal")
         .help("Proposal control commands.")
@@ -837,6 +1132,22 @@ async fn main_internal() -> Result <(), String> {
             .takes_value(true)
             .help("Validator seed phrase or path to the file with keypair."));
 
+    let convert_cmd = SubCommand::with_name("convert")
+        .about("Converts amounts between tokens and nanotokens.")
+        .setting(AppSettings::SubcommandRequired)
+        .subcommand(SubCommand::with_name("tokens")
+            .about("Converts tokens to nanotokens.")
+            .arg(Arg::with_name("AMOUNT")
+                .required(true)
+                .takes_value(true)
+                .help("Amount in tokens (e.g. 1.5).")))
+        .subcommand(SubCommand::with_name("nano")
+            .about("Converts nanotokens to tokens.")
+            .arg(Arg::with_name("AMOUNT")
+                .required(true)
+                .takes_value(true)
+                .help("Amount in nanotokens.")));
+
     let sendfile_cmd = SubCommand::with_name("sendfile")
         .about("Sends the boc file with an external inbound message to account.")
         .arg(Arg::with_name("BOC")
@@ -866,6 +1177,47 @@ async fn main_internal() -> Result <(), String> {
 
     let replay_cmd = SubCommand::with_name("replay")
         .about("Replays account's transactions starting from zerostate.")
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(SubCommand::with_name("message")
+            .about("Executes the message on the saved account state with the debug executor offline. Prints the transaction, out messages and the new account state.")
+            .arg(Arg::with_name("ACCOUNT")
+                .required(true)
+                .takes_value(true)
+                .help("Path to the saved account state (account BOC)."))
+            .arg(Arg::with_name("MESSAGE")
+                .required(true)
+                .takes_value(true)
+                .help("Message in Base64 or path to the file with message BOC."))
+            .arg(Arg::with_name("CONFIG_BOC")
+                .long("--config")
+                .short("-c")
+                .takes_value(true)
+                .help("Path to the config contract boc. Default blockchain config is used if not set."))
+            .arg(Arg::with_name("ABI")
+                .long("--abi")
+                .takes_value(true)
+                .help("Path to the ABI file used to decode out messages."))
+            .arg(Arg::with_name("NOW")
+                .long("--now")
+                .takes_value(true)
+                .help("Now timestamp (in milliseconds) for execution. If not set it is equal to the current timestamp."))
+            .arg(Arg::with_name("UPDATE_STATE")
+                .long("--update")
+                .short("-u")
+                .help("Save the new account state to the account file."))
+            .arg(Arg::with_name("LOG_PATH")
+                .long("--output")
+                .short("-o")
+                .takes_value(true)
+                .help("Path where to store the trace. Default path is \"./trace.log\". Note: old file will be removed."))
+            .arg(Arg::with_name("DBG_INFO")
+                .long("--dbg_info")
+                .short("-d")
+                .takes_value(true)
+                .help("Path to the file with debug info."))
+            .arg(Arg::with_name("FULL_TRACE")
+                .long("--full_trace")
+                .help("Flag that changes trace to full version.")))
         .arg(Arg::with_name("CONFIG_TXNS")
             .long("--config")
             .short("-c")
@@ -906,16 +1258,82 @@ async fn main_internal() -> Result <(), String> {
             .short("-c")
             .long("--config")
             .takes_value(true))
+        .arg(Arg::with_name("CONFIG_FILE")
+            .help("Path to the TOML or YAML file with config parameters applied on top of the tonos-cli configuration file. TONOSCLI_<PARAMETER> environment variables override them, command line flags override both.")
+            .long("--config-file")
+            .takes_value(true)
+            .value_name("PATH"))
         .arg(Arg::with_name("JSON")
             .help("Cli prints output in json format.")
             .short("-j")
             .long("--json"))
+        .arg(Arg::with_name("PRETTY")
+            .help("Print the results of calls and get-methods as pretty json. Default without --json.")
+            .long("--pretty")
+            .conflicts_with("COMPACT"))
+        .arg(Arg::with_name("COMPACT")
+            .help("Print the results of calls and get-methods as compact single line json. Default with --json.")
+            .long("--compact"))
+        .arg(Arg::with_name("DRY_RUN")
+            .help("Emulate messages locally and print fees and output without sending them to the network. Applies to all commands sending messages: calls, deploy, multisig, depool, message and sendfile.")
+            .long("--dry-run"))
+        .arg(Arg::with_name("NO_COLOR")
+            .help("Disable colors in the output. Colors are also disabled if stdout is not a terminal or NO_COLOR environment variable is set.")
+            .long("--no-color"))
+        .arg(Arg::with_name("LOG_FILE")
+            .help("Path to the file where SDK and CLI logs are appended.")
+            .long("--log-file")
+            .takes_value(true))
+        .arg(Arg::with_name("LOG_LEVEL")
+            .help("Maximum level of the records written to the log file: error, warn, info, debug or trace. Default is debug.")
+            .long("--log-level")
+            .takes_value(true)
+            .requires("LOG_FILE"))
+        .arg(Arg::with_name("JSON_OUTPUT_FILE")
+            .help("Path to the file where the result json is written. Without --json flag the result is not printed to stdout, status lines are still printed.")
+            .long("--json-output-file")
+            .takes_value(true)
+            .value_name("PATH"))
+        .arg(Arg::with_name("TRACE_OUTPUT")
+            .help("Path where the debug trace of the failed call or run is saved. By default it is saved to call_<address>_<method>.log or run_<address>_<method>.log.")
+            .long("--trace-output")
+            .takes_value(true)
+            .value_name("PATH"))
+        .arg(Arg::with_name("ENUMS")
+            .help("Path to the json file with enums ({\"Status\": {\"Active\": 2}}) whose values can be passed by name (Status.Active) in integer arguments of alternative syntax commands. Enums from the \"enums\" section of the ABI are used too.")
+            .long("--enums")
+            .takes_value(true)
+            .value_name("PATH"))
+        .arg(Arg::with_name("ACCESS_KEY")
+            .help("Access key (project secret or JWT token) for the endpoint. Overrides access_key from the config for this command.")
+            .long("--access-key")
+            .takes_value(true)
+            .value_name("KEY"))
+        .arg(Arg::with_name("HEADER")
+            .help("HTTP header for the endpoint requests in form \"Name: value\". Only \"Authorization: Bearer <token>\" is supported by the network client. Can be specified several times.")
+            .long("--header")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .value_name("HEADER"))
+        .arg(Arg::with_name("INTERACTIVE")
+            .help("Prompt for the function arguments missing in the command line of alternative syntax commands (callx, runx, deployx). Works only when stdin is a terminal.")
+            .long("--interactive"))
+        .arg(Arg::with_name("ALLOW_UNDEFINED")
+            .help("Keep references to undefined environment variables (${VAR}) in the function arguments as is instead of failing. Use $${ to pass the literal ${.")
+            .long("--allow-undefined"))
+        .arg(Arg::with_name("REFRESH_CONFIG")
+            .help("Query the blockchain config from the network for debug and replay commands even if a fresh copy is cached in ~/.evs-cli.")
+            .long("--refresh-config"))
+        .arg(Arg::with_name("DECODE_TYPES")
+            .help("Reformat decoded output of calls and get-methods using ABI types: token amounts are shown in nano and tokens, UTF-8 bytes are shown as strings.")
+            .long("--decode-types"))
         .subcommand(version_cmd)
         .subcommand(genphrase_cmd)
         .subcommand(genpubkey_cmd)
         .subcommand(getkeypair_cmd)
         .subcommand(genaddr_cmd)
-        .subcommand(deploy_cmd
+        .subcommand(deploy_cmd.arg(prepay_arg)
             .arg(alias_arg_long.clone()))
         .subcommand(deploy_message_cmd)
         .subcommand(call_cmd)
@@ -940,6 +1358,7 @@ async fn main_internal() -> Result <(), String> {
         .subcommand(getconfig_cmd)
         .subcommand(bcconfig_cmd)
         .subcommand(nodeid_cmd)
+        .subcommand(convert_cmd)
         .subcommand(sendfile_cmd)
         .subcommand(fetch_block_cmd)
         .subcommand(fetch_cmd)
@@ -963,32 +1382,93 @@ async fn main_internal() -> Result <(), String> {
         })?;
 
     let is_json = matches.is_present("JSON");
+    output::init_color(matches.is_present("NO_COLOR"));
+    if let Some(path) = matches.value_of("LOG_FILE") {
+        init_file_logger(path, matches.value_of("LOG_LEVEL"))?;
+        log::info!("running command \"{}\"", matches.subcommand_name().unwrap_or_default());
+    }
 
-    command_parser(&matches, is_json).await
-        .map_err(|e| {
-            if e.is_empty() {
-                e
-            } else if is_json {
-                let e = serde_json::from_str(&e).unwrap_or(Value::String(e));
-                format!("{:#}", json!({"Error": e}))
-            } else {
-                format!("Error: {e}")
-            }
-        })
-}
-
-async fn command_parser(matches: &ArgMatches<'_>, is_json: bool) -> Result <(), String> {
     let config_file = matches.value_of("CONFIG").map(|v| v.to_string())
         .or(env::var("TONOSCLI_CONFIG").ok())
         .unwrap_or(default_config_name());
 
-    let mut full_config = FullConfig::from_file(&config_file);
+    let full_config = FullConfig::from_file(&config_file);
+    // errors are printed in json if it is enabled either by the flag or in the config file
+    let error_config = Config {
+        is_json: is_json || full_config.config.is_json,
+        pretty_json: pretty_json_from_matches(&matches),
+        ..Default::default()
+    };
+
+    command_parser(&matches, full_config, is_json).await
+        .map_err(|e| format_error(e, &error_config))
+}
+
+fn pretty_json_from_matches(matches: &ArgMatches<'_>) -> Option<bool> {
+    if matches.is_present("PRETTY") {
+        Some(true)
+    } else if matches.is_present("COMPACT") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Returns the process exit code for the error. If the error was caused by the contract
+/// compute phase, its exit code is used clamped to the 1..=125 range (so it is not mixed
+/// with codes reserved by shells, and negative TVM codes like -14 become 1). Otherwise 1.
+fn error_exit_code(err: &str) -> i32 {
+    let err = err.strip_prefix("Error: ").unwrap_or(err);
+    serde_json::from_str::<Value>(err).ok()
+        .and_then(|e| {
+            let e = e.get("Error").unwrap_or(&e);
+            e["data"]["exit_code"].as_i64()
+        })
+        .map(|code| code.clamp(1, 125) as i32)
+        .unwrap_or(1)
+}
+
+fn format_error(e: String, config: &Config) -> String {
+    if e.is_empty() {
+        e
+    } else if config.is_json {
+        let e = serde_json::from_str(&e).unwrap_or(Value::String(e));
+        format_json_result(&json!({"Error": e}), config)
+    } else {
+        format!("Error: {e}")
+    }
+}
 
+async fn command_parser(matches: &ArgMatches<'_>, mut full_config: FullConfig, is_json: bool) -> Result <(), String> {
     if let Some(m) = matches.subcommand_matches("config") {
         return config_command(m, full_config, is_json);
     }
 
+    if let Some(path) = matches.value_of("CONFIG_FILE") {
+        full_config.apply_layers(path)?;
+    }
     full_config.config.is_json |= is_json;
+    // only `call` asks for confirmation before sending (see `call_config`), commands that
+    // send messages on behalf of the user (multisig, depool, callx, etc.) have no `-y` flag
+    full_config.config.confirm = Some(false);
+    full_config.config.dry_run = matches.is_present("DRY_RUN");
+    full_config.config.decode_types = matches.is_present("DECODE_TYPES");
+    full_config.config.json_output_file = matches.value_of("JSON_OUTPUT_FILE").map(|x| x.to_owned());
+    full_config.config.trace_output = matches.value_of("TRACE_OUTPUT").map(|x| x.to_owned());
+    full_config.config.enums = matches.value_of("ENUMS").map(|x| x.to_owned());
+    full_config.config.interactive = matches.is_present("INTERACTIVE");
+    full_config.config.refresh_config = matches.is_present("REFRESH_CONFIG");
+    if let Some(pretty) = pretty_json_from_matches(matches) {
+        full_config.config.pretty_json = Some(pretty);
+    }
+    full_config.config.allow_undefined_env = matches.is_present("ALLOW_UNDEFINED");
+    if let Some(access_key) = matches.value_of("ACCESS_KEY") {
+        full_config.config.access_key = Some(access_key.to_owned());
+    }
+    full_config.config.headers = matches.values_of("HEADER")
+        .map(|headers| headers.map(|x| x.to_owned()).collect())
+        .unwrap_or_default();
+    full_config.config.once_ledger = once_ledger_path(&full_config.path);
     let config = &mut full_config.config;
 
     if let Some(url) = matches.value_of("NETWORK") {
@@ -998,6 +1478,11 @@ async fn command_parser(matches: &ArgMatches<'_>, is_json: bool) -> Result <(),
         config.url = resolved_url;
     }
 
+    for subcommand in ["callx", "runx", "deployx"] {
+        if matches.subcommand_matches(subcommand).map_or(false, |m| m.is_present("YES")) {
+            full_config.config.interactive = false;
+        }
+    }
     if let Some(m) = matches.subcommand_matches("callx") {
         return callx_command(m, &full_config).await;
     }
@@ -1100,6 +1585,9 @@ async fn command_parser(matches: &ArgMatches<'_>, is_json: bool) -> Result <(),
     if let Some(m) = matches.subcommand_matches("nodeid") {
         return nodeid_command(m, config);
     }
+    if let Some(m) = matches.subcommand_matches("convert") {
+        return convert_command(m, config);
+    }
     if let Some(m) = matches.subcommand_matches("sendfile") {
         return sendfile_command(m, config).await;
     }
@@ -1119,6 +1607,9 @@ async fn command_parser(matches: &ArgMatches<'_>, is_json: bool) -> Result <(),
         return fetch_command(m, config).await;
     }
     if let Some(m) = matches.subcommand_matches("replay") {
+        if let Some(m) = m.subcommand_matches("message") {
+            return replay_message_command(m, config).await;
+        }
         return replay_command(m, config).await;
     }
     if let Some(m) = matches.subcommand_matches("test") {
@@ -1172,13 +1663,17 @@ fn getkeypair_command(matches: &ArgMatches, config: &Config) -> Result<(), Strin
 
 async fn send_command(matches: &ArgMatches<'_>, config: &Config) -> Result<(), String> {
     let message = matches.value_of("MESSAGE");
-    let abi = Some(abi_from_matches_or_config(matches, &config)?);
+    let abis: Vec<String> = match matches.values_of("ABI") {
+        Some(values) => values.map(|s| s.to_string()).collect(),
+        None => vec![abi_from_matches_or_config(matches, &config)?],
+    };
+    let abi = Some(abis.join(", "));
 
     if !config.is_json {
         print_args!(message, abi);
     }
 
-    call_contract_with_msg(config, message.unwrap().to_owned(), &abi.unwrap()).await
+    call_contract_with_msg(config, message.unwrap().to_owned(), &abis, matches.is_present("OFFLINE")).await
 }
 
 async fn body_command(matches: &ArgMatches<'_>, config: &Config) -> Result<(), String> {
@@ -1219,7 +1714,160 @@ async fn body_command(matches: &ArgMatches<'_>, config: &Config) -> Result<(), S
     Ok(())
 }
 
+fn parse_message_time(val: &str) -> Result<u64, String> {
+    let time = u64::from_str_radix(val, 10)
+        .map_err(|e| format!("Failed to parse time: {e}"))?;
+    // Timestamps in milliseconds between 2001 and 2286 years.
+    if !(1_000_000_000_000..10_000_000_000_000).contains(&time) {
+        return Err(format!("time {} is not a timestamp in milliseconds", time));
+    }
+    Ok(time)
+}
+
+fn parse_call_lifetime(lifetime: Option<&str>, config: &Config) -> Result<Option<u32>, String> {
+    let lifetime = lifetime.map(|val| {
+            u32::from_str_radix(val, 10)
+                .map_err(|e| format!("Failed to parse lifetime: {e}"))
+        })
+        .transpose()?;
+    if let Some(lifetime) = lifetime {
+        if lifetime == 0 {
+            return Err("lifetime must be greater than zero".to_string());
+        }
+        if lifetime > MAX_CALL_LIFETIME && !config.is_json {
+            println!("Warning: lifetime {} exceeds {} seconds.", lifetime, MAX_CALL_LIFETIME);
+        }
+    }
+    Ok(lifetime)
+}
+
+fn call_config(matches: &ArgMatches<'_>, config: &Config) -> Result<Config, String> {
+    let mut config = config.clone();
+    config.output_message = matches.value_of("OUTPUT_MESSAGE").map(|x| x.to_owned());
+    config.verify_key = matches.value_of("VERIFY_KEY").map(|x| x.to_owned());
+    config.raw_transaction = matches.is_present("RAW");
+    config.error_map = matches.value_of("ERRORS").map(|x| x.to_owned());
+    config.real_balance = matches.is_present("REAL_BALANCE");
+    config.config_boc = matches.value_of("CONFIG_BOC").map(|x| x.to_owned());
+    config.libs = matches.value_of("LIBS").map(|x| x.to_owned());
+    config.emulate_src = matches.value_of("SRC")
+        .map(|src| load_ton_address(src, &config))
+        .transpose()?;
+    config.emulate_value = matches.value_of("VALUE").map(|x| x.to_owned());
+    config.once = matches.value_of("ONCE").map(|x| x.to_owned());
+    config.nonce = matches.value_of("NONCE")
+        .map(|n| n.parse::<u64>().map_err(|e| format!("failed to parse nonce: {}", e)))
+        .transpose()?;
+    config.nonce_getter = matches.value_of("NONCE_GETTER").map(|x| x.to_owned());
+    config.await_result = matches.is_present("AWAIT");
+    config.message_ttl = matches.value_of("MESSAGE_TTL")
+        .map(|t| t.parse::<u32>().map_err(|e| format!("failed to parse ttl: {}", e)))
+        .transpose()?;
+    config.warn_drift = matches.is_present("WARN_DRIFT");
+    config.fail_on_empty_output = matches.is_present("FAIL_ON_EMPTY_OUTPUT");
+    config.account_snapshot = matches.value_of("ACCOUNT_SNAPSHOT").map(|x| x.to_owned());
+    config.tonconnect_amount = matches.value_of("AMOUNT")
+        .filter(|_| matches.is_present("TONCONNECT"))
+        .map(parse_integer_param)
+        .transpose()?;
+    config.max_fee = matches.value_of("MAX_FEE")
+        .map(|fee| parse_integer_param(fee)?.parse::<u64>()
+            .map_err(|e| format!("failed to parse max fee: {}", e)))
+        .transpose()?;
+    config.out_abis = matches.values_of("OUT_ABI")
+        .map(|abis| abis.map(|x| x.to_owned()).collect())
+        .unwrap_or_default();
+    config.wait_timeout = matches.value_of("WAIT_TIMEOUT")
+        .map(|t| t.parse::<u64>().map_err(|e| format!("failed to parse wait timeout: {}", e)))
+        .transpose()?;
+    // the SDK takes the timeout in milliseconds as u32
+    if let Some(secs) = config.wait_timeout.filter(|secs| *secs > (u32::MAX / 1000) as u64) {
+        return Err(format!("wait timeout {} is too large, the maximum is {} seconds", secs, u32::MAX / 1000));
+    }
+    config.signers = matches.values_of("SIGN")
+        .map(|keys| keys.map(|x| x.to_owned()).collect())
+        .unwrap_or_default();
+    config.since_lt = matches.value_of("SINCE_LT")
+        .map(|lt| match lt {
+            "current" => Ok(None),
+            lt => match lt.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16),
+                None => lt.parse::<u64>(),
+            }.map(Some).map_err(|e| format!("failed to parse logical time: {}", e)),
+        })
+        .transpose()?;
+    config.message_time = matches.value_of("TIME")
+        .map(parse_message_time)
+        .transpose()?;
+    config.confirm = if matches.is_present("YES") {
+        Some(false)
+    } else if matches.is_present("CONFIRM") {
+        Some(true)
+    } else {
+        None
+    };
+    Ok(config)
+}
+
+/// Keys derived from `--phrase` or `--phrase-file`, passed on as an inline keypair so that the
+/// phrase itself is never printed. Keys from `--keys-from-keychain` are passed as a reference to
+/// the keychain entry, the secret is read from it only when the message is signed.
+fn phrase_keys(matches: &ArgMatches<'_>) -> Result<Option<String>, String> {
+    let keypair = if let Some(phrase) = matches.value_of("PHRASE") {
+        keypair_from_phrase(phrase)?
+    } else if let Some(path) = matches.value_of("PHRASE_FILE") {
+        keypair_from_phrase_file(path)?
+    } else if let Some(name) = matches.value_of("KEYS_FROM_KEYCHAIN") {
+        return Ok(Some(format!("{}{}", crypto::KEYCHAIN_KEYS_PREFIX, name)));
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(format!("{}:{}", keypair.secret, keypair.public)))
+}
+
 async fn call_command(matches: &ArgMatches<'_>, config: &Config, call: CallType) -> Result<(), String> {
+    if let Some(batch) = matches.value_of("BATCH") {
+        let is_fee = matches!(call, CallType::Fee);
+        let abi = abi_from_matches_or_config(matches, config).ok();
+        let keys = match phrase_keys(matches)? {
+            Some(keys) => Some(keys),
+            None => matches.value_of("KEYS")
+                .or(matches.value_of("SIGN"))
+                .map(|s| s.to_string())
+                .or(config.keys_path.clone()),
+        };
+        let lifetime = parse_call_lifetime(matches.value_of("LIFETIME"), config)?;
+        let config = call_config(matches, config)?;
+        return call_batch(
+            &config,
+            batch,
+            abi,
+            keys,
+            is_fee,
+            lifetime,
+            matches.is_present("STOP_ON_ERROR"),
+        ).await;
+    }
+    if let Some(body) = matches.value_of("RAW_BODY") {
+        if !matches!(call, CallType::Call) {
+            return Err("--raw-body can be used only to send a call".to_owned());
+        }
+        let address = matches.value_of("ADDRESS");
+        let keys = match phrase_keys(matches)? {
+            Some(keys) => Some(keys),
+            None => matches.value_of("KEYS")
+                .or(matches.value_of("SIGN"))
+                .map(|s| s.to_string()),
+        };
+        if !config.is_json {
+            let raw_body = Some(body);
+            let keys = keys.as_deref().map(hide_inline_keypair);
+            print_args!(address, raw_body, keys);
+        }
+        let address = load_ton_address(address.unwrap(), config)?;
+        let config = call_config(matches, config)?;
+        return call_raw_body(&config, &address, body, keys).await;
+    }
     let address = matches.value_of("ADDRESS");
     let method = matches.value_of("METHOD");
     let params = matches.value_of("PARAMS");
@@ -1228,15 +1876,30 @@ async fn call_command(matches: &ArgMatches<'_>, config: &Config, call: CallType)
     let output = matches.value_of("OUTPUT");
     let signature_id = matches.value_of("SIGNATURE_ID");
 
-    let abi = Some(abi_from_matches_or_config(matches, &config)?);
+    let abi = match matches.value_of("ABI_FROM_CONTRACT") {
+        Some(getter) => {
+            let address = load_ton_address(address.unwrap(), &config)?;
+            abi_from_contract(&config, &address, getter).await?
+        },
+        None => abi_from_matches_or_config(matches, &config)?,
+    };
+    let abi = Some(abi);
 
-    let keys = matches.value_of("KEYS")
-        .or(matches.value_of("SIGN"))
-        .map(|s| s.to_string())
-        .or(config.keys_path.clone());
+    let keys = match phrase_keys(matches)? {
+        Some(keys) => Some(keys),
+        None => matches.value_of("KEYS")
+            .or(matches.value_of("SIGN"))
+            .map(|s| s.to_string())
+            .or(config.keys_path.clone()),
+    };
 
     let params = Some(load_params(params.unwrap())?);
     if !config.is_json {
+        let keys = keys.as_deref().map(hide_inline_keypair);
+        let abi = match matches.value_of("ABI_FROM_CONTRACT") {
+            Some(getter) => Some(format!("fetched with getter {}", getter)),
+            None => abi.clone(),
+        };
         print_args!(address, method, params, abi, keys, signature_id, lifetime, output);
     }
     let address = load_ton_address(address.unwrap(), &config)?;
@@ -1244,18 +1907,43 @@ async fn call_command(matches: &ArgMatches<'_>, config: &Config, call: CallType)
     match call {
         CallType::Call | CallType::Fee => {
             let is_fee = if let CallType::Fee = call { true } else { false };
+            let lifetime = parse_call_lifetime(lifetime, config)?;
+            let config = call_config(matches, config)?;
+            if let Some(count) = matches.value_of("COUNT") {
+                let count = count.parse::<usize>()
+                    .map_err(|e| format!("failed to parse count: {}", e))?;
+                if count == 0 {
+                    return Err("count must be greater than 0".to_owned());
+                }
+                let concurrency = matches.value_of("CONCURRENCY")
+                    .map(|c| c.parse::<usize>().map_err(|e| format!("failed to parse concurrency: {}", e)))
+                    .transpose()?
+                    .unwrap_or(1);
+                return call_repeated(
+                    &config,
+                    address.as_str(),
+                    &abi.unwrap(),
+                    method.unwrap(),
+                    &params.unwrap(),
+                    keys,
+                    lifetime,
+                    count,
+                    concurrency,
+                ).await;
+            }
             call_contract(
-                config,
+                &config,
                 address.as_str(),
                 &abi.unwrap(),
                 method.unwrap(),
                 &params.unwrap(),
                 keys,
                 is_fee,
+                lifetime,
             ).await
         },
         CallType::Msg => {
-            let lifetime = lifetime.map(|val| {
+            let lifetime = matches.value_of("MESSAGE_TTL").or(lifetime).map(|val| {
                     u32::from_str_radix(val, 10)
                         .map_err(|e| format!("Failed to parse lifetime: {e}"))
                 })
@@ -1304,6 +1992,7 @@ async fn callx_command(matches: &ArgMatches<'_>, full_config: &FullConfig) -> Re
     let params = Some(load_params(&params)?);
 
     if !config.is_json {
+        let keys = keys.as_deref().map(hide_inline_keypair);
         print_args!(address, method, params, abi, keys);
     }
 
@@ -1317,6 +2006,7 @@ async fn callx_command(matches: &ArgMatches<'_>, full_config: &FullConfig) -> Re
         &params.unwrap(),
         keys,
         false,
+        None,
     ).await
 }
 
@@ -1348,6 +2038,8 @@ async fn runget_command(matches: &ArgMatches<'_>, config: &Config) -> Result<(),
 
 
 async fn deploy_command(matches: &ArgMatches<'_>, full_config: &mut FullConfig, deploy_type: DeployType) -> Result<(), String> {
+    full_config.config.prepay = matches.value_of("PREPAY").map(|x| x.to_owned());
+    full_config.config.constructor = matches.value_of("CONSTRUCTOR").map(|x| x.to_owned());
     let config = &full_config.config;
     let tvc = matches.value_of("TVC");
     let wc = wc_from_matches_or_config(matches, config)?;
@@ -1355,19 +2047,23 @@ async fn deploy_command(matches: &ArgMatches<'_>, full_config: &mut FullConfig,
     let output = matches.value_of("OUTPUT");
     let abi = Some(abi_from_matches_or_config(matches, config)?);
     let signature_id = matches.value_of("SIGNATURE_ID");
-    let keys = matches.value_of("KEYS")
+    let keys = match phrase_keys(matches)? {
+        Some(keys) => Some(keys),
+        None => matches.value_of("KEYS")
             .or(matches.value_of("SIGN"))
             .map(|s| s.to_string())
-            .or(config.keys_path.clone());
+            .or(config.keys_path.clone()),
+    };
     let alias = matches.value_of("ALIAS");
     let params = Some(unpack_alternative_params(
         matches,
         abi.as_ref().unwrap(),
-        "constructor",
+        constructor_name(config),
         config
     ).await?);
     if !config.is_json {
         let opt_wc = Some(format!("{}", wc));
+        let keys = keys.as_deref().map(hide_inline_keypair);
         print_args!(tvc, params, abi, keys, signature_id, opt_wc, alias);
     }
     match deploy_type {
@@ -1388,6 +2084,7 @@ async fn deploy_command(matches: &ArgMatches<'_>, full_config: &mut FullConfig,
 }
 
 async fn deployx_command(matches: &ArgMatches<'_>, full_config: &mut FullConfig) -> Result<(), String> {
+    full_config.config.constructor = matches.value_of("CONSTRUCTOR").map(|x| x.to_owned());
     let config = &full_config.config;
     let tvc = matches.value_of("TVC");
     let wc = wc_from_matches_or_config(matches, config)?;
@@ -1395,7 +2092,7 @@ async fn deployx_command(matches: &ArgMatches<'_>, full_config: &mut FullConfig)
     let params = Some(unpack_alternative_params(
         matches,
         abi.as_ref().unwrap(),
-        "constructor",
+        constructor_name(config),
         config
     ).await?);
     let keys = matches.value_of("KEYS")
@@ -1405,6 +2102,7 @@ async fn deployx_command(matches: &ArgMatches<'_>, full_config: &mut FullConfig)
     let alias = matches.value_of("ALIAS");
     if !config.is_json {
         let opt_wc = Some(format!("{}", wc));
+        let keys = keys.as_deref().map(hide_inline_keypair);
         print_args!(tvc, params, abi, keys, opt_wc, alias);
     }
     deploy_contract(full_config, tvc.unwrap(), &abi.unwrap(), &params.unwrap(), keys, wc, false, alias).await
@@ -1574,6 +2272,7 @@ async fn proposal_create_command(matches: &ArgMatches<'_>, config: &Config) -> R
     let lifetime = matches.value_of("LIFETIME");
     let offline = matches.is_present("OFFLINE");
     if !config.is_json {
+        let keys = keys.map(hide_inline_keypair);
         print_args!(address, comment, keys, lifetime);
     }
     let address = load_ton_address(address.unwrap(), &config)?;
@@ -1597,6 +2296,7 @@ async fn proposal_vote_command(matches: &ArgMatches<'_>, config: &Config) -> Res
     let lifetime = matches.value_of("LIFETIME");
     let offline = matches.is_present("OFFLINE");
     if !config.is_json {
+        let keys = keys.map(hide_inline_keypair);
         print_args!(address, id, keys, lifetime);
     }
     let address = load_ton_address(address.unwrap(), &config)?;
@@ -1671,6 +2371,22 @@ fn nodeid_command(matches: &ArgMatches, config: &Config) -> Result<(), String> {
     Ok(())
 }
 
+fn convert_command(matches: &ArgMatches, config: &Config) -> Result<(), String> {
+    let (value, unit) = if let Some(m) = matches.subcommand_matches("tokens") {
+        (convert::convert_token(m.value_of("AMOUNT").unwrap())?, "nanotokens")
+    } else if let Some(m) = matches.subcommand_matches("nano") {
+        (convert::convert_nanotoken(m.value_of("AMOUNT").unwrap())?, "tokens")
+    } else {
+        return Err("unknown convert command".to_owned());
+    };
+    if !config.is_json {
+        println!("{} {}", value, unit);
+    } else {
+        println!("{}", format_json_result(&json!({ "value": value }), config));
+    }
+    Ok(())
+}
+
 async fn sendfile_command(m: &ArgMatches<'_>, config: &Config) -> Result<(), String> {
     let boc = m.value_of("BOC");
     if !config.is_json {
@@ -12,11 +12,15 @@
  */
 
 use chrono::{Local, TimeZone};
-use serde_json::json;
-use ton_client::abi::{Abi, CallSet, encode_message, FunctionHeader, ParamsOfEncodeMessage, Signer};
+use serde_json::{json, Map, Value};
+use ton_client::abi::{Abi, AbiContract, AbiParam, CallSet, encode_boc, encode_message, FunctionHeader,
+    ParamsOfAbiEncodeBoc, ParamsOfEncodeMessage, Signer};
+use ton_client::boc::{get_boc_hash, ParamsOfGetBocHash};
+use ton_client::crypto::{sign, ParamsOfSign};
 use crate::SignatureIDType;
 use crate::config::Config;
-use crate::helpers::{create_client_with_signature_id, load_abi, load_ton_address, now, TonClient};
+use crate::helpers::{check_abi_function, create_client_with_signature_id, load_abi, load_ton_address, now,
+    parse_ton_abi, TonClient};
 use crate::crypto::load_keypair;
 
 pub struct EncodedMessage {
@@ -41,7 +45,7 @@ pub async fn prepare_message(
         println!("Generating external inbound message...");
     }
 
-    let msg_params = prepare_message_params(addr, abi, method, params, header.clone(), keys, signature_id)?;
+    let msg_params = prepare_message_params(addr, abi, method, params, header.clone(), keys, signature_id, None)?;
 
     let msg = encode_message(ton, msg_params).await
         .map_err(|e| format!("failed to create inbound message: {}", e))?;
@@ -62,10 +66,23 @@ pub fn prepare_message_params (
     header: Option<FunctionHeader>,
     keys: Option<String>,
     signature_id: Option<i32>,
+    nonce: Option<u64>,
 ) -> Result<ParamsOfEncodeMessage, String> {
+    let contract = match &abi {
+        Abi::Json(abi_str) => {
+            let contract = parse_ton_abi(abi_str)?;
+            check_abi_function(&contract, method)?;
+            Some(contract)
+        },
+        _ => None,
+    };
     let keys = keys.map(|k| load_keypair(&k)).transpose()?;
-    let params = serde_json::from_str(&params)
+    let mut params = serde_json::from_str(&params)
         .map_err(|e| format!("arguments are not in json format: {}", e))?;
+    if let Some(nonce) = nonce {
+        let contract = contract.ok_or("nonce can be set only with json ABI".to_string())?;
+        set_nonce(&contract, method, &mut params, nonce)?;
+    }
 
     let call_set = Some(CallSet {
         function_name: method.into(),
@@ -87,20 +104,117 @@ pub fn prepare_message_params (
     })
 }
 
-pub fn print_encoded_message(msg: &EncodedMessage, is_json:bool) {
-    let expire = if msg.expire.is_some() {
-        let expire_at = Local.timestamp_opt(msg.expire.unwrap() as i64, 0).single().unwrap();
-        expire_at.to_rfc2822()
-    } else {
-        "unknown".to_string()
+/// Function parameter which receives signatures of all keys when the call is signed by several keys.
+const SIGNATURES_PARAM: &str = "signatures";
+
+/// Fills the `signatures` parameter of the function (of type `bytes[]`) with signatures of all
+/// the keys. Each key signs the hash of the other function parameters encoded into a cell, which
+/// the contract can check as `tvm.hash(abi.encode(...))`.
+pub fn add_signatures(
+    ton: TonClient,
+    abi: &Abi,
+    method: &str,
+    params: &str,
+    keys: &[String],
+) -> Result<String, String> {
+    let abi_str = match abi {
+        Abi::Json(abi_str) => abi_str,
+        _ => return Err("multiple signatures can be used only with json ABI".to_string()),
     };
+    let contract = serde_json::from_str::<AbiContract>(abi_str)
+        .map_err(|e| format!("failed to parse ABI: {}", e))?;
+    let function = contract.functions.iter()
+        .find(|f| f.name == method)
+        .ok_or(format!("function \"{}\" is not found in the ABI", method))?;
+    let (signatures, payload): (Vec<&AbiParam>, Vec<&AbiParam>) = function.inputs.iter()
+        .partition(|p| p.name == SIGNATURES_PARAM);
+    match signatures.first() {
+        Some(param) if param.param_type == "bytes[]" => {},
+        _ => return Err(format!(
+            "function \"{}\" doesn't support multiple signatures: it must have \"{}\" parameter of type bytes[]",
+            method, SIGNATURES_PARAM
+        )),
+    }
+
+    let mut params: Value = serde_json::from_str(params)
+        .map_err(|e| format!("arguments are not in json format: {}", e))?;
+    let params_map = params.as_object_mut()
+        .ok_or("arguments must be a json object".to_string())?;
+    params_map.remove(SIGNATURES_PARAM);
+    let payload = encode_boc(
+        ton.clone(),
+        ParamsOfAbiEncodeBoc {
+            params: payload.into_iter().cloned().collect(),
+            data: Value::Object(params_map.clone()),
+            boc_cache: None,
+        },
+    ).map_err(|e| format!("failed to encode parameters to sign: {}", e))?;
+    let hash = get_boc_hash(ton.clone(), ParamsOfGetBocHash { boc: payload.boc })
+        .map_err(|e| format!("failed to calculate hash of the parameters: {}", e))?
+        .hash;
+    let hash = hex::decode(hash).map_err(|e| format!("failed to decode hash: {}", e))?;
+
+    let mut signatures = vec![];
+    for keys in keys {
+        let keys = load_keypair(keys)?;
+        let signed = sign(
+            ton.clone(),
+            ParamsOfSign { unsigned: base64::encode(&hash), keys },
+        ).map_err(|e| format!("failed to sign parameters: {}", e))?;
+        signatures.push(signed.signature);
+    }
+    params_map.insert(SIGNATURES_PARAM.to_owned(), json!(signatures));
+    Ok(params.to_string())
+}
+
+/// Puts the nonce into the `nonce` parameter of the function.
+fn set_nonce(contract: &ton_abi::Contract, method: &str, params: &mut Value, nonce: u64) -> Result<(), String> {
+    let function = contract.function(method)
+        .map_err(|e| format!("failed to load function from abi: {}", e))?;
+    if !function.input_params().iter().any(|p| p.name == "nonce") {
+        return Err(format!("function \"{}\" has no \"nonce\" parameter", method));
+    }
+    let params = params.as_object_mut()
+        .ok_or("arguments must be a json object".to_string())?;
+    if let Some(value) = params.get("nonce") {
+        let value = value.as_u64().map(|v| v.to_string())
+            .or(value.as_str().map(|v| v.to_owned()));
+        if value != Some(nonce.to_string()) {
+            return Err(format!("nonce {} conflicts with the \"nonce\" argument", nonce));
+        }
+    }
+    params.insert("nonce".to_owned(), json!(nonce.to_string()));
+    Ok(())
+}
+
+/// Returns the message id and expiration time of the message as a json object.
+pub fn encoded_message_json(msg: &EncodedMessage) -> Map<String, Value> {
+    let expire = msg.expire
+        .and_then(|expire| Local.timestamp_opt(expire as i64, 0).single())
+        .map(|expire_at| expire_at.to_rfc2822())
+        .unwrap_or("unknown".to_string());
+    let mut result = Map::new();
+    result.insert("MessageId".to_owned(), json!(msg.message_id));
+    result.insert("Expire at".to_owned(), json!(expire));
+    result
+}
+
+/// Prints the encoded message: human readable lines or a single line json object with `is_json`.
+pub fn print_encoded_message(msg: &EncodedMessage, extra: Map<String, Value>, is_json: bool) {
+    let mut result = encoded_message_json(msg);
     if !is_json {
         println!();
         println!("MessageId: {}", msg.message_id);
-        println!("Expire at: {}", expire);
+        println!("Expire at: {}", result["Expire at"].as_str().unwrap_or("unknown"));
+        for (name, value) in extra {
+            match value {
+                Value::String(value) => println!("{}: {}", name, value),
+                value => println!("{}: {:#}", name, value),
+            }
+        }
     } else {
-        println!("  \"MessageId\": \"{}\",", msg.message_id);
-        println!("  \"Expire at\": \"{}\",", expire);
+        result.extend(extra);
+        println!("{}", Value::Object(result));
     }
 }
 
@@ -207,34 +321,95 @@ pub fn display_generated_message(
     output: Option<&str>,
     is_json: bool,
 ) -> Result<(), String> {
-    if is_json {
-        println!("{{");
-    }
-    print_encoded_message(msg, is_json);
-
     let msg_bytes = pack_message(msg, method, is_raw)?;
-    if output.is_some() {
-        let out_file = output.unwrap();
+    let mut extra = Map::new();
+    if let Some(out_file) = output {
         std::fs::write(out_file, msg_bytes)
             .map_err(|e| format!("cannot write message to file: {}", e))?;
+        if is_json {
+            extra.insert("Message".to_owned(), json!(format!("saved to file {}", out_file)));
+        }
+        print_encoded_message(msg, extra, is_json);
         if !is_json {
             println!("Message saved to file {}", out_file);
-        } else {
-            println!("  \"Message\": \"saved to file {}\"", out_file);
         }
     } else {
         let msg_hex = hex::encode(&msg_bytes);
+        extra.insert("Message".to_owned(), json!(msg_hex));
+        print_encoded_message(msg, extra, is_json);
         if !is_json {
-            println!("Message: {}", msg_hex);
             println!();
             qr2term::print_qr(msg_hex).map_err(|e| format!("failed to print QR code: {}", e))?;
             println!();
-        } else {
-            println!("  \"Message\": \"{}\"", msg_hex);
         }
     }
-    if is_json {
-        println!("}}");
-    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::create_client_local;
+    use ton_client::crypto::{verify_signature, ParamsOfVerifySignature};
+
+    const MULTISIG_ABI: &str = r#"{
+        "ABI version": 2,
+        "version": "2.2",
+        "header": ["time", "expire"],
+        "functions": [{
+            "name": "execute",
+            "inputs": [
+                {"name":"dest","type":"address"},
+                {"name":"value","type":"uint128"},
+                {"name":"signatures","type":"bytes[]"}
+            ],
+            "outputs": []
+        }, {
+            "name": "ping",
+            "inputs": [{"name":"value","type":"uint128"}],
+            "outputs": []
+        }],
+        "data": [],
+        "events": []
+    }"#;
+
+    #[test]
+    fn test_add_signatures() {
+        let ton = create_client_local().unwrap();
+        let abi = Abi::Json(MULTISIG_ABI.to_owned());
+        let keys = vec!["tests/samples/test.keys.json".to_owned(), "tests/samples/giver_v2.key".to_owned()];
+        let dest = format!("0:{}", "1".repeat(64));
+        let params = format!(r#"{{"dest":"{}","value":"1000"}}"#, dest);
+        let signed = add_signatures(ton.clone(), &abi, "execute", &params, &keys).unwrap();
+        let signed: Value = serde_json::from_str(&signed).unwrap();
+        let signatures = signed["signatures"].as_array().unwrap();
+        assert_eq!(signatures.len(), 2);
+
+        let payload = encode_boc(ton.clone(), ParamsOfAbiEncodeBoc {
+            params: serde_json::from_value(json!([
+                {"name":"dest","type":"address"},
+                {"name":"value","type":"uint128"}
+            ])).unwrap(),
+            data: json!({"dest": dest, "value": "1000"}),
+            boc_cache: None,
+        }).unwrap().boc;
+        let hash = hex::decode(get_boc_hash(ton.clone(), ParamsOfGetBocHash { boc: payload }).unwrap().hash).unwrap();
+        for (signature, keys) in signatures.iter().zip(keys.iter()) {
+            let mut signed = hex::decode(signature.as_str().unwrap()).unwrap();
+            signed.extend_from_slice(&hash);
+            let verified = verify_signature(ton.clone(), ParamsOfVerifySignature {
+                signed: base64::encode(&signed),
+                public: load_keypair(keys).unwrap().public,
+            }).unwrap();
+            assert_eq!(base64::decode(verified.unsigned).unwrap(), hash);
+        }
+
+        // the message with signatures is encoded as usual
+        prepare_message_params(
+            &dest, abi.clone(), "execute", &signed.to_string(), None, Some(keys[0].clone()), None, None,
+        ).unwrap();
+
+        let err = add_signatures(ton, &abi, "ping", r#"{"value":"1"}"#, &keys).unwrap_err();
+        assert!(err.contains("doesn't support multiple signatures"));
+    }
+}
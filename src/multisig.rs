@@ -22,7 +22,7 @@ use crate::helpers::{
 use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use serde_json::json;
 use ton_client::abi::{
-    encode_message_body, Abi, AbiContract, AbiParam, CallSet, ParamsOfEncodeMessageBody,
+    encode_message, encode_message_body, Abi, AbiContract, AbiParam, CallSet, ParamsOfEncodeMessageBody,
 };
 
 const SAFEMULTISIG_LINK: &str = "https://github.com/tonlabs/ton-labs-contracts/blob/master/solidity/safemultisig/SafeMultisigWallet.tvc?raw=true";
@@ -387,8 +387,10 @@ impl MultisigArgs {
             &self.params().to_string(),
             Some(self.keys.clone()),
             false,
+            None,
         )
         .await
+        .map(|result| result.output)
     }
 }
 
@@ -533,6 +535,13 @@ async fn multisig_deploy_command(matches: &ArgMatches<'_>, config: &Config) -> R
 
     let ton = create_client_verbose(&config)?;
 
+    if config.dry_run {
+        let message = encode_message(ton.clone(), msg).await
+            .map_err(|e| format!("failed to create inbound message: {}", e))?
+            .message;
+        return call::dry_run_message(ton, &address, message, Some(args.abi().clone()), true, config).await;
+    }
+
     if let Some(value) = matches.value_of("VALUE") {
         let params = format!(r#"{{"dest":"{}","amount":"{}"}}"#, address, value);
         call::call_contract_with_client(
@@ -544,6 +553,7 @@ async fn multisig_deploy_command(matches: &ArgMatches<'_>, config: &Config) -> R
             &params,
             None,
             false,
+            None,
         )
         .await?;
     }
@@ -0,0 +1,61 @@
+/*
+ * Copyright 2018-2023 EverX.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(false);
+
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Enables colors if stdout is a terminal, unless they are disabled with `--no-color`
+/// or with the `NO_COLOR` environment variable.
+pub fn init_color(no_color: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").map(|v| !v.is_empty()).unwrap_or(false);
+    let enabled = !no_color && !no_color_env && std::io::stdout().is_terminal();
+    COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn paint(color: &str, msg: &str, enabled: bool) -> String {
+    if enabled {
+        format!("{}{}{}", color, msg, RESET)
+    } else {
+        msg.to_owned()
+    }
+}
+
+pub fn success(msg: &str) -> String {
+    paint(GREEN, msg, COLOR_ENABLED.load(Ordering::Relaxed))
+}
+
+pub fn warning(msg: &str) -> String {
+    paint(YELLOW, msg, COLOR_ENABLED.load(Ordering::Relaxed))
+}
+
+pub fn error(msg: &str) -> String {
+    paint(RED, msg, COLOR_ENABLED.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(paint(GREEN, "Succeeded.", true), "\x1b[32mSucceeded.\x1b[0m");
+        assert_eq!(paint(GREEN, "Succeeded.", false), "Succeeded.");
+    }
+}
@@ -20,10 +20,12 @@ use std::{
 use clap::ArgMatches;
 use failure::err_msg;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 
-use ton_block::{Account, ConfigParams, Deserializable, Message, Serializable,
+use ton_block::{Account, ConfigParams, Deserializable, InRefValue, Message, Serializable,
                 Transaction, TransactionDescr, Block, HashmapAugType};
+use ton_client::abi::{decode_message, ParamsOfDecodeMessage};
+use ton_client::boc::{parse_message, parse_transaction, ParamsOfParse};
 use ton_client::net::{
     AggregationFn, FieldAggregation, OrderBy, ParamsOfAggregateCollection,
     ParamsOfQueryCollection, SortDirection, aggregate_collection, query_collection,
@@ -34,7 +36,10 @@ use ton_types::{BuilderData, SliceData, UInt256, write_boc};
 use ton_vm::executor::{Engine, EngineTraceInfo};
 
 use crate::config::Config;
-use crate::helpers::{create_client, get_blockchain_config};
+use crate::call::format_json_result;
+use crate::debug::{execute_debug, init_debug_logger, DEFAULT_TRACE_PATH};
+use crate::helpers::{blockchain_config_from_default_json, create_client, create_client_local,
+                     get_blockchain_config, load_abi, now_ms};
 
 pub static CONFIG_ADDR: &str  = "-1:5555555555555555555555555555555555555555555555555555555555555555";
 
@@ -646,3 +651,102 @@ pub async fn replay_command(m: &ArgMatches<'_>, cli_config: &Config) -> Result<(
     ).await?;
     Ok(())
 }
+
+/// Executes the message on the saved account state with the debug executor, offline.
+pub async fn replay_message_command(m: &ArgMatches<'_>, cli_config: &Config) -> Result<(), String> {
+    let account_path = m.value_of("ACCOUNT").ok_or("Missing account state filename")?;
+    let message = m.value_of("MESSAGE").ok_or("Missing message")?;
+    let trace_path = m.value_of("LOG_PATH").unwrap_or(DEFAULT_TRACE_PATH);
+
+    let account = Account::construct_from_file(account_path)
+        .map_err(|e| format!("Failed to load account from the file {}: {}", account_path, e))?;
+    let message = if std::path::Path::new(message).exists() {
+        Message::construct_from_file(message)
+    } else {
+        Message::construct_from_base64(message)
+    }.map_err(|e| format!("Failed to decode message: {}", e))?;
+    let bc_config = match m.value_of("CONFIG_BOC") {
+        Some(path) => get_blockchain_config(cli_config, Some(path)).await?,
+        None => blockchain_config_from_default_json()?,
+    };
+
+    init_debug_logger(trace_path)?;
+    let now = match m.value_of("NOW") {
+        Some(now) => now.parse()
+            .map_err(|e| format!("Failed to convert now to u64: {}", e))?,
+        None => now_ms(),
+    };
+    let last_tr_lt = account.last_tr_time().unwrap_or_default();
+    let mut account_root = account.serialize()
+        .map_err(|e| format!("Failed to serialize account: {}", e))?;
+    let transaction = execute_debug(
+        bc_config,
+        &mut account_root,
+        Some(&message),
+        Some(m),
+        now,
+        last_tr_lt + 1,
+        last_tr_lt + 1,
+        false,
+        false,
+        cli_config,
+    ).await?;
+
+    let ton = create_client_local()?;
+    let tr_boc = transaction.write_to_bytes()
+        .map_err(|e| format!("Failed to serialize transaction: {}", e))?;
+    let parsed_tr = parse_transaction(ton.clone(), ParamsOfParse { boc: base64::encode(tr_boc) })
+        .map_err(|e| format!("Failed to parse transaction: {}", e))?
+        .parsed;
+    let abi = match m.value_of("ABI") {
+        Some(abi) => Some(load_abi(abi, cli_config).await?),
+        None => None,
+    };
+    let mut out_messages = vec![];
+    for InRefValue(msg) in transaction.out_msgs.export_vector()
+        .map_err(|e| format!("Failed to parse out messages: {}", e))? {
+        let msg_boc = base64::encode(msg.write_to_bytes()
+            .map_err(|e| format!("Failed to serialize out message: {}", e))?);
+        let mut parsed = parse_message(ton.clone(), ParamsOfParse { boc: msg_boc.clone() })
+            .map_err(|e| format!("Failed to parse out message: {}", e))?
+            .parsed;
+        if let Some(abi) = &abi {
+            let decoded = decode_message(
+                ton.clone(),
+                ParamsOfDecodeMessage { abi: abi.clone(), message: msg_boc, ..Default::default() },
+            );
+            if let Ok(decoded) = decoded {
+                parsed["decoded"] = json!({ "name": decoded.name, "value": decoded.value });
+            }
+        }
+        out_messages.push(parsed);
+    }
+
+    let account = Account::construct_from_cell(account_root)
+        .map_err(|e| format!("Failed to construct resulting account: {}", e))?;
+    let account_boc = base64::encode(account.write_to_bytes()
+        .map_err(|e| format!("Failed to serialize resulting account: {}", e))?);
+    if m.is_present("UPDATE_STATE") {
+        account.write_to_file(account_path)
+            .map_err(|e| format!("Failed to dump account: {}", e))?;
+    }
+
+    if cli_config.is_json {
+        println!("{}", format_json_result(&json!({
+            "exit_code": parsed_tr["compute"]["exit_code"],
+            "transaction": parsed_tr,
+            "out_messages": out_messages,
+            "account": account_boc,
+        }), cli_config));
+    } else {
+        println!("Transaction: {}", format_json_result(&parsed_tr, cli_config));
+        println!("Out messages: {}", format_json_result(&json!(out_messages), cli_config));
+        println!("Exit code: {}", parsed_tr["compute"]["exit_code"]);
+        println!("Account: {}", account_boc);
+        if m.is_present("UPDATE_STATE") {
+            println!("{} successfully updated", account_path);
+        }
+        println!("Log saved to {}", trace_path);
+    }
+    Ok(())
+}
\ No newline at end of file
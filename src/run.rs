@@ -12,25 +12,36 @@
  */
 
 use clap::ArgMatches;
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Value};
+use std::time::{Duration, Instant};
 use ton_block::{Account, Deserializable, Serializable};
 use ton_client::abi::FunctionHeader;
 use ton_client::tvm::{ExecutionOptions, ParamsOfRunGet, ParamsOfRunTvm, run_get, run_tvm};
 use crate::config::{Config, FullConfig};
-use crate::call::print_json_result;
+use crate::call::{abi_from_contract, decode_out_messages, decode_output_types, execute_message, format_json_result,
+                  load_out_abis, print_json_result};
 use crate::debug::{debug_error, DebugParams, init_debug_logger};
 use crate::helpers::{create_client, now, now_ms, TonClient,
                      contract_data_from_matches_or_config_alias, abi_from_matches_or_config,
                      AccountSource, create_client_local, create_client_verbose, load_abi,
-                     load_account, load_params, unpack_alternative_params, get_blockchain_config};
+                     load_account, load_params, unpack_alternative_params, get_blockchain_config,
+                     load_ton_abi, load_ton_address};
 use crate::message::prepare_message;
 use crate::replay::construct_blockchain_config;
 
 pub async fn run_command(matches: &ArgMatches<'_>, full_config: &FullConfig, is_alternative: bool) -> Result<(), String> {
-    let config = &full_config.config;
+    let mut config = full_config.config.clone();
+    config.out_abis = matches.values_of("OUT_ABI")
+        .map(|abis| abis.map(|x| x.to_owned()).collect())
+        .unwrap_or_default();
+    let config = &config;
     let (address, abi_path) = if is_alternative {
         let (address,abi, _) = contract_data_from_matches_or_config_alias(matches, full_config)?;
         (address.unwrap(), abi.unwrap())
+    } else if let Some(getter) = matches.value_of("ABI_FROM_CONTRACT") {
+        let address = load_ton_address(matches.value_of("ADDRESS").unwrap(), config)?;
+        let abi = abi_from_contract(config, &address, getter).await?;
+        (address, abi)
     } else {
         (matches.value_of("ADDRESS").unwrap().to_string(),
         abi_from_matches_or_config(matches, &config)?)
@@ -57,6 +68,7 @@ pub async fn run_command(matches: &ArgMatches<'_>, full_config: &FullConfig, is_
         trace_path = "trace.log".to_string();
         create_client_local()?
     };
+    let trace_path = config.trace_output.clone().unwrap_or(trace_path);
 
     let (account, account_boc) = load_account(
         &account_source,
@@ -69,7 +81,106 @@ pub async fn run_command(matches: &ArgMatches<'_>, full_config: &FullConfig, is_
         AccountSource::BOC => account.get_addr().unwrap().to_string(),
         AccountSource::TVC => std::iter::repeat("0").take(64).collect()
     };
-    run(matches, config, Some(ton_client), &address, account_boc, abi_path, is_alternative, trace_path).await
+    let until = match matches.value_of("UNTIL") {
+        Some(until) => until,
+        None => return run(matches, config, Some(ton_client), &address, account_boc, abi_path, is_alternative, trace_path).await,
+    };
+    let predicate = Predicate::parse(until)?;
+    let interval = parse_secs(matches.value_of("POLL_INTERVAL"), DEFAULT_POLL_INTERVAL, "poll interval")?;
+    let timeout = parse_secs(matches.value_of("POLL_TIMEOUT"), DEFAULT_POLL_TIMEOUT, "poll timeout")?;
+    if !config.is_json {
+        println!("Waiting for {}...", until);
+    }
+    let (account_source, address, abi_path, trace_path, client) =
+        (&account_source, address.as_str(), abi_path.as_str(), trace_path.as_str(), &ton_client);
+    let (output, out_messages) = poll_until(&predicate, interval, timeout, move || async move {
+        let (_, account_boc) = load_account(account_source, address, Some(client.clone()), config).await?;
+        run_getter(matches, config, client.clone(), address, account_boc, abi_path, is_alternative, trace_path).await
+    }).await?;
+    print_run_output(output, &out_messages, &abi_path, method, config).await
+}
+
+const DEFAULT_POLL_INTERVAL: u64 = 1;
+const DEFAULT_POLL_TIMEOUT: u64 = 60;
+
+fn parse_secs(value: Option<&str>, default: u64, name: &str) -> Result<Duration, String> {
+    let secs = value
+        .map(|x| x.parse::<u64>().map_err(|e| format!("failed to parse {}: {}", name, e)))
+        .transpose()?
+        .unwrap_or(default);
+    Ok(Duration::from_secs(secs))
+}
+
+/// Condition on the decoded get-method output for `run --until`.
+#[derive(Debug, PartialEq)]
+struct Predicate {
+    path: Vec<String>,
+    expected: Value,
+    negate: bool,
+}
+
+impl Predicate {
+    /// Parses `<path>==<value>` or `<path>!=<value>`. The path consists of field names separated
+    /// by dots and array indexes in brackets (`.info.owners[0]`), the value is json or a plain string.
+    fn parse(s: &str) -> Result<Self, String> {
+        let (path, value, negate) = if let Some((path, value)) = s.split_once("!=") {
+            (path, value, true)
+        } else if let Some((path, value)) = s.split_once("==") {
+            (path, value, false)
+        } else {
+            return Err(format!("invalid predicate \"{}\": expected <path>==<value> or <path>!=<value>", s));
+        };
+        let path: Vec<String> = path.trim()
+            .split(|c| c == '.' || c == '[')
+            .map(|key| key.trim_end_matches(']').to_owned())
+            .filter(|key| !key.is_empty())
+            .collect();
+        if path.is_empty() {
+            return Err(format!("invalid predicate \"{}\": path is empty", s));
+        }
+        let value = value.trim();
+        let expected = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_owned()));
+        Ok(Self { path, expected, negate })
+    }
+
+    fn matches(&self, output: &Value) -> bool {
+        let actual = self.path.iter().try_fold(output, |value, key| match value {
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            value => value.get(key),
+        });
+        let equal = match (actual, &self.expected) {
+            (Some(actual), expected) if actual == expected => true,
+            // ABI decodes integers as strings, so they are compared with the string form of the value
+            (Some(Value::String(actual)), expected) if !expected.is_string() => actual == &expected.to_string(),
+            _ => false,
+        };
+        equal != self.negate
+    }
+}
+
+/// Runs the getter until its output satisfies the predicate or the timeout elapses.
+async fn poll_until<F, Fut>(
+    predicate: &Predicate,
+    interval: Duration,
+    timeout: Duration,
+    mut run_getter: F,
+) -> Result<(Option<Value>, Vec<String>), String>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<(Option<Value>, Vec<String>), String>>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        let result = run_getter().await?;
+        if result.0.as_ref().map_or(false, |output| predicate.matches(output)) {
+            return Ok(result);
+        }
+        if Instant::now() + interval > deadline {
+            return Err(format!("the condition was not met in {} seconds, last output: {}",
+                timeout.as_secs(), result.0.unwrap_or(Value::Null)));
+        }
+        tokio::time::sleep(interval).await;
+    }
 }
 
 async fn run(
@@ -88,8 +199,6 @@ async fn run(
     } else {
         matches.value_of("METHOD").unwrap()
     };
-    let bc_config = matches.value_of("BCCONFIG");
-
     if !config.is_json {
         println!("Running get-method...");
     }
@@ -99,10 +208,37 @@ async fn run(
             create_client_local()?
         }
     };
+    let (output, out_messages) = run_getter(
+        matches, config, ton_client, address, account_boc, &abi_path, is_alternative, &trace_path,
+    ).await?;
+    if !config.is_json {
+        println!("Succeeded.");
+    }
+    print_run_output(output, &out_messages, &abi_path, method, config).await
+}
 
-    let abi = load_abi(&abi_path, config).await?;
+// runs the get-method and returns its decoded output and out messages.
+async fn run_getter(
+    matches: &ArgMatches<'_>,
+    config: &Config,
+    ton_client: TonClient,
+    address: &str,
+    account_boc: String,
+    abi_path: &str,
+    is_alternative: bool,
+    trace_path: &str,
+) -> Result<(Option<Value>, Vec<String>), String> {
+    let method = if is_alternative {
+        matches.value_of("METHOD").or(config.method.as_deref())
+        .ok_or("Method is not defined. Supply it in the config file or command line.")?
+    } else {
+        matches.value_of("METHOD").unwrap()
+    };
+    let bc_config = matches.value_of("BCCONFIG");
+
+    let abi = load_abi(abi_path, config).await?;
     let params = if is_alternative {
-        unpack_alternative_params(matches, &abi_path, method, config).await?
+        unpack_alternative_params(matches, abi_path, method, config).await?
     } else {
         matches.value_of("PARAMS").unwrap().to_string()
     };
@@ -115,6 +251,12 @@ async fn run(
         ..Default::default()
     };
 
+    let executor = matches.is_present("EXECUTOR");
+    let keys = if executor {
+        matches.value_of("SIGN").map(|keys| keys.to_owned())
+    } else {
+        None
+    };
     let msg = prepare_message(
         ton_client.clone(),
         &address,
@@ -122,11 +264,22 @@ async fn run(
         method,
         &params,
         Some(header),
-        None,
+        keys,
         config.is_json,
         None,
     ).await?;
 
+    if executor {
+        let result = execute_message(
+            ton_client.clone(),
+            account_boc,
+            msg.message,
+            Some(abi),
+            true,
+        ).await?;
+        return Ok((result.decoded.and_then(|d| d.output), result.out_messages));
+    }
+
     let execution_options = prepare_execution_options(bc_config.clone())?;
     let result = run_tvm(
         ton_client.clone(),
@@ -154,23 +307,46 @@ async fn run(
                 is_getter: true,
                 ..DebugParams::new(config, bc_config)
             };
-            init_debug_logger(&trace_path)?;
+            init_debug_logger(trace_path)?;
             debug_error(&e, debug_params).await?;
             return Err(format!("{:#}", e));
         }
     };
-    if !config.is_json {
-        println!("Succeeded.");
-    }
-    if !result.out_messages.is_empty() {
-        let res = result.decoded.and_then(|d| d.output);
-        match res {
+    Ok((result.decoded.and_then(|d| d.output), result.out_messages))
+}
+
+async fn print_run_output(
+    output: Option<Value>,
+    out_messages: &[String],
+    abi_path: &str,
+    method: &str,
+    config: &Config,
+) -> Result<(), String> {
+    if !out_messages.is_empty() {
+        match output {
             Some(data) => {
+                let data = if config.decode_types {
+                    let abi = load_ton_abi(abi_path, config).await?;
+                    decode_output_types(&abi, method, data)?
+                } else {
+                    data
+                };
+                let data = if config.out_abis.is_empty() {
+                    data
+                } else {
+                    let abis = load_out_abis(config).await?;
+                    json!({ "output": data, "out_messages": decode_out_messages(out_messages, &abis).await? })
+                };
+                print_json_result(data, config)?;
+            },
+            None if !config.out_abis.is_empty() => {
+                let abis = load_out_abis(config).await?;
+                let data = json!({ "output": null, "out_messages": decode_out_messages(out_messages, &abis).await? });
                 print_json_result(data, config)?;
             },
             None => {
                 println!("Failed to decode output messages. Check that abi matches the contract.");
-                println!("Messages in base64:\n{:?}", result.out_messages);
+                println!("Messages in base64:\n{:?}", out_messages);
             }
         }
     }
@@ -246,7 +422,44 @@ pub async fn run_get_method(config: &Config, addr: &str, method: &str, params: O
             }
         }
         let res = Value::Object(res);
-        println!("{:#}", res);
+        println!("{}", format_json_result(&res, config));
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predicate() {
+        let output = json!({"value0": "5", "info": {"owners": ["0x12", "0x34"], "active": true}});
+        assert!(Predicate::parse(".value0==5").unwrap().matches(&output));
+        assert!(Predicate::parse("value0 != 6").unwrap().matches(&output));
+        assert!(Predicate::parse(".info.owners[1]==0x34").unwrap().matches(&output));
+        assert!(Predicate::parse(".info.active==true").unwrap().matches(&output));
+        assert!(!Predicate::parse(".info.missing==true").unwrap().matches(&output));
+        assert!(Predicate::parse(".value0").is_err());
+        assert!(Predicate::parse("==5").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_poll_until() {
+        // the getter flips its value on the third run
+        let runs = std::cell::Cell::new(0);
+        let getter = || {
+            runs.set(runs.get() + 1);
+            let flipped = runs.get() >= 3;
+            async move { Ok((Some(json!({ "value0": flipped })), vec![])) }
+        };
+        let predicate = Predicate::parse(".value0==true").unwrap();
+        let interval = Duration::from_millis(10);
+        let (output, _) = poll_until(&predicate, interval, Duration::from_secs(5), getter).await.unwrap();
+        assert_eq!(output, Some(json!({ "value0": true })));
+        assert_eq!(runs.get(), 3);
+
+        let predicate = Predicate::parse(".value0==false").unwrap();
+        let err = poll_until(&predicate, interval, Duration::from_millis(30), getter).await.unwrap_err();
+        assert!(err.contains("the condition was not met"));
+    }
+}
@@ -12,7 +12,7 @@
  */
 use crate::helpers::create_client_verbose;
 use crate::config::Config;
-use crate::call::send_message_and_wait;
+use crate::call::{dry_run_message, format_json_result, send_message_and_wait};
 
 pub async fn sendfile(config: &Config, msg_boc: &str) -> Result<(), String> {
     let ton = create_client_verbose(&config)?;
@@ -23,12 +23,17 @@ pub async fn sendfile(config: &Config, msg_boc: &str) -> Result<(), String> {
     let dst = tvm_msg.dst()
         .ok_or("failed to parse dst address".to_string())?;
 
+    if config.dry_run {
+        return dry_run_message(ton, &dst.to_string(), base64::encode(&boc_vec), None, false, config).await;
+    }
     if !config.is_json {
         println!("Sending message to account {}", dst);
     }
-    send_message_and_wait(ton, None, base64::encode(&boc_vec), config).await?;
+    let result = send_message_and_wait(ton, None, base64::encode(&boc_vec), config).await?;
     if !config.is_json {
         println!("Succeded.");
+    } else if config.async_call {
+        println!("{}", format_json_result(&result.output, config));
     }
     Ok(())
 }
\ No newline at end of file
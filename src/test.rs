@@ -12,6 +12,7 @@
  */
 
 use crate::config::Config;
+use crate::call::format_json_result;
 use crate::crypto::{self, load_keypair};
 use crate::debug::{decode_messages, execute_debug, init_debug_logger, DEFAULT_TRACE_PATH};
 use crate::getconfig::serialize_config_param;
@@ -29,11 +30,17 @@ use ton_client::abi::{
     encode_internal_message, encode_message, CallSet, DeploySet, FunctionHeader,
     ParamsOfEncodeInternalMessage, ParamsOfEncodeMessage, Signer as AbiSigner,
 };
-use ton_types::{read_single_root_boc, write_boc, SliceData, BuilderData, ed25519_sign_with_secret};
+use ton_types::{read_single_root_boc, write_boc, SliceData, BuilderData};
 
 pub fn create_test_sign_command<'a, 'b>() -> App<'a, 'b> {
     SubCommand::with_name("sign")
         .about("Generates the ED25519 signature for bytestring.")
+        .arg(
+            Arg::with_name("HASH")
+                .takes_value(true)
+                .conflicts_with_all(&["DATA", "CELL"])
+                .help("Hash for signing hex or base64 encoded. Signed as is, the same way as the signing box does it."),
+        )
         .arg(
             Arg::with_name("DATA")
                 .long("--data")
@@ -387,7 +394,9 @@ async fn test_ticktock(matches: &ArgMatches<'_>, config: &Config) -> Result<(),
 }
 
 pub fn test_sign_command(matches: &ArgMatches<'_>, config: &Config) -> Result<(), String> {
-    let data = if let Some(data) = matches.value_of("DATA") {
+    let data = if let Some(hash) = matches.value_of("HASH") {
+        crypto::decode_hash(hash)?
+    } else if let Some(data) = matches.value_of("DATA") {
         decode_data(data, "data")?
     } else if let Some(data) = matches.value_of("CELL") {
         let data = decode_data(data, "cell")?;
@@ -400,7 +409,7 @@ pub fn test_sign_command(matches: &ArgMatches<'_>, config: &Config) -> Result<()
             cell.repr_hash().into_vec()
         }
     } else {
-        return Err("nor data neither cell parameter".to_string());
+        return Err("nor hash neither data or cell parameter".to_string());
     };
     let pair = match matches.value_of("KEYS") {
         Some(keys) => crypto::load_keypair(&keys)?,
@@ -409,21 +418,20 @@ pub fn test_sign_command(matches: &ArgMatches<'_>, config: &Config) -> Result<()
             None => return Err("nor signing keys in the params neither in the config".to_string()),
         },
     };
-    let key = pair
-        .decode()
-        .map_err(|err| format!("cannot decode keypair {}", err))?;
-    let signature = ed25519_sign_with_secret(&key.to_bytes(), &data)
-        .map_err(|e| format!("Failed to sign: {e}"))?;
-    let signature = base64::encode(signature.as_ref());
+    let signature = crypto::sign_data(&pair, &data)?;
+    let signature_hex = hex::encode(&signature);
+    let signature = base64::encode(&signature);
     if config.is_json {
         let result = json!({
             "Data": hex::encode(data),
             "public": hex::encode(pair.public.as_bytes()),
-            "Signature": signature
+            "Signature": signature,
+            "SignatureHex": signature_hex,
         });
-        println!("{:#}", result);
+        println!("{}", format_json_result(&result, config));
     } else {
         println!("Signature: {}", signature);
+        println!("Signature (hex): {}", signature_hex);
     }
 
     Ok(())
@@ -438,7 +446,7 @@ pub fn test_config_command(matches: &ArgMatches<'_>, config: &Config) -> Result<
             Err(e) => return Err(format!("Failed to serialize json {encode}: {e}")),
         };
         if config.is_json {
-            println!("{:#}", json!({ "Cell": cell, "index": index }));
+            println!("{}", format_json_result(&json!({ "Cell": cell, "index": index }), config));
         } else {
             println!("Cell: \"{}\"", cell);
         }
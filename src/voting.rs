@@ -62,6 +62,7 @@ pub async fn create_proposal(
 			&params,
 			keys,
 			false,
+			None,
 		).await
 	}
 }
@@ -104,6 +105,7 @@ pub async fn vote(
 			&params,
 			keys,
 			false,
+			None,
 		).await
 	}
 }
@@ -123,7 +125,8 @@ pub async fn decode_proposal(
 		"{}",
 		None,
 		false,
-	).await?;
+		None,
+	).await?.output;
 
 	let txns = result["transactions"].as_array()
 		.ok_or(r#"failed to decode result: "transactions" array not found"#.to_string())?;
@@ -93,6 +93,36 @@ fn test_signing_box_interface() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_disabled_signing_box_interface() -> Result<(), Box<dyn std::error::Error>> {
+    let addr = deploy_debot("sample1")?;
+    let (_, _, keys) = get_debot_paths("sample1");
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.timeout(std::time::Duration::from_secs(2))
+        .write_stdin(format!("y\n{}", keys))
+        .arg("debot")
+        .arg("start")
+        .arg(&addr)
+        .arg("--disable-interface")
+        .arg("signingBox");
+    let _cmd = cmd
+        .assert()
+        .stdout(predicate::str::contains("Enter my signing keys:").not())
+        .stdout(predicate::str::contains("Signing Box Handle:").not());
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("debot")
+        .arg("start")
+        .arg(&addr)
+        .arg("--disable-interface")
+        .arg("unknown")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("unknown DeBot interface"));
+    Ok(())
+}
+
 #[test]
 fn test_userinfo() -> Result<(), Box<dyn std::error::Error>> {
     let addr = deploy_debot("sample2")?;
@@ -134,6 +164,93 @@ fn test_userinfo() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_userinfo_answers() -> Result<(), Box<dyn std::error::Error>> {
+    let addr = deploy_debot("sample2")?;
+    let (_, abi, keys) = get_debot_paths("sample2");
+    let wallet = format!("0:{:064}", 1);
+    let key = format!("0x{:064}", 2);
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(abi)
+        .arg("--sign")
+        .arg(keys)
+        .arg(&addr)
+        .arg("setParams")
+        .arg(format!(r#"{{"wallet":"{}","key":"{}"}}"#, wallet, key));
+    cmd.assert()
+        .success();
+
+    let path_to_answers = "tests/sample2_answers.json_tmp";
+    let answers = json!({
+        "browser": { "run": [true] },
+        "a56115147709ed3437efb89460b94a120b7fe94379c795d1ebb0435a847ee580": {
+            "getAccount": [{ "value": wallet }],
+            "getPublicKey": [{ "value": key }]
+        }
+    });
+    std::fs::write(path_to_answers, serde_json::to_string_pretty(&answers).unwrap())?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.timeout(std::time::Duration::from_secs(2))
+        .arg("debot")
+        .arg("start")
+        .arg(&addr)
+        .arg("--answers")
+        .arg(path_to_answers);
+    let _cmd = cmd
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Account is valid"))
+        .stdout(predicate::str::contains("Public key is valid"));
+    std::fs::remove_file(path_to_answers)?;
+    Ok(())
+}
+
+#[test]
+fn test_debot_log() -> Result<(), Box<dyn std::error::Error>> {
+    let addr = deploy_debot("sample2")?;
+    let wallet = format!("0:{:064}", 1);
+    let key = format!("0x{:064}", 2);
+    let path_to_answers = "tests/sample2_log_answers.json_tmp";
+    let path_to_log = "tests/sample2_debot.log_tmp";
+    let answers = json!({
+        "browser": { "run": [true] },
+        "a56115147709ed3437efb89460b94a120b7fe94379c795d1ebb0435a847ee580": {
+            "getAccount": [{ "value": wallet }],
+            "getPublicKey": [{ "value": key }]
+        }
+    });
+    std::fs::write(path_to_answers, serde_json::to_string_pretty(&answers).unwrap())?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.timeout(std::time::Duration::from_secs(2))
+        .arg("debot")
+        .arg("start")
+        .arg(&addr)
+        .arg("--answers")
+        .arg(path_to_answers)
+        .arg("--debot-log")
+        .arg(path_to_log);
+    let _cmd = cmd
+        .assert()
+        .success();
+
+    let log = std::fs::read_to_string(path_to_log)?;
+    let calls: Vec<String> = log.lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .filter(|entry| entry["event"] == "call"
+            && entry["interface"] == "a56115147709ed3437efb89460b94a120b7fe94379c795d1ebb0435a847ee580")
+        .map(|entry| entry["function"].as_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(calls, vec!["getAccount", "getPublicKey"]);
+    assert!(log.contains(r#""event":"answer""#));
+    std::fs::remove_file(path_to_answers)?;
+    std::fs::remove_file(path_to_log)?;
+    Ok(())
+}
+
 #[test]
 fn test_pipechain_inputs() -> Result<(), Box<dyn std::error::Error>> {
     let path_to_pipechain = "tests/PipechainTest1.chain";
@@ -214,6 +331,69 @@ fn test_address_input() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_input_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
+    let addr = deploy_debot("AddressInput")?;
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(BIN_NAME))
+        .arg("debot")
+        .arg("start")
+        .arg(&addr)
+        .arg("--input-timeout")
+        .arg("2")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()?;
+    // keep stdin open without providing the address
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(b"y\n")?;
+    let out = child.wait_with_output()?;
+    drop(stdin);
+    assert!(!out.status.success());
+    assert!(String::from_utf8(out.stdout)?.contains("input timeout: no answer was given in 2 seconds"));
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.timeout(std::time::Duration::from_secs(10))
+        .arg("debot")
+        .arg("start")
+        .arg(&addr)
+        .write_stdin("y\n".to_string())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("input is closed"));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_interrupt() -> Result<(), Box<dyn std::error::Error>> {
+    let addr = deploy_debot("AddressInput")?;
+    let path_to_log = "tests/interrupt_debot.log_tmp";
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(BIN_NAME))
+        .arg("debot")
+        .arg("start")
+        .arg(&addr)
+        .arg("--debot-log")
+        .arg(path_to_log)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()?;
+    // keep stdin open so the browser waits for the answer
+    let stdin = child.stdin.take().unwrap();
+    sleep(Duration::from_secs(5));
+    let status = std::process::Command::new("kill")
+        .arg("-INT")
+        .arg(child.id().to_string())
+        .status()?;
+    assert!(status.success());
+    let out = child.wait_with_output()?;
+    drop(stdin);
+    assert_eq!(out.status.code(), Some(130));
+    assert!(String::from_utf8(out.stdout)?.contains("interrupted"));
+    let _ = std::fs::remove_file(path_to_log);
+    Ok(())
+}
+
 #[test]
 fn test_amount_input() -> Result<(), Box<dyn std::error::Error>> {
     let addr = deploy_debot("AmountInput")?;
@@ -312,3 +492,41 @@ fn test_pipechain_signing() -> Result<(), Box<dyn std::error::Error>> {
         .stdout(predicate::str::contains("Debot error").count(0));
     Ok(())
 }
+
+#[test]
+#[cfg(feature = "sold")]
+fn test_max_steps() -> Result<(), Box<dyn std::error::Error>> {
+    let contract_path = "tests/samples/LoopDebot";
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("compile")
+        .arg("solidity")
+        .arg(format!("{contract_path}.sol"))
+        .arg("-O")
+        .arg("tests/samples/");
+    cmd.assert()
+        .success();
+    let addr = deploy_debot("LoopDebot")?;
+
+    // every step of the DeBot calls the interface again, so only the limit stops it
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.timeout(std::time::Duration::from_secs(10))
+        .write_stdin("y\n".to_string())
+        .arg("debot")
+        .arg("start")
+        .arg(&addr)
+        .arg("--max-steps")
+        .arg("5");
+    let _cmd = cmd
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("step 5"))
+        .stdout(predicate::str::contains("step 6").not())
+        .stdout(predicate::str::contains("DeBot aborted: limit of 5 interface calls is exceeded"));
+
+    for ext in ["code", "abi.json", "debug.json", "tvc"] {
+        let _ = std::fs::remove_file(format!("{contract_path}.{ext}"));
+    }
+    let (_, _, keys) = get_debot_paths("LoopDebot");
+    let _ = std::fs::remove_file(keys);
+    Ok(())
+}
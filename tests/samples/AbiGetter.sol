@@ -0,0 +1,27 @@
+pragma ever-solidity >= 0.35.0;
+pragma AbiHeader expire;
+
+// Contract which returns its own ABI with a getter.
+contract AbiGetter {
+
+	// ABI json passed to the constructor.
+	string m_abi;
+	uint public value = 0;
+
+	constructor(string abi) public {
+		require(tvm.pubkey() != 0, 101);
+		require(msg.pubkey() == tvm.pubkey(), 102);
+		tvm.accept();
+		m_abi = abi;
+	}
+
+	function getAbi() public view returns (string abi) {
+		abi = m_abi;
+	}
+
+	function setValue(uint newValue) public {
+		require(msg.pubkey() == tvm.pubkey(), 102);
+		tvm.accept();
+		value = newValue;
+	}
+}
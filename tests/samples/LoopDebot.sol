@@ -0,0 +1,56 @@
+pragma ton-solidity >=0.43.0;
+pragma AbiHeader expire;
+pragma AbiHeader time;
+pragma AbiHeader pubkey;
+
+import "Debot.sol";
+
+interface ITerminal {
+    function print(uint32 answerId, string message) external pure;
+}
+
+library Terminal {
+
+    uint256 constant ID = 0x8796536366ee21852db56dccb60bc564598b618c865fc50c8b1ab740bba128e3;
+    int8 constant DEBOT_WC = -31;
+
+    function print(uint32 answerId, string message) public pure {
+        address addr = address.makeAddrStd(DEBOT_WC, ID);
+        ITerminal(addr).print(answerId, message);
+    }
+}
+
+/// DeBot which never stops by itself: every answer of the Terminal
+/// interface calls the same step again.
+contract LoopDebot is Debot {
+    uint32 m_counter;
+
+    function start() public override {
+        step();
+    }
+
+    function step() public {
+        m_counter++;
+        Terminal.print(tvm.functionId(step), format("step {}", m_counter));
+    }
+
+    function getRequiredInterfaces() public view override returns (uint256[] interfaces) {
+        return [ Terminal.ID ];
+    }
+
+    function getDebotInfo() public functionID(0xDEB) override view returns(
+        string name, string version, string publisher, string caption, string author,
+        address support, string hello, string language, string dabi, bytes icon
+    ) {
+        name = "Loop DeBot";
+        version = "0.1.0";
+        publisher = "TON Labs";
+        caption = "DeBot which loops forever";
+        author = "TON Labs";
+        support = address(0);
+        hello = "Hello, i am a looping DeBot.";
+        language = "en";
+        dabi = m_debotAbi.get();
+        icon = "";
+    }
+}
@@ -5,6 +5,7 @@ use std::thread::sleep;
 use std::time::{SystemTime, Duration};
 use std::fs;
 use serde_json::{json, Value};
+use ton_block::Deserializable;
 
 mod common;
 use common::{BIN_NAME, NETWORK, giver_v2, grep_address, set_config, GIVER_V2_ABI,
@@ -430,6 +431,1254 @@ fn test_call_giver() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_call_lifetime() -> Result<(), Box<dyn std::error::Error>> {
+    let lifetime = 600;
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs() as i64;
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--lifetime")
+        .arg(lifetime.to_string())
+        .output()?;
+    assert!(out.status.success());
+    let out = String::from_utf8(out.stdout)?;
+    let expire = out.lines()
+        .find_map(|line| line.strip_prefix("Expire at: "))
+        .expect("Expire at is not printed");
+    let expire = chrono::DateTime::parse_from_rfc2822(expire)?.timestamp();
+    assert!(expire >= now + lifetime && expire <= now + lifetime + 60);
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--lifetime")
+        .arg("0")
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn test_call_result_transaction_id() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    let transaction_id = result["transaction_id"].as_str().unwrap();
+    assert_eq!(transaction_id.len(), 64);
+    assert_eq!(result["exit_code"], json!(0));
+    assert!(result["output"].is_object());
+    assert!(result["fees"].is_object());
+    Ok(())
+}
+
+#[test]
+fn test_call_dry_run() -> Result<(), Box<dyn std::error::Error>> {
+    let last_trans_lt = || -> Result<String, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        let out = cmd.arg("-j")
+            .arg("account")
+            .arg(GIVER_V2_ADDR)
+            .output()?;
+        let account: Value = serde_json::from_slice(&out.stdout)?;
+        Ok(account["last_trans_lt"].as_str().unwrap().to_owned())
+    };
+    let lt_before = last_trans_lt()?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("--dry-run")
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    assert!(result["fees"]["gas_fee"].is_number() || result["fees"]["gas_fee"].is_string());
+    assert!(result["output"].is_object());
+    assert!(result["transaction_id"].is_null());
+
+    sleep(Duration::new(1, 0));
+    assert_eq!(lt_before, last_trans_lt()?);
+    Ok(())
+}
+
+#[test]
+fn test_call_fixed_time() -> Result<(), Box<dyn std::error::Error>> {
+    let time = now_ms().to_string();
+    let message_id = || -> Result<String, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        let out = cmd.arg("--dry-run")
+            .arg("call")
+            .arg("--abi")
+            .arg(GIVER_V2_ABI)
+            .arg(GIVER_V2_ADDR)
+            .arg("--sign")
+            .arg(GIVER_V2_KEY)
+            .arg("sendTransaction")
+            .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+            .arg("--time")
+            .arg(&time)
+            .output()?;
+        assert!(out.status.success());
+        let out = String::from_utf8(out.stdout)?;
+        Ok(out.lines()
+            .find_map(|line| line.strip_prefix("MessageId: "))
+            .expect("MessageId is not printed")
+            .to_owned())
+    };
+    let first = message_id()?;
+    sleep(Duration::new(1, 0));
+    assert_eq!(first, message_id()?);
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("sendTransaction")
+        .arg("{}")
+        .arg("--time")
+        .arg("1700000000")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("is not a timestamp in milliseconds"));
+    Ok(())
+}
+
+#[test]
+fn test_emulate_uninit_account() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "emulate_uninit.key";
+    let address = generate_key_and_address(key_path, SAFEMSIG_TVC, SAFEMSIG_ABI)?;
+    giver_v2(&address);
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--dry-run")
+        .arg("call")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--sign")
+        .arg(key_path)
+        .arg(&address)
+        .arg("getParameters")
+        .arg("{}")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("is not initialized"));
+
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_local_run_output() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "local_run_output.key";
+    let config_path = "local_run_output.conf";
+    let address = deploy_safe_msig(key_path)?;
+    set_config(&["--url", "--local_run"], &[&*NETWORK, "true"], Some(config_path))?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("--config")
+        .arg(config_path)
+        .arg("call")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--sign")
+        .arg(SAFEMSIG_SEED)
+        .arg(&address)
+        .arg("submitTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false,"allBalance":false,"payload":""}}"#, address))
+        .output()?;
+    assert!(out.status.success());
+    let out = String::from_utf8(out.stdout)?;
+    let start = out.find("Local run result: ").expect("local run result is not printed") + "Local run result: ".len();
+    let end = out.find("Executing onchain.").expect("local run is not finished");
+    let preview: Value = serde_json::from_str(out[start..end].trim())?;
+    let onchain = out.rfind("Result: ").expect("result is not printed") + "Result: ".len();
+    let onchain: Value = serde_json::from_str(out[onchain..].trim())?;
+    assert_eq!(preview, json!({"transId": "0"}));
+    assert_eq!(preview, onchain);
+
+    fs::remove_file(key_path)?;
+    fs::remove_file(config_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_call_with_phrase() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "call_with_phrase.key";
+    let phrase_path = "call_with_phrase.phrase";
+    let address = deploy_safe_msig(key_path)?;
+    // the phrase file may contain extra whitespace
+    fs::write(phrase_path, format!("{}\n", SAFEMSIG_SEED))?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("call")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--phrase-file")
+        .arg(phrase_path)
+        .arg(&address)
+        .arg("submitTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false,"allBalance":false,"payload":""}}"#, address))
+        .output()?;
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout)?;
+    assert!(stdout.contains("Succeeded."));
+    assert!(!stdout.contains(SAFEMSIG_SEED));
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--phrase")
+        .arg(SAFEMSIG_SEED)
+        .arg(&address)
+        .arg("submitTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false,"allBalance":false,"payload":""}}"#, address))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Succeeded."))
+        .stdout(predicate::str::contains(SAFEMSIG_SEED).not());
+
+    fs::remove_file(key_path)?;
+    fs::remove_file(phrase_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_run_executor() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "run_executor.key";
+    let address = deploy_safe_msig(key_path)?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("run")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--executor")
+        .arg("--sign")
+        .arg(SAFEMSIG_SEED)
+        .arg(&address)
+        .arg("submitTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false,"allBalance":false,"payload":""}}"#, address))
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    assert_eq!(result, json!({"transId": "0"}));
+
+    // the message was executed locally only, so there are no pending transactions
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("run")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg(&address)
+        .arg("getTransactions")
+        .arg("{}")
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    assert_eq!(result, json!({"transactions": []}));
+
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_call_batch() -> Result<(), Box<dyn std::error::Error>> {
+    let batch_path = "call_batch.json_tmp";
+    let call = json!({
+        "address": GIVER_V2_ADDR,
+        "method": "sendTransaction",
+        "params": {"dest": GIVER_V2_ADDR, "value": 1000000000, "bounce": false},
+        "keys": GIVER_V2_KEY,
+    });
+    fs::write(batch_path, serde_json::to_string(&json!([call, call]))?)?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg("--batch")
+        .arg(batch_path)
+        .output()?;
+    assert!(out.status.success());
+    let results: Value = serde_json::from_slice(&out.stdout)?;
+    let results = results.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    for result in results {
+        assert_eq!(result["method"], "sendTransaction");
+        assert!(result["result"]["transaction_id"].is_string());
+        assert!(result.get("error").is_none());
+    }
+
+    let bad_call = json!({"address": GIVER_V2_ADDR, "method": "unknownMethod", "params": {}});
+    fs::write(batch_path, serde_json::to_string(&json!([bad_call, call]))?)?;
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg("--batch")
+        .arg(batch_path)
+        .arg("--stop-on-error")
+        .output()?;
+    assert!(!out.status.success());
+    let stdout = String::from_utf8(out.stdout)?;
+    let results: Value = serde_json::Deserializer::from_str(&stdout)
+        .into_iter::<Value>()
+        .next()
+        .unwrap()?;
+    assert_eq!(results.as_array().unwrap().len(), 1);
+    assert!(results[0]["error"].is_string() || results[0]["error"].is_object());
+
+    fs::remove_file(batch_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_verify_key() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "verify_key.key";
+    let address = deploy_safe_msig(key_path)?;
+    let params = format!(r#"{{"dest":"{}","value":1000000000,"bounce":false,"allBalance":false,"payload":""}}"#, address);
+
+    // contract is owned by SAFEMSIG_SEED keys, deploy keys do not match
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--sign")
+        .arg(key_path)
+        .arg(&address)
+        .arg("submitTransaction")
+        .arg(&params)
+        .arg("--verify-key")
+        .arg("getCustodians")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("does not match the key returned by \"getCustodians\""))
+        .stdout(predicate::str::contains("Processing").not());
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--sign")
+        .arg(SAFEMSIG_SEED)
+        .arg(&address)
+        .arg("submitTransaction")
+        .arg(&params)
+        .arg("--verify-key")
+        .arg("getCustodians")
+        .assert()
+        .success();
+
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_call_raw_transaction() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--raw")
+        .output()?;
+    assert!(out.status.success());
+    let transaction: Value = serde_json::from_slice(&out.stdout)?;
+    assert!(transaction["id"].is_string());
+    assert!(transaction.get("output").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_deploy_prepay() -> Result<(), Box<dyn std::error::Error>> {
+    let config_path = "test_deploy_prepay.config";
+    let wallet_tvc = "tests/samples/wallet.tvc";
+    let wallet_abi = "tests/samples/wallet.abi.json";
+    let key_path = "test_deploy_prepay.key";
+
+    set_config(
+        &["--url", "--giver_address", "--giver_abi", "--giver_keys"],
+        &[&*NETWORK, GIVER_V2_ADDR, GIVER_V2_ABI, GIVER_V2_KEY],
+        Some(config_path)
+    )?;
+    let address = generate_key_and_address(key_path, wallet_tvc, wallet_abi)?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--config")
+        .arg(config_path)
+        .arg("deploy")
+        .arg(wallet_tvc)
+        .arg("{}")
+        .arg("--abi")
+        .arg(wallet_abi)
+        .arg("--sign")
+        .arg(key_path)
+        .arg("--prepay")
+        .arg("10T");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Prepaying 10000000000 nanotokens"))
+        .stdout(predicate::str::contains("Transaction succeeded."))
+        .stdout(predicate::str::contains(address));
+
+    fs::remove_file(config_path)?;
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_deploy_dry_run() -> Result<(), Box<dyn std::error::Error>> {
+    let config_path = "test_deploy_dry_run.config";
+    let wallet_tvc = "tests/samples/wallet.tvc";
+    let wallet_abi = "tests/samples/wallet.abi.json";
+    let key_path = "test_deploy_dry_run.key";
+
+    set_config(
+        &["--url", "--giver_address", "--giver_abi", "--giver_keys"],
+        &[&*NETWORK, GIVER_V2_ADDR, GIVER_V2_ABI, GIVER_V2_KEY],
+        Some(config_path)
+    )?;
+    let address = generate_key_and_address(key_path, wallet_tvc, wallet_abi)?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("--config")
+        .arg(config_path)
+        .arg("--dry-run")
+        .arg("deploy")
+        .arg(wallet_tvc)
+        .arg("{}")
+        .arg("--abi")
+        .arg(wallet_abi)
+        .arg("--sign")
+        .arg(key_path)
+        .arg("--prepay")
+        .arg("10000000000")
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    assert!(result["fees"].is_object());
+    assert!(result["message_id"].is_string());
+
+    // neither the prepay nor the deploy message is sent
+    sleep(Duration::new(1, 0));
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--config")
+        .arg(config_path)
+        .arg("account")
+        .arg(&address);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Account not found"));
+
+    fs::remove_file(config_path)?;
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_fee_real_balance() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "fee_real_balance_test.key";
+    let address = generate_key_and_address(key_path, SAFEMSIG_TVC, SAFEMSIG_ABI)?;
+    let keys: Value = serde_json::from_str(&fs::read_to_string(key_path)?)?;
+    let owner = keys["public"].as_str().unwrap();
+    giver_v2(&address);
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("deploy")
+        .arg(SAFEMSIG_TVC)
+        .arg(format!(r#"{{"owners":["0x{}"],"reqConfirms":1}}"#, owner))
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--sign")
+        .arg(key_path)
+        .assert()
+        .success();
+
+    let fee_call = |value: &str| -> Result<Value, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        let out = cmd.arg("-j")
+            .arg("fee")
+            .arg("call")
+            .arg("--abi")
+            .arg(SAFEMSIG_ABI)
+            .arg(&address)
+            .arg("--sign")
+            .arg(key_path)
+            .arg("sendTransaction")
+            .arg(format!(r#"{{"dest":"{}","value":{},"bounce":false,"flags":0,"payload":""}}"#, GIVER_V2_ADDR, value))
+            .arg("--real-balance")
+            .output()?;
+        assert!(out.status.success());
+        Ok(serde_json::from_slice(&out.stdout)?)
+    };
+
+    let result = fee_call("1000000000")?;
+    assert_eq!(result["sufficient_funds"], json!(true));
+    assert!(result["total_account_fees"].is_string());
+
+    let result = fee_call("1000000000000000000")?;
+    assert_eq!(result["sufficient_funds"], json!(false));
+    assert!(result["total_account_fees"].is_string());
+    assert!(!result["error"].is_null());
+
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_message_ttl() -> Result<(), Box<dyn std::error::Error>> {
+    let msg_path = "tests/message_ttl.msg";
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("message")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--sign")
+        .arg(SAFEMSIG_SEED)
+        .arg(SAFEMSIG_ADDR)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":true,"flags":1,"payload":""}}"#, SAFEMSIG_ADDR))
+        .arg("--time")
+        .arg("1700000000000")
+        .arg("--ttl")
+        .arg("3600")
+        .arg("--output")
+        .arg(msg_path)
+        .assert()
+        .success();
+    let message: Value = serde_json::from_str(&fs::read_to_string(msg_path)?)?;
+    assert_eq!(message["msg"]["expire"], json!(1700000000 + 3600));
+    fs::remove_file(msg_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_message_json_output() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("message")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg("--sign")
+        .arg(SAFEMSIG_SEED)
+        .arg(SAFEMSIG_ADDR)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":true,"flags":1,"payload":""}}"#, SAFEMSIG_ADDR))
+        .output()?;
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout)?;
+    assert_eq!(stdout.trim().lines().count(), 1);
+    let message: Value = serde_json::from_str(&stdout)?;
+    assert!(message["MessageId"].is_string());
+    assert!(message["Expire at"].is_string());
+    let message = message["Message"].as_str().unwrap().to_owned();
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("send")
+        .arg("--offline")
+        .arg("--abi")
+        .arg(SAFEMSIG_ABI)
+        .arg(&message)
+        .output()?;
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout)?;
+    assert_eq!(stdout.trim().lines().count(), 1);
+    let decoded: Value = serde_json::from_str(&stdout)?;
+    assert_eq!(decoded["Method"], json!("sendTransaction"));
+    assert_eq!(decoded["Parameters"]["dest"], json!(SAFEMSIG_ADDR));
+    Ok(())
+}
+
+#[test]
+fn test_no_color_when_piped() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Succeeded."))
+        .stdout(predicate::str::contains("\x1b[").not());
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("notExistingMethod")
+        .arg("{}")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Error: "))
+        .stdout(predicate::str::contains("\x1b[").not());
+    Ok(())
+}
+
+#[test]
+fn test_call_once() -> Result<(), Box<dyn std::error::Error>> {
+    let config_path = "call_once_test.conf";
+    let ledger_path = "call_once_test.once.json";
+    set_config(&["--url"], &[&*NETWORK], Some(config_path))?;
+
+    let call = || -> Result<Value, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        let out = cmd.arg("-j")
+            .arg("--config")
+            .arg(config_path)
+            .arg("call")
+            .arg("--abi")
+            .arg(GIVER_V2_ABI)
+            .arg(GIVER_V2_ADDR)
+            .arg("--sign")
+            .arg(GIVER_V2_KEY)
+            .arg("sendTransaction")
+            .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+            .arg("--once")
+            .arg("transfer-1")
+            .output()?;
+        assert!(out.status.success());
+        Ok(serde_json::from_slice(&out.stdout)?)
+    };
+    let first = call()?;
+    assert!(first["transaction_id"].is_string());
+    assert!(first["output"]["skipped"].is_null());
+
+    let second = call()?;
+    assert_eq!(second["output"]["skipped"], json!(true));
+    assert_eq!(second["transaction_id"], first["transaction_id"]);
+
+    let ledger: Value = serde_json::from_str(&fs::read_to_string(ledger_path)?)?;
+    assert_eq!(ledger["transfer-1"]["transaction_id"], first["transaction_id"]);
+    assert!(ledger["transfer-1"]["message_id"].is_string());
+
+    // the message was sent but the transaction was not recorded
+    let pending = |expire: u64| -> Result<(), Box<dyn std::error::Error>> {
+        let mut ledger = ledger.clone();
+        ledger["transfer-1"]["transaction_id"] = Value::Null;
+        ledger["transfer-1"]["message_id"] = json!("0".repeat(64));
+        ledger["transfer-1"]["expire"] = json!(expire);
+        fs::write(ledger_path, serde_json::to_string(&ledger)?)?;
+        Ok(())
+    };
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+    pending(now + 600)?;
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--config")
+        .arg(config_path)
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--once")
+        .arg("transfer-1")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("its transaction is not found yet"));
+
+    // the expired message without a transaction is sent again
+    pending(now - 600)?;
+    let third = call()?;
+    assert!(third["transaction_id"].is_string());
+    assert_ne!(third["transaction_id"], first["transaction_id"]);
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--config")
+        .arg(config_path)
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":2000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--once")
+        .arg("transfer-1")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("was already used for another call"));
+
+    fs::remove_file(ledger_path)?;
+    fs::remove_file(config_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_call_since_lt() -> Result<(), Box<dyn std::error::Error>> {
+    let call = |since_lt: Option<&str>| -> Result<Value, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        cmd.arg("-j")
+            .arg("call")
+            .arg("--abi")
+            .arg(GIVER_V2_ABI)
+            .arg(GIVER_V2_ADDR)
+            .arg("--sign")
+            .arg(GIVER_V2_KEY)
+            .arg("sendTransaction")
+            .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+            .arg("--raw");
+        if let Some(lt) = since_lt {
+            cmd.arg("--since-lt").arg(lt);
+        }
+        let out = cmd.output()?;
+        assert!(out.status.success());
+        Ok(serde_json::from_slice(&out.stdout)?)
+    };
+    // the transfer to itself produces another transaction on the same account
+    let first = call(None)?;
+    let first_lt = first["lt"].as_str().unwrap().to_owned();
+    let second = call(Some(&first_lt))?;
+    assert_ne!(second["id"], first["id"]);
+    let lt = |tr: &Value| u64::from_str_radix(tr["lt"].as_str().unwrap().trim_start_matches("0x"), 16).unwrap();
+    assert!(lt(&second) > lt(&first));
+    let third = call(Some("current"))?;
+    assert!(lt(&third) > lt(&second));
+
+    // the transaction of the call is older than the filter, so it is not matched
+    let config_path = "call_since_lt_test.conf";
+    set_config(&["--url", "--timeout"], &[&*NETWORK, "5000"], Some(config_path))?;
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--config")
+        .arg(config_path)
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--since-lt")
+        .arg(format!("0x{:x}", u64::MAX >> 1))
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("failed to wait for the transaction"));
+    fs::remove_file(config_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_json_output_file() -> Result<(), Box<dyn std::error::Error>> {
+    let out_path = "test_json_output_file.json";
+    let call = |is_json: bool| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        if is_json {
+            cmd.arg("-j");
+        }
+        let out = cmd.arg("--json-output-file")
+            .arg(out_path)
+            .arg("call")
+            .arg("--abi")
+            .arg(GIVER_V2_ABI)
+            .arg(GIVER_V2_ADDR)
+            .arg("--sign")
+            .arg(GIVER_V2_KEY)
+            .arg("sendTransaction")
+            .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+            .output()?;
+        assert!(out.status.success());
+        Ok(out.stdout)
+    };
+
+    let stdout = String::from_utf8(call(false)?)?;
+    assert!(stdout.contains("Succeeded."));
+    assert!(stdout.contains(&format!("Result saved to file {}", out_path)));
+    assert!(!stdout.contains("Result: "));
+    let saved: Value = serde_json::from_str(&fs::read_to_string(out_path)?)?;
+    assert!(saved["transaction_id"].is_string());
+    assert!(saved["output"].is_object());
+
+    // with --json the file mirrors stdout
+    let stdout: Value = serde_json::from_slice(&call(true)?)?;
+    let saved: Value = serde_json::from_str(&fs::read_to_string(out_path)?)?;
+    assert_eq!(stdout, saved);
+
+    fs::remove_file(out_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_call_wait_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--wait-timeout")
+        .arg("0")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("timeout of 0 seconds elapsed"))
+        .stdout(predicate::str::is_match("transaction of message [0-9a-f]{64}")?);
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("sendTransaction")
+        .arg("{}")
+        .arg("--wait-timeout")
+        .arg("4294968")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("wait timeout 4294968 is too large, the maximum is 4294967 seconds"));
+    Ok(())
+}
+
+#[test]
+fn test_call_fail_on_empty_output() -> Result<(), Box<dyn std::error::Error>> {
+    // sendTransaction returns nothing
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--fail-on-empty-output")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(r#"function "sendTransaction" returned empty output"#));
+    Ok(())
+}
+
+#[test]
+fn test_account_snapshot() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = "test_account_snapshot";
+    let _ = fs::remove_dir_all(dir);
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--account-snapshot")
+        .arg(dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Account state saved to"));
+
+    let before = fs::read(format!("{}/before.boc", dir))?;
+    let after = fs::read(format!("{}/after.boc", dir))?;
+    fs::remove_dir_all(dir)?;
+    assert!(!before.is_empty());
+    assert_ne!(before, after);
+
+    let config_path = "account_snapshot_test.conf";
+    set_config(&["--url", "--async_call"], &[&*NETWORK, "true"], Some(config_path))?;
+    let call = |config: Option<&str>| -> Result<Command, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        if let Some(config) = config {
+            cmd.arg("--config").arg(config);
+        }
+        cmd.arg("call")
+            .arg("--abi")
+            .arg(GIVER_V2_ABI)
+            .arg(GIVER_V2_ADDR)
+            .arg("--sign")
+            .arg(GIVER_V2_KEY)
+            .arg("sendTransaction")
+            .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+            .arg("--account-snapshot")
+            .arg(dir);
+        Ok(cmd)
+    };
+
+    // the transaction of the async call is not awaited, so there is no state after it
+    call(Some(config_path))?
+        .assert()
+        .success();
+    fs::remove_file(config_path)?;
+    assert!(fs::metadata(format!("{}/before.boc", dir)).is_ok());
+    assert!(fs::metadata(format!("{}/after.boc", dir)).is_err());
+    fs::remove_dir_all(dir)?;
+
+    // the snapshot can't be saved, but the call is made
+    fs::write(dir, "")?;
+    call(None)?.assert()
+        .success()
+        .stdout(predicate::str::contains("Failed to save the account snapshot"))
+        .stdout(predicate::str::contains("Succeeded"));
+    fs::remove_file(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_call_wrong_key() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg("tests/samples/fakeDepool.key")
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("the provided signing key does not match the contract's required public key"));
+    Ok(())
+}
+
+#[test]
+fn test_call_max_fee() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--max-fee")
+        .arg("1")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("estimated fee"))
+        .stdout(predicate::str::contains("exceeds --max-fee 1 "));
+    Ok(())
+}
+
+#[test]
+fn test_call_count() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":"100000000{{i}}","bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--count")
+        .arg("3")
+        .arg("--concurrency")
+        .arg("3")
+        .output()?;
+    assert!(out.status.success());
+    let stats: Value = serde_json::from_slice(&out.stdout)?;
+    assert_eq!(stats["count"], 3);
+    assert_eq!(stats["failed"], 0);
+    assert_eq!(stats["transactions"].as_array().unwrap().len(), 3);
+    assert!(stats["min_ms"].as_u64().unwrap() <= stats["avg_ms"].as_u64().unwrap());
+    assert!(stats["avg_ms"].as_u64().unwrap() <= stats["max_ms"].as_u64().unwrap());
+    Ok(())
+}
+
+#[test]
+fn test_log_file() -> Result<(), Box<dyn std::error::Error>> {
+    let log_path = "test_log_file.log";
+    let _ = fs::remove_file(log_path);
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--log-file")
+        .arg(log_path)
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("notExistingMethod")
+        .arg("{}")
+        .assert()
+        .failure();
+
+    let log = fs::read_to_string(log_path)?;
+    assert!(log.contains("running command \"call\""));
+    assert!(log.lines().any(|line| line.contains("ERROR") && line.contains("notExistingMethod")));
+    fs::remove_file(log_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_call_raw_body() -> Result<(), Box<dyn std::error::Error>> {
+    let msg_path = "test_call_raw_body.msg";
+    let body_path = "test_call_raw_body.boc";
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .arg("--output-message")
+        .arg(msg_path)
+        .assert()
+        .success();
+
+    // the body of the signed message is sent as is, without ABI
+    let msg = ton_block::Message::construct_from_base64(fs::read_to_string(msg_path)?.trim())
+        .map_err(|e| e.to_string())?;
+    let body = msg.body().unwrap().into_cell();
+    fs::write(body_path, ton_types::write_boc(&body).map_err(|e| e.to_string())?)?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("call")
+        .arg(GIVER_V2_ADDR)
+        .arg("--raw-body")
+        .arg(body_path)
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    assert!(result["transaction_id"].is_string());
+
+    fs::remove_file(msg_path)?;
+    fs::remove_file(body_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_replay_message() -> Result<(), Box<dyn std::error::Error>> {
+    let tvc_path = "replay_message.tvc";
+    let account_path = "replay_message.boc";
+    let msg_path = "replay_message.msg";
+    let abi = "tests/samples/test.abi.json";
+    let keys = "tests/samples/test.keys.json";
+    fs::copy("tests/samples/test.tvc", tvc_path)?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("genaddr")
+        .arg(tvc_path)
+        .arg("--abi")
+        .arg(abi)
+        .arg("--setkey")
+        .arg(keys)
+        .output()?;
+    let address = grep_address(&out.stdout);
+
+    // the account state is deployed locally, everything is offline
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("test")
+        .arg("deploy")
+        .arg(tvc_path)
+        .arg("--abi")
+        .arg(abi)
+        .arg("--keys")
+        .arg(keys)
+        .arg("--external")
+        .arg("--address")
+        .arg(&address)
+        .arg("--initial_balance")
+        .arg("10000000000")
+        .assert()
+        .success();
+
+    let replay = |sign: &str| -> Result<std::process::Output, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        cmd.arg("message")
+            .arg(&address)
+            .arg("test")
+            .arg(r#"{"ctype":1,"data":""}"#)
+            .arg("--abi")
+            .arg(abi)
+            .arg("--sign")
+            .arg(sign)
+            .arg("--raw")
+            .arg("--output")
+            .arg(msg_path)
+            .assert()
+            .success();
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        Ok(cmd.arg("-j")
+            .arg("replay")
+            .arg("message")
+            .arg(account_path)
+            .arg(msg_path)
+            .output()?)
+    };
+
+    let out = replay(keys)?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    assert_eq!(result["exit_code"], json!(0));
+    assert!(result["transaction"]["id"].is_string());
+    assert!(result["account"].is_string());
+
+    // the contract rejects a message signed by a foreign key
+    let out = replay(GIVER_V2_KEY)?;
+    assert!(!out.status.success());
+    let output = format!("{}{}", String::from_utf8(out.stdout)?, String::from_utf8(out.stderr)?);
+    assert!(output.contains(r#""exit_code":102"#));
+
+    fs::remove_file(tvc_path)?;
+    fs::remove_file(account_path)?;
+    fs::remove_file(msg_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_convert() -> Result<(), Box<dyn std::error::Error>> {
+    let convert = |unit: &str, amount: &str| -> Result<String, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        let out = cmd.arg("-j")
+            .arg("convert")
+            .arg(unit)
+            .arg(amount)
+            .output()?;
+        assert!(out.status.success());
+        let result: Value = serde_json::from_slice(&out.stdout)?;
+        Ok(result["value"].as_str().unwrap().to_owned())
+    };
+    assert_eq!(convert("tokens", "1.5")?, "1500000000");
+    assert_eq!(convert("nano", "1500000000")?, "1.500000000");
+    assert_eq!(convert("tokens", &convert("nano", "1500000000")?)?, "1500000000");
+    assert_eq!(convert("nano", "123456789012345678901234567890")?, "123456789012345678901.234567890");
+    assert_eq!(convert("tokens", "123456789012345678901.23456789")?, "123456789012345678901234567890");
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("convert")
+        .arg("tokens")
+        .arg("1.5")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1500000000 nanotokens"));
+
+    // json output follows --pretty and --compact like other commands
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("-j")
+        .arg("convert")
+        .arg("tokens")
+        .arg("1.5")
+        .assert()
+        .success()
+        .stdout(predicate::eq("{\"value\":\"1500000000\"}\n"));
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("-j")
+        .arg("--pretty")
+        .arg("convert")
+        .arg("tokens")
+        .arg("1.5")
+        .assert()
+        .success()
+        .stdout(predicate::eq("{\n  \"value\": \"1500000000\"\n}\n"));
+    Ok(())
+}
+
+#[test]
+fn test_json_error() -> Result<(), Box<dyn std::error::Error>> {
+    let config_path = "json_error.conf";
+    set_config(&["--url", "--is_json"], &[&*NETWORK, "true"], Some(config_path))?;
+
+    for json_flag in [true, false] {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        cmd.arg("--config")
+            .arg(config_path);
+        if json_flag {
+            cmd.arg("-j");
+        }
+        let out = cmd.arg("call")
+            .arg("--abi")
+            .arg(GIVER_V2_ABI)
+            .arg(GIVER_V2_ADDR)
+            .arg("unknownMethod")
+            .arg("{}")
+            .output()?;
+        assert!(!out.status.success());
+        let result: Value = serde_json::from_slice(&out.stdout)?;
+        assert!(result.get("Error").is_some());
+    }
+
+    fs::remove_file(config_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_call_confirmation() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("account")
+        .arg(GIVER_V2_ADDR)
+        .output()?;
+    let lt_before = serde_json::from_slice::<Value>(&out.stdout)?["last_trans_lt"].clone();
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.write_stdin("n\n")
+        .arg("call")
+        .arg("--confirm")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Send the message?"))
+        .stdout(predicate::str::contains("aborted by user"));
+
+    sleep(Duration::new(1, 0));
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("account")
+        .arg(GIVER_V2_ADDR)
+        .output()?;
+    let lt_after = serde_json::from_slice::<Value>(&out.stdout)?["last_trans_lt"].clone();
+    assert_eq!(lt_before, lt_after);
+    Ok(())
+}
+
 #[test]
 fn test_fee() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin(BIN_NAME)?;
@@ -470,6 +1719,23 @@ fn test_fee() -> Result<(), Box<dyn std::error::Error>> {
         .stdout(predicate::str::contains(r#"  "total_output":"#))
         .stdout(predicate::str::contains(r#"Succeeded."#));
 
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("fee")
+        .arg("call")
+        .arg("--abi")
+        .arg(GIVER_V2_ABI)
+        .arg(GIVER_V2_ADDR)
+        .arg("--sign")
+        .arg(GIVER_V2_KEY)
+        .arg("sendTransaction")
+        .arg(format!(r#"{{"dest":"{}","value":100000000000,"bounce":false}}"#, GIVER_V2_ADDR))
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    assert!(result["gas_used"].as_u64().unwrap() > 0);
+    assert!(result["vm_steps"].as_u64().unwrap() > 0);
+
     let key_path = "deploy_test_fee.key";
     let _ = generate_phrase_and_key(key_path)?;
 
@@ -697,7 +1963,7 @@ fn test_deploy() -> Result<(), Box<dyn std::error::Error>> {
         .arg(&addr)
         .assert()
         .success()
-        .stdout(predicate::str::contains("\"balance\": \""));
+        .stdout(predicate::str::contains("\"balance\":\""));
 
     let mut cmd = Command::cargo_bin(BIN_NAME)?;
     cmd.arg("account")
@@ -712,7 +1978,7 @@ fn test_deploy() -> Result<(), Box<dyn std::error::Error>> {
         .arg(&addr)
         .assert()
         .success()
-        .stdout(predicate::str::contains("\"balance\": \""));
+        .stdout(predicate::str::contains("\"balance\":\""));
 
     let mut cmd = Command::cargo_bin(BIN_NAME)?;
     cmd.arg("deploy")
@@ -1257,9 +2523,9 @@ fn test_decode_msg() -> Result<(), Box<dyn std::error::Error>> {
         .arg("tests/samples/wallet.abi.json")
         .assert()
         .success()
-        .stdout(predicate::str::contains(r#"Init": {"#))
-        .stdout(predicate::str::contains(r#""StateInit": {"#))
-        .stdout(predicate::str::contains(r#""data": "te6ccgEBAgEAKAABAcABAEPQAZ6jzp01QGBqmSPd8SBPy4vE1I8GSisk4ihjvGiRJP7g""#));
+        .stdout(predicate::str::contains(r#"Init":{"#))
+        .stdout(predicate::str::contains(r#""StateInit":{"#))
+        .stdout(predicate::str::contains(r#""data":"te6ccgEBAgEAKAABAcABAEPQAZ6jzp01QGBqmSPd8SBPy4vE1I8GSisk4ihjvGiRJP7g""#));
 
     let mut cmd = Command::cargo_bin(BIN_NAME)?;
     cmd.arg("--json")
@@ -1270,10 +2536,10 @@ fn test_decode_msg() -> Result<(), Box<dyn std::error::Error>> {
         .arg("tests/samples/accumulator.abi.json")
         .assert()
         .success()
-        .stdout(predicate::str::contains(r#"Type": "external inbound message"#))
-        .stdout(predicate::str::contains(r#""destination": "0:ff5ccc94e0f9e71720a21ddccfd520542cf7766ed0d33ba42e012c13d27a5f8b"#))
-        .stdout(predicate::str::contains(r#""Body": "te6ccgEBAQEAcwAA4dFBEQoq0xgjjctNZukvkYBlQyFLMl8vHJtswO29MAkkFQmzGSxewgSp+iHDxxTEjqG7hAcLAhBvpP3Es+9KoAOAAADBi8crwjGMO0wS99kEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAADLA""#))
-        .stdout(predicate::str::contains(r#""value": "0x0000000000000000000000000000000000000000000000000000000000000065""#));
+        .stdout(predicate::str::contains(r#"Type":"external inbound message"#))
+        .stdout(predicate::str::contains(r#""destination":"0:ff5ccc94e0f9e71720a21ddccfd520542cf7766ed0d33ba42e012c13d27a5f8b"#))
+        .stdout(predicate::str::contains(r#""Body":"te6ccgEBAQEAcwAA4dFBEQoq0xgjjctNZukvkYBlQyFLMl8vHJtswO29MAkkFQmzGSxewgSp+iHDxxTEjqG7hAcLAhBvpP3Es+9KoAOAAADBi8crwjGMO0wS99kEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAADLA""#))
+        .stdout(predicate::str::contains(r#""value":"0x0000000000000000000000000000000000000000000000000000000000000065""#));
 
     let mut cmd = Command::cargo_bin(BIN_NAME)?;
     cmd.arg("--json")
@@ -1284,10 +2550,10 @@ fn test_decode_msg() -> Result<(), Box<dyn std::error::Error>> {
         .arg("tests/samples/accumulator.abi.json")
         .assert()
         .success()
-        .stdout(predicate::str::contains(r#"Type": "external inbound message"#))
-        .stdout(predicate::str::contains(r#""destination": "0:ff5ccc94e0f9e71720a21ddccfd520542cf7766ed0d33ba42e012c13d27a5f8b"#))
-        .stdout(predicate::str::contains(r#""Body": "te6ccgEBAQEAcwAA4dFBEQoq0xgjjctNZukvkYBlQyFLMl8vHJtswO29MAkkFQmzGSxewgSp+iHDxxTEjqG7hAcLAhBvpP3Es+9KoAOAAADBi8crwjGMO0wS99kEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAADLA""#))
-        .stdout(predicate::str::contains(r#""value": "0x0000000000000000000000000000000000000000000000000000000000000065""#));
+        .stdout(predicate::str::contains(r#"Type":"external inbound message"#))
+        .stdout(predicate::str::contains(r#""destination":"0:ff5ccc94e0f9e71720a21ddccfd520542cf7766ed0d33ba42e012c13d27a5f8b"#))
+        .stdout(predicate::str::contains(r#""Body":"te6ccgEBAQEAcwAA4dFBEQoq0xgjjctNZukvkYBlQyFLMl8vHJtswO29MAkkFQmzGSxewgSp+iHDxxTEjqG7hAcLAhBvpP3Es+9KoAOAAADBi8crwjGMO0wS99kEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAADLA""#))
+        .stdout(predicate::str::contains(r#""value":"0x0000000000000000000000000000000000000000000000000000000000000065""#));
 
     let mut cmd = Command::cargo_bin(BIN_NAME)?;
     let output = cmd.arg("call")
@@ -1470,6 +2736,257 @@ fn test_decode_body_constructor_for_minus_workchain() -> Result<(), Box<dyn std:
     Ok(())
 }
 
+#[test]
+fn test_call_internal_emulation() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "internal_emulation_test.key";
+    let depool_addr = deploy_depool(key_path)?;
+    let sender = "0:1111111111111111111111111111111111111111111111111111111111111111";
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("call")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("setVestingDonor")
+        .arg(format!(r#"{{"donor":"{}"}}"#, GIVER_V2_ADDR))
+        .arg("--src")
+        .arg(sender)
+        .arg("--value")
+        .arg("2ever")
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    assert_eq!(result["exit_code"], json!(0));
+    // the contract answers to msg.sender
+    let out_messages = result["output"]["out_messages"].as_array().unwrap();
+    assert_eq!(out_messages.len(), 1);
+    assert_eq!(out_messages[0]["dst"], json!(sender));
+    assert_eq!(out_messages[0]["value"], json!("123456789"));
+    // the method doesn't emit events
+    assert!(result.get("events").is_none());
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("-j")
+        .arg("call")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("ticktock")
+        .arg("{}")
+        .arg("--src")
+        .arg(sender)
+        .arg("--value")
+        .arg("1ever")
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    assert_eq!(result["events"], json!([{
+        "name": "StakeSigningRequested",
+        "value": {
+            "electionId": "1",
+            "proxy": "0:0000000000000000000000000000000000000000000000000000000000000002",
+        },
+    }]));
+
+    // emulation doesn't change the account state
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("run")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("getData")
+        .arg("{}")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(GIVER_V2_ADDR).not());
+
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_revert_exit_code() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "revert_exit_code_test.key";
+    let depool_addr = deploy_depool(key_path)?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("error")
+        .arg(r#"{"code":101}"#);
+    cmd.assert()
+        .code(101);
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("-j")
+        .arg("call")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("error")
+        .arg(r#"{"code":101}"#);
+    cmd.assert()
+        .code(101);
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("outOfGas")
+        .arg(r#"{}"#);
+    cmd.assert()
+        .code(1);
+
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_call_error_map() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "call_error_map_test.key";
+    let errors_path = "call_error_map_test.json";
+    let depool_addr = deploy_depool(key_path)?;
+    fs::write(errors_path, r#"{"101": "Custom revert message"}"#)?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("error")
+        .arg(r#"{"code":101}"#)
+        .arg("--errors")
+        .arg(errors_path);
+    cmd.assert()
+        .code(101)
+        .stdout(predicate::str::contains("Contract exit code 101: Custom revert message"));
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("-j")
+        .arg("call")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("error")
+        .arg(r#"{"code":102}"#)
+        .arg("--errors")
+        .arg(errors_path);
+    cmd.assert()
+        .code(102)
+        .stdout(predicate::str::contains("Custom revert message").not());
+
+    fs::remove_file(errors_path)?;
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_debug_fail_trace_output() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "debug_trace_output_test.key";
+    let config_path = "debug_trace_output_test.conf";
+    let depool_addr = deploy_depool(key_path)?;
+    set_config(&["--url", "--debug_fail"], &[&*NETWORK, "full"], Some(config_path))?;
+
+    let run = |code: u32| {
+        let depool_addr = depool_addr.clone();
+        std::thread::spawn(move || {
+            let trace_path = format!("debug_trace_output_{}.log", code);
+            let mut cmd = Command::cargo_bin(BIN_NAME).unwrap();
+            cmd.arg("--config")
+                .arg(config_path)
+                .arg("--trace-output")
+                .arg(&trace_path)
+                .arg("call")
+                .arg("--abi")
+                .arg(DEPOOL_ABI)
+                .arg(&depool_addr)
+                .arg("error")
+                .arg(format!(r#"{{"code":{}}}"#, code))
+                .assert()
+                .code(code as i32)
+                .stdout(predicate::str::contains("Debug finished."));
+            trace_path
+        })
+    };
+    // both calls are debugged at the same time
+    let first = run(101);
+    let second = run(102);
+    let first = first.join().unwrap();
+    let second = second.join().unwrap();
+
+    let first_trace = fs::read_to_string(&first)?;
+    let second_trace = fs::read_to_string(&second)?;
+    assert!(!first_trace.is_empty());
+    assert!(!second_trace.is_empty());
+    assert_ne!(first_trace, second_trace);
+    // the default trace file is not used
+    assert!(!std::path::Path::new(&format!("call_{}_error.log", depool_addr)).exists());
+
+    fs::remove_file(first)?;
+    fs::remove_file(second)?;
+    fs::remove_file(key_path)?;
+    fs::remove_file(config_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_debug_fail_config_boc() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "debug_config_boc_test.key";
+    let config_path = "debug_config_boc_test.conf";
+    let bc_config_path = "debug_config_boc_test.boc";
+    let depool_addr = deploy_depool(key_path)?;
+    set_config(&["--url", "--debug_fail"], &[&*NETWORK, "full"], Some(config_path))?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--config")
+        .arg(config_path)
+        .arg("dump")
+        .arg("config")
+        .arg(bc_config_path)
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--config")
+        .arg(config_path)
+        .arg("call")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("error")
+        .arg(r#"{"code":101}"#)
+        .arg("--config-boc")
+        .arg(bc_config_path);
+    cmd.assert()
+        .code(101)
+        .stdout(predicate::str::contains("Debug finished."));
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("--config")
+        .arg(config_path)
+        .arg("call")
+        .arg("--abi")
+        .arg(DEPOOL_ABI)
+        .arg(&depool_addr)
+        .arg("error")
+        .arg(r#"{"code":101}"#)
+        .arg("--config-boc")
+        .arg("not_existing_config.boc");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Failed to load config contract account from file"));
+
+    fs::remove_file(format!("call_{}_error.log", depool_addr))?;
+    fs::remove_file(bc_config_path)?;
+    fs::remove_file(config_path)?;
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
 #[test]
 fn test_error() -> Result<(), Box<dyn std::error::Error>> {
     let key_path = "error_test.key";
@@ -2262,14 +3779,85 @@ fn test_run_account() -> Result<(), Box<dyn std::error::Error>> {
         .arg("participant_list_extended")
         .assert()
         .success()
-        .stdout(predicate::str::contains(r#"value0": "0","#))
-        .stdout(predicate::str::contains(r#"value1": "0","#))
-        .stdout(predicate::str::contains(r#"value2": "0","#))
-        .stdout(predicate::str::contains(r#"value3": "0","#))
-        .stdout(predicate::str::contains(r#"value4": null,"#))
-        .stdout(predicate::str::contains(r#"value5": "0","#))
-        .stdout(predicate::str::contains(r#"value6": "0""#));
+        .stdout(predicate::str::contains(r#"value0":"0","#))
+        .stdout(predicate::str::contains(r#"value1":"0","#))
+        .stdout(predicate::str::contains(r#"value2":"0","#))
+        .stdout(predicate::str::contains(r#"value3":"0","#))
+        .stdout(predicate::str::contains(r#"value4":null,"#))
+        .stdout(predicate::str::contains(r#"value5":"0","#))
+        .stdout(predicate::str::contains(r#"value6":"0""#));
+
+    Ok(())
+}
+
+#[test]
+fn test_async_call_json_output() -> Result<(), Box<dyn std::error::Error>> {
+    let config_path = "async_call_json.conf";
+    set_config(&["--url", "--async_call"], &[&*NETWORK, "true"], Some(config_path))?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    let out = cmd.arg("--config")
+        .arg(config_path)
+        .arg("-j")
+        .arg("call")
+        .arg("0:841288ed3b55d9cdafa806807f02a0ae0c169aa5edfe88a789a6482429756a94")
+        .arg("sendGrams")
+        .arg(r#"{"dest":"0:841288ed3b55d9cdafa806807f02a0ae0c169aa5edfe88a789a6482429756a94","amount":1000000000}"#)
+        .arg("--abi")
+        .arg(GIVER_ABI)
+        .output()?;
+    assert!(out.status.success());
+    let result: Value = serde_json::from_slice(&out.stdout)?;
+    let message_id = result["output"]["message_id"].as_str().unwrap();
+    assert_eq!(message_id.len(), 64);
+    assert!(message_id.chars().all(|c| c.is_ascii_hexdigit()));
+    assert!(result["output"]["message"].is_string());
+    assert!(result["output"]["shard_block_id"].is_string());
+
+    fs::remove_file(config_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_async_call_await() -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = "async_call_await.key";
+    let config_path = "async_call_await.conf";
+    let address = deploy_safe_msig(key_path)?;
+    set_config(&["--url", "--async_call"], &[&*NETWORK, "true"], Some(config_path))?;
+
+    let call = |wait: bool| -> Result<Value, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        cmd.arg("--config")
+            .arg(config_path)
+            .arg("-j")
+            .arg("call")
+            .arg("--abi")
+            .arg(SAFEMSIG_ABI)
+            .arg("--sign")
+            .arg(SAFEMSIG_SEED)
+            .arg(&address)
+            .arg("submitTransaction")
+            .arg(format!(r#"{{"dest":"{}","value":1000000000,"bounce":false,"allBalance":false,"payload":""}}"#, address));
+        if wait {
+            cmd.arg("--await");
+        }
+        let out = cmd.output()?;
+        assert!(out.status.success());
+        Ok(serde_json::from_slice(&out.stdout)?)
+    };
+
+    let result = call(false)?;
+    assert!(result["output"]["message_id"].is_string());
+    assert!(result["output"]["transId"].is_null());
+    assert!(result["transaction_id"].is_null());
 
+    let result = call(true)?;
+    assert!(result["output"]["transId"].is_string());
+    assert!(result["output"]["message_id"].is_null());
+    assert!(result["transaction_id"].is_string());
+
+    fs::remove_file(key_path)?;
+    fs::remove_file(config_path)?;
     Ok(())
 }
 
@@ -3292,6 +4880,81 @@ fn test_alternative_paths() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_abi_from_contract_conflicts() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi-from-contract")
+        .arg("getAbi")
+        .arg("--batch")
+        .arg("calls.json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi-from-contract")
+        .arg("getAbi")
+        .arg("--raw-body")
+        .arg("te6ccgEBAQEAAgAAAA==")
+        .arg(GIVER_V2_ADDR)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "sold")]
+fn test_abi_from_contract() -> Result<(), Box<dyn std::error::Error>> {
+    let contract_path = "tests/samples/AbiGetter";
+    let key_path = "tests/abi_getter.key";
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("compile")
+        .arg("solidity")
+        .arg(format!("{contract_path}.sol"))
+        .arg("-O")
+        .arg("tests/samples/");
+    cmd.assert()
+        .success();
+
+    let tvc_path = format!("{contract_path}.tvc");
+    let abi_path = format!("{contract_path}.abi.json");
+    let abi = fs::read_to_string(&abi_path)?;
+    let params = json!({ "abi": abi }).to_string();
+    let address = deploy_contract(key_path, &tvc_path, &abi_path, &params)?;
+
+    // the ABI file is not used after the deploy
+    check_compiled_files_and_delete(contract_path)?;
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("call")
+        .arg("--abi-from-contract")
+        .arg("getAbi")
+        .arg(&address)
+        .arg("setValue")
+        .arg(r#"{"newValue":5}"#)
+        .arg("--sign")
+        .arg(key_path)
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin(BIN_NAME)?;
+    cmd.arg("run")
+        .arg("--abi-from-contract")
+        .arg("getAbi")
+        .arg(&address)
+        .arg("value")
+        .arg("{}")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""value": "5""#));
+
+    fs::remove_file(key_path)?;
+    Ok(())
+}
+
 #[cfg(feature = "sold")]
 fn check_compiled_files_and_delete(path: &str) -> Result<(), Box<dyn std::error::Error>> {
     assert!(std::path::Path::new(&format!("{path}.code")).exists());